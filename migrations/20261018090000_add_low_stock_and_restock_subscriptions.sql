@@ -0,0 +1,25 @@
+-- เกณฑ์สต็อกต่ำรายสินค้า (ต่ำกว่าหรือเท่ากับค่านี้ถือว่าใกล้หมด)
+ALTER TABLE products
+ADD COLUMN low_stock_threshold INT NOT NULL DEFAULT 5 CHECK (low_stock_threshold >= 0);
+
+-- ผู้ใช้ที่ขอให้แจ้งเตือนเมื่อสินค้ากลับมามีของ
+CREATE TABLE restock_subscriptions (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id UUID NOT NULL,
+    product_id UUID NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT fk_user
+        FOREIGN KEY(user_id)
+        REFERENCES users(id)
+        ON DELETE CASCADE,
+
+    CONSTRAINT fk_product
+        FOREIGN KEY(product_id)
+        REFERENCES products(id)
+        ON DELETE CASCADE,
+
+    UNIQUE (user_id, product_id)
+);
+
+CREATE INDEX idx_restock_subscriptions_product_id ON restock_subscriptions(product_id);
@@ -0,0 +1,8 @@
+-- สิทธิ์ผู้ใช้ (customer / admin)
+ALTER TABLE users
+ADD COLUMN role VARCHAR(20) NOT NULL DEFAULT 'customer';
+
+-- Key สำหรับ Import/Export สินค้าจากระบบภายนอก (NULL ได้ แต่ห้ามซ้ำ)
+ALTER TABLE products
+ADD COLUMN sku TEXT UNIQUE,
+ADD COLUMN external_id TEXT UNIQUE;
@@ -0,0 +1,9 @@
+-- Version สำหรับ Optimistic Concurrency (ETag / If-Match) เพิ่มขึ้นทุกครั้งที่แก้ไข
+ALTER TABLE users
+ADD COLUMN version INT NOT NULL DEFAULT 1;
+
+ALTER TABLE categories
+ADD COLUMN version INT NOT NULL DEFAULT 1;
+
+ALTER TABLE products
+ADD COLUMN version INT NOT NULL DEFAULT 1;
@@ -0,0 +1,3 @@
+-- จำว่าสินค้าถูกปิดเพราะหมวดหมู่ถูกปิด (ตอน Restore หมวดหมู่จะเปิดคืนเฉพาะสินค้ากลุ่มนี้)
+ALTER TABLE products
+ADD COLUMN deactivated_via_category BOOLEAN NOT NULL DEFAULT false;
@@ -0,0 +1,9 @@
+-- ข้อมูลติดต่อ / โปรไฟล์ (user เดิมยังไม่มีอีเมล จึงเป็น NULL ได้)
+ALTER TABLE users
+ADD COLUMN email TEXT,
+ADD COLUMN email_verified_at TIMESTAMPTZ,
+ADD COLUMN display_name VARCHAR(100),
+ADD COLUMN phone VARCHAR(32);
+
+-- อีเมลห้ามซ้ำแบบไม่สนตัวพิมพ์ (A@x.com กับ a@X.com ถือว่าอันเดียวกัน)
+CREATE UNIQUE INDEX users_email_lower_key ON users (LOWER(email));
@@ -0,0 +1,31 @@
+-- สมุดที่อยู่ของ user (Order จะ copy ที่อยู่ไปเก็บเอง ไม่ได้อ้างอิงตารางนี้)
+CREATE TABLE user_addresses (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id UUID NOT NULL,
+    label VARCHAR(50),                 -- เช่น "บ้าน", "ที่ทำงาน"
+    recipient_name VARCHAR(100) NOT NULL,
+    line1 TEXT NOT NULL,
+    line2 TEXT,
+    city VARCHAR(100) NOT NULL,
+    region VARCHAR(100),               -- จังหวัด / รัฐ
+    postal_code VARCHAR(20) NOT NULL,
+    country CHAR(2) NOT NULL,          -- ISO 3166-1 alpha-2 เช่น TH, US
+    phone VARCHAR(32),
+    is_default_shipping BOOLEAN NOT NULL DEFAULT false,
+    is_default_billing BOOLEAN NOT NULL DEFAULT false,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ,
+
+    CONSTRAINT fk_user
+        FOREIGN KEY(user_id)
+        REFERENCES users(id)
+        ON DELETE CASCADE
+);
+
+CREATE INDEX idx_user_addresses_user_id ON user_addresses (user_id);
+
+-- default ของแต่ละประเภทมีได้แค่ 1 อันต่อ user
+CREATE UNIQUE INDEX user_addresses_default_shipping_key
+    ON user_addresses (user_id) WHERE is_default_shipping;
+CREATE UNIQUE INDEX user_addresses_default_billing_key
+    ON user_addresses (user_id) WHERE is_default_billing;
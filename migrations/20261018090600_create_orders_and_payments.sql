@@ -0,0 +1,55 @@
+-- คำสั่งซื้อ: ราคา/ชื่อสินค้า/ที่อยู่ ถูก copy มาเก็บ ณ ตอน Checkout
+CREATE TABLE orders (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID REFERENCES users(id) ON DELETE SET NULL, -- ลบ user แล้ว order ยังต้องอยู่ (บัญชี)
+    status VARCHAR(20) NOT NULL DEFAULT 'pending_payment', -- pending_payment / paid / payment_failed / refunded
+    currency CHAR(3) NOT NULL,
+    subtotal DECIMAL(12, 2) NOT NULL,
+    total DECIMAL(12, 2) NOT NULL,
+    shipping_address JSONB NOT NULL,
+    billing_address JSONB NOT NULL,
+    paid_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ
+);
+
+CREATE INDEX idx_orders_user_id ON orders (user_id, created_at DESC);
+
+CREATE TABLE order_items (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    order_id UUID NOT NULL REFERENCES orders(id) ON DELETE CASCADE,
+    product_id UUID REFERENCES products(id) ON DELETE SET NULL,
+    product_name TEXT NOT NULL,
+    unit_price DECIMAL(10, 2) NOT NULL,
+    quantity INT NOT NULL CHECK (quantity > 0),
+    line_total DECIMAL(12, 2) NOT NULL
+);
+
+CREATE INDEX idx_order_items_order_id ON order_items (order_id);
+
+-- ความพยายามชำระเงินแต่ละครั้ง (1 order มีได้หลายครั้ง)
+CREATE TABLE payments (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    order_id UUID NOT NULL REFERENCES orders(id) ON DELETE CASCADE,
+    provider VARCHAR(30) NOT NULL,
+    provider_payment_id TEXT NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending', -- pending / authorized / captured / failed / refund_pending / refunded
+    amount DECIMAL(12, 2) NOT NULL,
+    currency CHAR(3) NOT NULL,
+    failure_reason TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ,
+
+    UNIQUE (provider, provider_payment_id)
+);
+
+CREATE INDEX idx_payments_order_id ON payments (order_id);
+
+-- Webhook ที่ประมวลผลแล้ว (provider ส่งซ้ำได้ ต้องทำแค่ครั้งเดียว)
+CREATE TABLE payment_webhook_events (
+    provider VARCHAR(30) NOT NULL,
+    event_id TEXT NOT NULL,
+    received_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (provider, event_id)
+);
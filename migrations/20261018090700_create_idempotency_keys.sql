@@ -0,0 +1,17 @@
+-- Idempotency-Key: เก็บผลของ request แรกไว้ตอบซ้ำเมื่อ client retry ด้วย key เดิม
+CREATE TABLE idempotency_keys (
+    scope TEXT NOT NULL, -- เจ้าของ key (user id) key เดียวกันของคนละ user ไม่ชนกัน
+    idempotency_key TEXT NOT NULL,
+    method VARCHAR(10) NOT NULL,
+    path TEXT NOT NULL,
+    request_hash TEXT NOT NULL, -- SHA-256 ของ method + path + body
+    status_code SMALLINT, -- NULL = request แรกยังทำงานอยู่
+    content_type TEXT,
+    response_body BYTEA,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ NOT NULL,
+
+    PRIMARY KEY (scope, idempotency_key)
+);
+
+CREATE INDEX idx_idempotency_keys_expires_at ON idempotency_keys (expires_at);
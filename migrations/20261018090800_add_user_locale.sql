@@ -0,0 +1,3 @@
+-- ภาษาที่ผู้ใช้เลือก (NULL = ใช้ตาม Accept-Language ของ request)
+ALTER TABLE users
+ADD COLUMN locale VARCHAR(8);
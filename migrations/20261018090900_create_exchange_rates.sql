@@ -0,0 +1,8 @@
+-- อัตราแลกเปลี่ยนจากสกุลเงินหลักของร้าน (payments.currency) ที่ Admin ตั้งเอง
+-- rate = จำนวนเงินสกุล currency ต่อ 1 หน่วยของสกุลหลัก (เช่น THB -> USD = 0.0275)
+CREATE TABLE exchange_rates (
+    currency CHAR(3) PRIMARY KEY,
+    rate NUMERIC(20, 10) NOT NULL CHECK (rate > 0),
+    updated_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,42 @@
+-- ประเภทภาษีของสินค้า (สินค้าใหม่เป็น standard)
+CREATE TABLE tax_classes (
+    code VARCHAR(32) PRIMARY KEY,
+    name TEXT NOT NULL
+);
+
+INSERT INTO tax_classes (code, name) VALUES
+    ('standard', 'Standard rate'),
+    ('reduced', 'Reduced rate'),
+    ('zero', 'Zero rated / exempt');
+
+ALTER TABLE products
+    ADD COLUMN tax_class VARCHAR(32) NOT NULL DEFAULT 'standard' REFERENCES tax_classes(code);
+
+-- อัตราภาษีตามที่อยู่จัดส่ง: region NULL = ทั้งประเทศ, ถ้ามีแถวของ region ที่ตรงกันจะใช้แถวนั้นแทน
+-- class ที่ไม่มีอัตราในประเทศนั้นถือว่าไม่มีภาษี
+CREATE TABLE tax_rates (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tax_class VARCHAR(32) NOT NULL REFERENCES tax_classes(code) ON DELETE CASCADE,
+    country CHAR(2) NOT NULL,          -- ISO 3166-1 alpha-2
+    region VARCHAR(100),               -- จังหวัด / รัฐ (เทียบแบบไม่สนตัวพิมพ์)
+    name TEXT NOT NULL,                -- ชื่อที่แสดงในตะกร้า/ใบเสร็จ เช่น "VAT 7%"
+    rate NUMERIC(7, 6) NOT NULL CHECK (rate >= 0 AND rate < 1), -- 0.07 = 7%
+    inclusive BOOLEAN NOT NULL,        -- true = ราคาสินค้ารวมภาษีแล้ว
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX tax_rates_location_key
+    ON tax_rates (tax_class, country, LOWER(COALESCE(region, '')));
+
+-- VAT ไทย 7% รวมในราคาสินค้าแล้ว
+INSERT INTO tax_rates (tax_class, country, name, rate, inclusive) VALUES
+    ('standard', 'TH', 'VAT 7%', 0.07, true);
+
+-- ภาษีที่คำนวณ ณ ตอน Checkout (Order เก่าถือว่าไม่มีภาษี)
+ALTER TABLE orders
+    ADD COLUMN tax_total DECIMAL(12, 2) NOT NULL DEFAULT 0;
+
+ALTER TABLE order_items
+    ADD COLUMN tax_rate NUMERIC(7, 6) NOT NULL DEFAULT 0,
+    ADD COLUMN tax_inclusive BOOLEAN NOT NULL DEFAULT false,
+    ADD COLUMN tax_amount DECIMAL(12, 2) NOT NULL DEFAULT 0;
@@ -0,0 +1,54 @@
+-- น้ำหนัก/ขนาดต่อชิ้น สำหรับคิดค่าส่ง (NULL = ไม่ได้ระบุ คิดเป็น 0)
+ALTER TABLE products
+    ADD COLUMN weight_grams INT CHECK (weight_grams >= 0),
+    ADD COLUMN length_mm INT CHECK (length_mm >= 0),
+    ADD COLUMN width_mm INT CHECK (width_mm >= 0),
+    ADD COLUMN height_mm INT CHECK (height_mm >= 0);
+
+-- วิธีจัดส่ง: ราคาเป็นสกุลหลักของร้าน (payments.currency)
+--   flat      = base_cost เสมอ
+--   weight    = ตาม shipping_weight_tiers (หนักเกินขั้นสุดท้าย = ส่งไม่ได้)
+--   free_over = base_cost แต่ฟรีเมื่อยอดสินค้าถึง free_threshold
+CREATE TABLE shipping_methods (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    code VARCHAR(50) NOT NULL UNIQUE,  -- ใช้เลือกตอน Checkout
+    name TEXT NOT NULL,
+    carrier VARCHAR(30) NOT NULL DEFAULT 'local', -- ShippingCarrier ที่คิดราคา
+    rate_type VARCHAR(20) NOT NULL CHECK (rate_type IN ('flat', 'weight', 'free_over')),
+    base_cost DECIMAL(10, 2) NOT NULL DEFAULT 0 CHECK (base_cost >= 0),
+    free_threshold DECIMAL(12, 2) CHECK (free_threshold >= 0),
+    countries TEXT[],                  -- ISO 3166-1 alpha-2, NULL = ทุกประเทศ
+    min_days INT,                      -- ระยะเวลาจัดส่งโดยประมาณ
+    max_days INT,
+    is_active BOOLEAN NOT NULL DEFAULT true,
+    sort_order INT NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CHECK (rate_type <> 'free_over' OR free_threshold IS NOT NULL)
+);
+
+-- ขั้นน้ำหนักของ rate_type = weight: ใช้ขั้นแรกที่ up_to_grams >= น้ำหนักรวม
+CREATE TABLE shipping_weight_tiers (
+    method_id UUID NOT NULL REFERENCES shipping_methods(id) ON DELETE CASCADE,
+    up_to_grams INT NOT NULL CHECK (up_to_grams > 0),
+    cost DECIMAL(10, 2) NOT NULL CHECK (cost >= 0),
+
+    PRIMARY KEY (method_id, up_to_grams)
+);
+
+INSERT INTO shipping_methods (code, name, rate_type, base_cost, free_threshold, countries, min_days, max_days, sort_order) VALUES
+    ('standard', 'Standard delivery', 'free_over', 50, 1000, '{TH}', 3, 5, 1),
+    ('ems', 'EMS', 'weight', 0, NULL, '{TH}', 1, 2, 2),
+    ('pickup', 'Store pickup', 'flat', 0, NULL, '{TH}', 0, 1, 3);
+
+INSERT INTO shipping_weight_tiers (method_id, up_to_grams, cost)
+SELECT id, tier.up_to_grams, tier.cost
+FROM shipping_methods,
+     (VALUES (1000, 60), (3000, 90), (5000, 130), (10000, 200), (20000, 320)) AS tier(up_to_grams, cost)
+WHERE code = 'ems';
+
+-- วิธีจัดส่งและค่าส่ง ณ ตอน Checkout (total = ยอดสินค้ารวมภาษี + shipping_cost)
+ALTER TABLE orders
+    ADD COLUMN shipping_method VARCHAR(50),
+    ADD COLUMN shipping_method_name TEXT,
+    ADD COLUMN shipping_cost DECIMAL(12, 2) NOT NULL DEFAULT 0;
@@ -0,0 +1,14 @@
+-- ประวัติการหยิบสินค้าใส่ตะกร้า สำหรับสถิติ (cart_items ถูกลบตอน Checkout จึงนับย้อนหลังจากตารางนั้นไม่ได้)
+CREATE TABLE cart_additions (
+    id BIGSERIAL PRIMARY KEY,
+    cart_id UUID NOT NULL REFERENCES carts(id) ON DELETE CASCADE,
+    product_id UUID NOT NULL REFERENCES products(id) ON DELETE CASCADE,
+    quantity INT NOT NULL CHECK (quantity > 0),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_cart_additions_created_at ON cart_additions (created_at);
+
+-- ช่วงวันที่ของหน้า /admin/stats
+CREATE INDEX idx_users_created_at ON users (created_at);
+CREATE INDEX idx_orders_paid_at ON orders (paid_at) WHERE paid_at IS NOT NULL;
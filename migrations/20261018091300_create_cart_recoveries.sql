@@ -0,0 +1,31 @@
+-- carts.updated_at = เวลาที่ตะกร้ามีการเปลี่ยนแปลงล่าสุด (เดิมแตะแค่ cart_items) เติมค่าของตะกร้าเก่าก่อน
+UPDATE carts c
+SET updated_at = GREATEST(c.updated_at, i.last_activity)
+FROM (
+    SELECT cart_id, MAX(updated_at) AS last_activity
+    FROM cart_items
+    GROUP BY cart_id
+) i
+WHERE i.cart_id = c.id;
+
+CREATE INDEX idx_carts_updated_at ON carts (updated_at);
+
+-- การแจ้งเตือนตะกร้าที่ถูกทิ้งไว้ 1 แถวต่อ 1 ช่วงที่ตะกร้าไม่มีการเคลื่อนไหว
+CREATE TABLE cart_recoveries (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    cart_id UUID NOT NULL REFERENCES carts(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    item_count INT NOT NULL,
+    cart_value DECIMAL(12, 2) NOT NULL, -- มูลค่าตอนแจ้งเตือน (สกุลหลักของร้าน)
+    currency VARCHAR(3) NOT NULL,
+    cart_updated_at TIMESTAMPTZ NOT NULL, -- carts.updated_at ตอนแจ้ง (มีการเคลื่อนไหวใหม่ = แจ้งรอบใหม่ได้)
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ NOT NULL, -- หมดอายุของลิงก์ และกรอบเวลาที่นับ Order เป็นผลของการแจ้งเตือน
+    clicked_at TIMESTAMPTZ,
+    converted_at TIMESTAMPTZ,
+    order_id UUID REFERENCES orders(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_cart_recoveries_cart_id ON cart_recoveries (cart_id, created_at);
+CREATE INDEX idx_cart_recoveries_user_id ON cart_recoveries (user_id, created_at);
+CREATE INDEX idx_cart_recoveries_created_at ON cart_recoveries (created_at);
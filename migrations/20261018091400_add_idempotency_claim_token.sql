@@ -0,0 +1,3 @@
+-- เจ้าของ key ปัจจุบัน: request ที่ค้างจนถูก retry แย่ง key ไปแล้ว จะเขียนผล/ปล่อย key ทับเจ้าของใหม่ไม่ได้
+ALTER TABLE idempotency_keys
+ADD COLUMN claim_token UUID;
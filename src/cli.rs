@@ -0,0 +1,58 @@
+// คำสั่งของ binary ตัวเดียวกัน (ไม่ใส่คำสั่ง = serve)
+//   mini-shop-axum serve
+//   mini-shop-axum migrate
+//   mini-shop-axum reindex
+//   mini-shop-axum create-admin <username>   (รหัสผ่านจาก ADMIN_PASSWORD หรือพิมพ์ผ่าน stdin)
+#[derive(Debug)]
+pub enum Command {
+    Serve,
+    Migrate,
+    Reindex,
+    CreateAdmin { username: String },
+}
+
+pub const USAGE: &str = "Usage: mini-shop-axum [serve | migrate | reindex | create-admin <username>]";
+
+impl Command {
+    pub fn from_args(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let command = match args.next().as_deref() {
+            None | Some("serve") => Command::Serve,
+            Some("migrate") => Command::Migrate,
+            Some("reindex") => Command::Reindex,
+            Some("create-admin") => {
+                let username = args
+                    .next()
+                    .filter(|name| !name.trim().is_empty())
+                    .ok_or_else(|| "create-admin requires a username".to_string())?;
+                Command::CreateAdmin { username }
+            }
+            Some(other) => return Err(format!("Unknown command '{}'", other)),
+        };
+
+        match args.next() {
+            Some(extra) => Err(format!("Unexpected argument '{}'", extra)),
+            None => Ok(command),
+        }
+    }
+}
+
+// ไม่รับรหัสผ่านทาง argument เพราะจะไปโผล่ใน shell history / ps
+pub fn read_admin_password() -> Result<String, String> {
+    let password = match std::env::var("ADMIN_PASSWORD") {
+        Ok(password) => password,
+        Err(_) => {
+            eprint!("Password: ");
+            let mut line = String::new();
+            std::io::stdin()
+                .read_line(&mut line)
+                .map_err(|e| e.to_string())?;
+            line.trim_end_matches(['\r', '\n']).to_string()
+        }
+    };
+
+    // ความแข็งแรงของรหัสผ่านตรวจด้วย PasswordPolicy ตอนสร้าง user
+    if password.is_empty() {
+        return Err("Password must not be empty".into());
+    }
+    Ok(password)
+}
@@ -1,29 +1,67 @@
+pub mod prometheus;
+pub mod settings;
+pub mod telemetry;
+
+use sqlx::migrate::Migrator;
 use sqlx::postgres::PgPoolOptions;
 use sqlx::{Pool, Postgres};
-use std::env;
+use std::sync::Arc;
 use meilisearch_sdk::client::Client;
+use metrics_exporter_prometheus::PrometheusHandle;
 
 use crate::services::categories_service::CategoriesService;
 use crate::services::products_service::ProductsService;
 use crate::services::search_service::SearchService;
 use crate::services::user_service::UserService;
+use crate::services::address_service::AddressService;
+use crate::services::auth_service::AuthService;
+use crate::services::cart_recovery_service::CartRecoveryService;
 use crate::services::cart_service::CartService;
+use crate::services::currency_service::CurrencyService;
+use crate::services::health_service::HealthService;
+use crate::services::idempotency_service::IdempotencyService;
+use crate::services::order_service::OrderService;
+use crate::services::payment_service::PaymentService;
+use crate::services::rate_limiter::RateLimiter;
+use crate::services::shipping_service::ShippingService;
+use crate::services::stats_service::StatsService;
+use settings::{DatabaseSettings, Settings};
 #[derive(Clone)]
 pub struct AppState {
+    pub settings: Arc<Settings>, // โหลดครั้งเดียวตอน Startup
     pub db: Pool<Postgres>, // นี่คือ Connection Pool
     pub meilisearch: Client,
+    pub metrics: PrometheusHandle,
+    pub auth_service: AuthService,
     pub user_service: UserService,
+    pub address_service: AddressService,
     pub categories_service: CategoriesService,
     pub products_service: ProductsService,
     pub cart_service: CartService,
-    pub search_service: SearchService
+    pub cart_recovery_service: CartRecoveryService,
+    pub currency_service: CurrencyService,
+    pub order_service: OrderService,
+    pub shipping_service: ShippingService,
+    pub payment_service: PaymentService,
+    pub idempotency_service: IdempotencyService,
+    pub search_service: SearchService,
+    pub health_service: HealthService,
+    pub stats_service: StatsService,
+    pub rate_limiter: RateLimiter, // ใช้ใน middleware จำกัด IP (AuthService ถือไว้อีกตัวสำหรับบัญชี)
+}
+
+// migrations/ ถูกฝังเข้าไปใน binary ตอน compile
+pub static MIGRATOR: Migrator = sqlx::migrate!();
+
+// sqlx ถือ pg_advisory_lock ไว้ระหว่างรัน หลาย replica ที่ boot พร้อมกันจะรอคิวกัน ไม่รันซ้อน
+pub async fn run_migrations(pool: &Pool<Postgres>) -> Result<(), sqlx::migrate::MigrateError> {
+    MIGRATOR.run(pool).await
 }
 
-pub async fn init_db() -> Pool<Postgres> {
-    let database_url = env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+pub async fn init_db(settings: &DatabaseSettings) -> Pool<Postgres> {
     PgPoolOptions::new()
-        .max_connections(5)
-        .connect(&database_url)
+        .max_connections(settings.max_connections)
+        .connect(settings.url.expose())
         .await
         .expect("Failed to create pool")
 }
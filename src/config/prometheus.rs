@@ -0,0 +1,50 @@
+use metrics::{describe_counter, describe_gauge, describe_histogram};
+use metrics_exporter_prometheus::{Matcher, PrometheusBuilder, PrometheusHandle};
+
+// Bucket ของ histogram เวลา (วินาที) ตั้งแต่ 5ms ถึง 10s
+const LATENCY_BUCKETS: [f64; 12] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 7.5, 10.0,
+];
+
+// ติดตั้ง recorder ระดับ global (เรียกครั้งเดียวตอน Startup) แล้วเก็บ handle ไว้ render ที่ /metrics
+pub fn init() -> PrometheusHandle {
+    let handle = PrometheusBuilder::new()
+        .set_buckets_for_metric(
+            Matcher::Suffix("duration_seconds".to_string()),
+            &LATENCY_BUCKETS,
+        )
+        .expect("Invalid histogram buckets")
+        .install_recorder()
+        .expect("Failed to install Prometheus recorder");
+
+    describe_histogram!(
+        "http_request_duration_seconds",
+        "HTTP request latency by method, route and status"
+    );
+    describe_counter!("http_requests_total", "HTTP requests by method, route and status");
+    describe_counter!("app_errors_total", "AppError responses by kind");
+    describe_gauge!("db_pool_connections", "Database pool connections by state");
+    describe_gauge!("db_pool_max_connections", "Configured database pool size");
+    describe_histogram!(
+        "meilisearch_request_duration_seconds",
+        "Meilisearch call latency by operation"
+    );
+    describe_counter!(
+        "meilisearch_request_failures_total",
+        "Failed Meilisearch calls by operation"
+    );
+    describe_counter!("rate_limited_total", "Requests rejected by rate limit by scope");
+    describe_counter!("idempotent_replays_total", "Responses replayed from a stored Idempotency-Key");
+    describe_counter!("shop_user_registrations_total", "Successful user registrations");
+    describe_counter!("shop_logins_total", "Login attempts by outcome");
+    describe_counter!("shop_password_rehashes_total", "Password hashes upgraded on login");
+    describe_counter!("shop_carts_created_total", "Carts created");
+    describe_counter!("shop_cart_add_events_total", "Add-to-cart requests");
+    describe_counter!("shop_cart_items_added_total", "Quantity added to carts");
+    describe_counter!("shop_orders_created_total", "Orders created at checkout");
+    describe_counter!("shop_cart_recoveries_sent_total", "Abandoned cart notifications sent");
+    describe_counter!("shop_cart_recoveries_opened_total", "Abandoned cart recovery links opened");
+    describe_counter!("shop_payments_total", "Payment state changes by provider and status");
+
+    handle
+}
@@ -0,0 +1,745 @@
+use serde::Deserialize;
+use std::fmt;
+use std::path::Path;
+
+// ค่าลับ (รหัสผ่าน DB, API Key, JWT Secret) ห้ามหลุดไปใน log ผ่าน {:?}
+#[derive(Clone, Default, Deserialize)]
+#[serde(transparent)]
+pub struct Secret(String);
+
+impl Secret {
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Secret([REDACTED])")
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Profile {
+    #[default]
+    Dev,
+    Test,
+    Prod,
+}
+
+impl Profile {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Profile::Dev => "dev",
+            Profile::Test => "test",
+            Profile::Prod => "prod",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "dev" | "development" => Some(Profile::Dev),
+            "test" => Some(Profile::Test),
+            "prod" | "production" => Some(Profile::Prod),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ServerSettings {
+    pub host: String,
+    pub port: u16,
+    pub shutdown_timeout_secs: u64, // เวลารอ background job หยุดหลังได้ SIGTERM
+}
+
+impl Default for ServerSettings {
+    fn default() -> Self {
+        Self {
+            host: "127.0.0.1".into(),
+            port: 3000,
+            shutdown_timeout_secs: 30,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct DatabaseSettings {
+    pub url: Secret, // มี username/password อยู่ใน URL
+    pub max_connections: u32,
+    pub run_migrations: bool, // รัน migration ตอน `serve` (ปิดไว้ถ้า deploy รัน `migrate` แยก)
+}
+
+impl Default for DatabaseSettings {
+    fn default() -> Self {
+        Self {
+            url: Secret::default(),
+            max_connections: 5,
+            run_migrations: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct MeilisearchSettings {
+    pub url: String,
+    pub api_key: Secret,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct JwtSettings {
+    pub secret: Secret,
+    pub expiration_hours: i64,
+}
+
+impl Default for JwtSettings {
+    fn default() -> Self {
+        Self {
+            secret: Secret::default(),
+            expiration_hours: 24,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct EmailSettings {
+    pub verification_ttl_hours: i64,
+    pub verification_url: String, // ลิงก์ในอีเมลจะเป็น {verification_url}?token=...
+}
+
+impl Default for EmailSettings {
+    fn default() -> Self {
+        Self {
+            verification_ttl_hours: 48,
+            verification_url: "http://127.0.0.1:3000/auth/verify-email".into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct PaymentSettings {
+    pub currency: String,          // ISO 4217 เช่น THB
+    pub default_provider: String,  // ตอนนี้มีแค่ "mock" (ทำงาน offline ทั้งหมด)
+    pub mock_webhook_secret: Secret,
+    pub webhook_tolerance_secs: i64, // webhook ที่เก่ากว่านี้ถือว่าเป็นการ replay
+}
+
+impl Default for PaymentSettings {
+    fn default() -> Self {
+        Self {
+            currency: "THB".into(),
+            default_provider: "mock".into(),
+            mock_webhook_secret: Secret::default(),
+            webhook_tolerance_secs: 300,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct TaxSettings {
+    pub default_country: String, // ใช้คิดภาษีในตะกร้าเมื่อ user ยังไม่มีที่อยู่จัดส่ง
+}
+
+impl Default for TaxSettings {
+    fn default() -> Self {
+        Self {
+            default_country: "TH".into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct IdempotencySettings {
+    pub ttl_hours: i64,                // เก็บผลไว้ตอบซ้ำนานเท่านี้
+    pub processing_timeout_secs: i64, // request แรกค้างนานเกินนี้ (เช่น process ตาย) ให้ request ใหม่ทำแทนได้
+    pub max_key_length: usize,
+    pub max_body_bytes: usize,
+}
+
+impl Default for IdempotencySettings {
+    fn default() -> Self {
+        Self {
+            ttl_hours: 24,
+            processing_timeout_secs: 60,
+            max_key_length: 255,
+            max_body_bytes: 1024 * 1024,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct JobSettings {
+    pub low_stock_interval_secs: u64,
+    pub idempotency_cleanup_interval_secs: u64,
+    pub cart_recovery_interval_secs: u64,
+}
+
+impl Default for JobSettings {
+    fn default() -> Self {
+        Self {
+            low_stock_interval_secs: 3600,
+            idempotency_cleanup_interval_secs: 3600,
+            cart_recovery_interval_secs: 900,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct CartRecoverySettings {
+    pub enabled: bool,
+    pub idle_hours: i64,     // ตะกร้าที่มีของแต่ไม่เคลื่อนไหวนานเท่านี้ถือว่าถูกทิ้ง
+    pub max_idle_hours: i64, // ทิ้งไว้นานเกินนี้ไม่แจ้งแล้ว (กันแจ้งตะกร้าเก่ามากตอนเปิดใช้ครั้งแรก)
+    pub link_ttl_hours: i64, // อายุลิงก์ และกรอบเวลาที่นับ Order เป็นผลของการแจ้งเตือน
+    pub batch_size: i64,     // จำนวนตะกร้าสูงสุดต่อรอบของ Job
+    pub recovery_url: String, // ลิงก์ในข้อความจะเป็น {recovery_url}?token=...
+}
+
+impl Default for CartRecoverySettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            idle_hours: 24,
+            max_idle_hours: 24 * 7,
+            link_ttl_hours: 24 * 7,
+            batch_size: 100,
+            recovery_url: "http://127.0.0.1:3000/cart/recover".into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct StatsSettings {
+    pub cache_ttl_secs: u64,     // ผลของ /admin/stats เก็บไว้ตอบซ้ำนานเท่านี้
+    pub default_range_days: i64, // ไม่ส่ง from มา = ย้อนหลังกี่วัน
+    pub max_range_days: i64,
+}
+
+impl Default for StatsSettings {
+    fn default() -> Self {
+        Self {
+            cache_ttl_secs: 60,
+            default_range_days: 30,
+            max_range_days: 366,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct HealthSettings {
+    pub check_timeout_ms: u64,
+    pub require_meilisearch: bool, // false = ค้นหาล่มได้ แต่ยังรับ traffic อื่นต่อ
+}
+
+impl Default for HealthSettings {
+    fn default() -> Self {
+        Self {
+            check_timeout_ms: 2000,
+            require_meilisearch: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum PasswordAlgorithm {
+    #[default]
+    Argon2id,
+    Bcrypt,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct PasswordSettings {
+    pub algorithm: PasswordAlgorithm, // hash ที่ไม่ตรงกับค่านี้จะถูก hash ใหม่ตอน login สำเร็จ
+    pub argon2_memory_kib: u32,
+    pub argon2_iterations: u32,
+    pub argon2_parallelism: u32,
+    pub bcrypt_cost: u32,
+    pub min_length: usize,
+    pub min_character_classes: usize, // ตัวเล็ก / ตัวใหญ่ / ตัวเลข / สัญลักษณ์
+}
+
+impl Default for PasswordSettings {
+    fn default() -> Self {
+        // ค่า argon2id ตามคำแนะนำขั้นต่ำของ OWASP (19 MiB, 2 รอบ, 1 lane)
+        Self {
+            algorithm: PasswordAlgorithm::Argon2id,
+            argon2_memory_kib: 19456,
+            argon2_iterations: 2,
+            argon2_parallelism: 1,
+            bcrypt_cost: 12,
+            min_length: 10,
+            min_character_classes: 2,
+        }
+    }
+}
+
+// Token bucket: burst = จำนวนครั้งที่ยิงติดกันได้, per_minute = อัตราเติม
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct RateLimitSettings {
+    pub enabled: bool,
+    pub ip_burst: u32,
+    pub ip_per_minute: u32,
+    pub account_burst: u32,
+    pub account_per_minute: u32,
+    pub lockout_threshold: u32,   // ผิดติดกันกี่ครั้งถึงเริ่มล็อก
+    pub lockout_base_secs: u64,   // ล็อกครั้งแรกกี่วินาที (ครั้งต่อไปเพิ่มเท่าตัว)
+    pub lockout_max_secs: u64,
+    pub failure_window_secs: u64, // ไม่ผิดเพิ่มนานเท่านี้ ให้เริ่มนับใหม่
+    pub trust_forwarded_for: bool, // เปิดเมื่ออยู่หลัง reverse proxy ที่เชื่อถือได้เท่านั้น
+}
+
+impl Default for RateLimitSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            ip_burst: 20,
+            ip_per_minute: 10,
+            account_burst: 5,
+            account_per_minute: 5,
+            lockout_threshold: 5,
+            lockout_base_secs: 30,
+            lockout_max_secs: 900,
+            failure_window_secs: 900,
+            trust_forwarded_for: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    #[default]
+    Json,
+    Pretty,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct TelemetrySettings {
+    pub log_format: LogFormat,
+    pub env_filter: String, // RUST_LOG จะทับค่านี้ถ้ามีการตั้งไว้
+    pub service_name: String,
+    pub otlp_endpoint: Option<String>, // เช่น http://127.0.0.1:4317 (ต้อง build ด้วย --features otlp)
+}
+
+impl Default for TelemetrySettings {
+    fn default() -> Self {
+        Self {
+            log_format: LogFormat::Json,
+            env_filter: "info,sqlx=warn".into(),
+            service_name: "mini-shop-axum".into(),
+            otlp_endpoint: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub profile: Profile,
+    pub server: ServerSettings,
+    pub database: DatabaseSettings,
+    pub meilisearch: MeilisearchSettings,
+    pub jwt: JwtSettings,
+    pub email: EmailSettings,
+    pub payments: PaymentSettings,
+    pub tax: TaxSettings,
+    pub idempotency: IdempotencySettings,
+    pub jobs: JobSettings,
+    pub cart_recovery: CartRecoverySettings,
+    pub health: HealthSettings,
+    pub stats: StatsSettings,
+    pub rate_limit: RateLimitSettings,
+    pub password: PasswordSettings,
+    pub telemetry: TelemetrySettings,
+}
+
+// รวม error ทั้งหมดไว้แสดงทีเดียวตอน Startup
+#[derive(Debug)]
+pub struct SettingsError(pub Vec<String>);
+
+impl fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Invalid configuration:")?;
+        for error in &self.0 {
+            writeln!(f, "  - {}", error)?;
+        }
+        Ok(())
+    }
+}
+
+// ชื่อ Env เดิมที่ใช้กันอยู่แล้ว -> key ใน Settings
+const LEGACY_ENV_KEYS: [(&str, &str); 5] = [
+    ("DATABASE_URL", "database.url"),
+    ("MEILISEARCH_URL", "meilisearch.url"),
+    ("MEILISEARCH_API_KEY", "meilisearch.api_key"),
+    ("JWT_SECRET", "jwt.secret"),
+    ("PAYMENTS_MOCK_WEBHOOK_SECRET", "payments.mock_webhook_secret"),
+];
+
+// Env แบบทั่วไป: APP__SERVER__PORT=8080 -> server.port
+const ENV_PREFIX: &str = "APP__";
+
+impl Settings {
+    // ลำดับการโหลด (ตัวหลังทับตัวก่อน):
+    // config/default.toml -> config/{profile}.toml -> Env
+    // เลือก profile ด้วย APP_PROFILE (dev/test/prod) และเปลี่ยนโฟลเดอร์ได้ด้วย APP_CONFIG_DIR
+    pub fn load() -> Result<Self, SettingsError> {
+        let profile = match std::env::var("APP_PROFILE") {
+            Ok(value) => Profile::parse(&value).ok_or_else(|| {
+                SettingsError(vec![format!(
+                    "APP_PROFILE '{}' is invalid (expected dev, test or prod)",
+                    value
+                )])
+            })?,
+            Err(_) => Profile::default(),
+        };
+        let config_dir = std::env::var("APP_CONFIG_DIR").unwrap_or_else(|_| "config".into());
+
+        let mut merged = toml::Table::new();
+        for name in ["default", profile.as_str()] {
+            let path = Path::new(&config_dir).join(format!("{}.toml", name));
+            if let Some(table) = read_table(&path)? {
+                merge_tables(&mut merged, table);
+            }
+        }
+
+        for (env_key, key) in LEGACY_ENV_KEYS {
+            if let Ok(value) = std::env::var(env_key) {
+                set_key(&mut merged, key, toml::Value::String(value));
+            }
+        }
+        for (env_key, value) in std::env::vars() {
+            if let Some(path) = env_key.strip_prefix(ENV_PREFIX) {
+                let key = path.to_lowercase().replace("__", ".");
+                let value = parse_env_value(get_key(&merged, &key), &value);
+                set_key(&mut merged, &key, value);
+            }
+        }
+        merged.insert("profile".into(), toml::Value::String(profile.as_str().into()));
+
+        let settings: Settings = toml::Value::Table(merged)
+            .try_into()
+            .map_err(|e: toml::de::Error| SettingsError(vec![e.message().to_string()]))?;
+
+        settings.validate()?;
+        Ok(settings)
+    }
+
+    pub fn validate(&self) -> Result<(), SettingsError> {
+        let mut errors = Vec::new();
+
+        if self.server.host.trim().is_empty() {
+            errors.push("server.host must not be empty".to_string());
+        }
+        if self.server.port == 0 {
+            errors.push("server.port must be greater than 0".to_string());
+        }
+        if self.server.shutdown_timeout_secs == 0 {
+            errors.push("server.shutdown_timeout_secs must be greater than 0".to_string());
+        }
+
+        let db_url = self.database.url.expose();
+        if db_url.is_empty() {
+            errors.push("database.url is required (set DATABASE_URL)".to_string());
+        } else if !db_url.starts_with("postgres://") && !db_url.starts_with("postgresql://") {
+            errors.push("database.url must start with postgres:// or postgresql://".to_string());
+        }
+        if self.database.max_connections == 0 {
+            errors.push("database.max_connections must be at least 1".to_string());
+        }
+
+        if self.meilisearch.url.is_empty() {
+            errors.push("meilisearch.url is required (set MEILISEARCH_URL)".to_string());
+        } else if !self.meilisearch.url.starts_with("http://")
+            && !self.meilisearch.url.starts_with("https://")
+        {
+            errors.push("meilisearch.url must start with http:// or https://".to_string());
+        }
+        if self.meilisearch.api_key.expose().is_empty() {
+            errors.push("meilisearch.api_key is required (set MEILISEARCH_API_KEY)".to_string());
+        }
+
+        let jwt_secret = self.jwt.secret.expose();
+        if jwt_secret.is_empty() {
+            errors.push("jwt.secret is required (set JWT_SECRET)".to_string());
+        } else if self.profile == Profile::Prod && jwt_secret.len() < 32 {
+            errors.push("jwt.secret must be at least 32 characters in prod".to_string());
+        }
+        if self.jwt.expiration_hours <= 0 {
+            errors.push("jwt.expiration_hours must be greater than 0".to_string());
+        }
+
+        if self.email.verification_ttl_hours <= 0 {
+            errors.push("email.verification_ttl_hours must be greater than 0".to_string());
+        }
+        if !self.email.verification_url.starts_with("http://")
+            && !self.email.verification_url.starts_with("https://")
+        {
+            errors.push("email.verification_url must start with http:// or https://".to_string());
+        }
+
+        let payments = &self.payments;
+        if payments.currency.len() != 3 || !payments.currency.chars().all(|c| c.is_ascii_uppercase()) {
+            errors.push("payments.currency must be a 3-letter ISO code (e.g. THB)".to_string());
+        }
+        if payments.default_provider != "mock" {
+            errors.push(format!(
+                "payments.default_provider '{}' is not supported (available: mock)",
+                payments.default_provider
+            ));
+        } else if payments.mock_webhook_secret.expose().is_empty() {
+            errors.push("payments.mock_webhook_secret is required for the mock provider".to_string());
+        }
+        if payments.webhook_tolerance_secs <= 0 {
+            errors.push("payments.webhook_tolerance_secs must be greater than 0".to_string());
+        }
+
+        let country = &self.tax.default_country;
+        if country.len() != 2 || !country.chars().all(|c| c.is_ascii_uppercase()) {
+            errors.push("tax.default_country must be a 2-letter ISO code (e.g. TH)".to_string());
+        }
+
+        let idempotency = &self.idempotency;
+        if idempotency.ttl_hours <= 0 || idempotency.processing_timeout_secs <= 0 {
+            errors.push(
+                "idempotency.ttl_hours and idempotency.processing_timeout_secs must be greater than 0"
+                    .to_string(),
+            );
+        }
+        if idempotency.max_key_length == 0 || idempotency.max_body_bytes == 0 {
+            errors.push(
+                "idempotency.max_key_length and idempotency.max_body_bytes must be greater than 0"
+                    .to_string(),
+            );
+        }
+
+        if self.jobs.low_stock_interval_secs == 0 {
+            errors.push("jobs.low_stock_interval_secs must be greater than 0".to_string());
+        }
+        if self.jobs.idempotency_cleanup_interval_secs == 0 {
+            errors.push("jobs.idempotency_cleanup_interval_secs must be greater than 0".to_string());
+        }
+        if self.jobs.cart_recovery_interval_secs == 0 {
+            errors.push("jobs.cart_recovery_interval_secs must be greater than 0".to_string());
+        }
+
+        if self.health.check_timeout_ms == 0 {
+            errors.push("health.check_timeout_ms must be greater than 0".to_string());
+        }
+
+        let recovery = &self.cart_recovery;
+        if recovery.idle_hours <= 0 || recovery.max_idle_hours <= recovery.idle_hours {
+            errors.push(
+                "cart_recovery.idle_hours must be greater than 0 and less than cart_recovery.max_idle_hours"
+                    .to_string(),
+            );
+        }
+        if recovery.link_ttl_hours <= 0 || recovery.batch_size <= 0 {
+            errors.push(
+                "cart_recovery.link_ttl_hours and cart_recovery.batch_size must be greater than 0"
+                    .to_string(),
+            );
+        }
+        if !recovery.recovery_url.starts_with("http://")
+            && !recovery.recovery_url.starts_with("https://")
+        {
+            errors.push("cart_recovery.recovery_url must start with http:// or https://".to_string());
+        }
+
+        let stats = &self.stats;
+        if stats.default_range_days <= 0 || stats.max_range_days < stats.default_range_days {
+            errors.push(
+                "stats.default_range_days must be greater than 0 and not exceed stats.max_range_days"
+                    .to_string(),
+            );
+        }
+
+        let rate_limit = &self.rate_limit;
+        if rate_limit.ip_burst == 0 || rate_limit.ip_per_minute == 0 {
+            errors.push("rate_limit.ip_burst and rate_limit.ip_per_minute must be greater than 0".to_string());
+        }
+        if rate_limit.account_burst == 0 || rate_limit.account_per_minute == 0 {
+            errors.push(
+                "rate_limit.account_burst and rate_limit.account_per_minute must be greater than 0".to_string(),
+            );
+        }
+        if rate_limit.lockout_threshold == 0 {
+            errors.push("rate_limit.lockout_threshold must be greater than 0".to_string());
+        }
+        if rate_limit.lockout_base_secs == 0 || rate_limit.lockout_base_secs > rate_limit.lockout_max_secs {
+            errors.push("rate_limit.lockout_base_secs must be between 1 and lockout_max_secs".to_string());
+        }
+
+        let password = &self.password;
+        if password.argon2_iterations == 0 || password.argon2_parallelism == 0 {
+            errors.push("password.argon2_iterations and password.argon2_parallelism must be greater than 0".to_string());
+        }
+        if password.argon2_memory_kib < 8 * password.argon2_parallelism {
+            errors.push("password.argon2_memory_kib must be at least 8 * argon2_parallelism".to_string());
+        }
+        if !(4..=31).contains(&password.bcrypt_cost) {
+            errors.push("password.bcrypt_cost must be between 4 and 31".to_string());
+        }
+        if password.min_length < 8 {
+            errors.push("password.min_length must be at least 8".to_string());
+        }
+        if password.min_character_classes > 4 {
+            errors.push("password.min_character_classes must be between 0 and 4".to_string());
+        }
+        if self.profile == Profile::Prod
+            && password.algorithm == PasswordAlgorithm::Bcrypt
+            && password.bcrypt_cost < 10
+        {
+            errors.push("password.bcrypt_cost must be at least 10 in prod".to_string());
+        }
+
+        if let Some(endpoint) = &self.telemetry.otlp_endpoint
+            && !endpoint.starts_with("http://")
+            && !endpoint.starts_with("https://")
+        {
+            errors.push("telemetry.otlp_endpoint must start with http:// or https://".to_string());
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(SettingsError(errors))
+        }
+    }
+
+    pub fn bind_address(&self) -> String {
+        format!("{}:{}", self.server.host, self.server.port)
+    }
+}
+
+// ไฟล์ไม่มีไม่เป็นไร (ใช้ค่า default / env แทน) แต่ถ้ามีแล้วอ่านไม่ได้ต้อง error
+fn read_table(path: &Path) -> Result<Option<toml::Table>, SettingsError> {
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| SettingsError(vec![format!("{}: {}", path.display(), e)]))?;
+    let table = content
+        .parse::<toml::Table>()
+        .map_err(|e| SettingsError(vec![format!("{}: {}", path.display(), e.message())]))?;
+
+    Ok(Some(table))
+}
+
+fn merge_tables(base: &mut toml::Table, overlay: toml::Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base_table)), toml::Value::Table(overlay_table)) => {
+                merge_tables(base_table, overlay_table);
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+// key แบบ "section.field"
+fn set_key(table: &mut toml::Table, key: &str, value: toml::Value) {
+    match key.split_once('.') {
+        Some((section, rest)) => {
+            let entry = table
+                .entry(section.to_string())
+                .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+            if !entry.is_table() {
+                *entry = toml::Value::Table(toml::Table::new());
+            }
+            if let toml::Value::Table(inner) = entry {
+                set_key(inner, rest, value);
+            }
+        }
+        None => {
+            table.insert(key.to_string(), value);
+        }
+    }
+}
+
+fn get_key<'a>(table: &'a toml::Table, key: &str) -> Option<&'a toml::Value> {
+    match key.split_once('.') {
+        Some((section, rest)) => get_key(table.get(section)?.as_table()?, rest),
+        None => table.get(key),
+    }
+}
+
+// Env เป็น string เสมอ แปลงเป็นตัวเลข/boolean ตามชนิดของค่าเดิมใน config
+// (ค่าลับหรือ string ที่หน้าตาเป็นตัวเลข เช่น 123456 จึงยังเป็น string) แปลงไม่ได้ก็ส่ง string ให้ serde แจ้ง error เอง
+// ถ้าไม่มีค่าเดิม (ไม่มี default.toml หรือชี้ APP_CONFIG_DIR ไปที่อื่น) เดาจากหน้าตา: int -> float -> bool -> string
+fn parse_env_value(current: Option<&toml::Value>, value: &str) -> toml::Value {
+    let typed = match current {
+        Some(toml::Value::Integer(_)) => value.parse().ok().map(toml::Value::Integer),
+        Some(toml::Value::Float(_)) => value.parse().ok().map(toml::Value::Float),
+        Some(toml::Value::Boolean(_)) => value.parse().ok().map(toml::Value::Boolean),
+        Some(_) => None,
+        None => value
+            .parse()
+            .map(toml::Value::Integer)
+            .or_else(|_| value.parse().map(toml::Value::Float))
+            .or_else(|_| value.parse().map(toml::Value::Boolean))
+            .ok(),
+    };
+    typed.unwrap_or_else(|| toml::Value::String(value.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn env_values_follow_the_configured_type() {
+        let port = toml::Value::Integer(3000);
+        let secret = toml::Value::String("changeme".into());
+        assert_eq!(parse_env_value(Some(&port), "8080"), toml::Value::Integer(8080));
+        assert_eq!(
+            parse_env_value(Some(&secret), "123456"),
+            toml::Value::String("123456".into())
+        );
+        assert_eq!(
+            parse_env_value(Some(&port), "abc"),
+            toml::Value::String("abc".into())
+        );
+    }
+
+    #[test]
+    fn env_only_values_are_inferred() {
+        assert_eq!(parse_env_value(None, "8080"), toml::Value::Integer(8080));
+        assert_eq!(parse_env_value(None, "0.5"), toml::Value::Float(0.5));
+        assert_eq!(parse_env_value(None, "true"), toml::Value::Boolean(true));
+        assert_eq!(
+            parse_env_value(None, "0.0.0.0"),
+            toml::Value::String("0.0.0.0".into())
+        );
+    }
+
+    #[test]
+    fn env_only_override_deserializes_into_settings() {
+        let mut merged = toml::Table::new();
+        set_key(&mut merged, "server.port", parse_env_value(None, "8080"));
+        let port = get_key(&merged, "server.port");
+        assert_eq!(port, Some(&toml::Value::Integer(8080)));
+
+        let server: ServerSettings = merged["server"].clone().try_into().unwrap();
+        assert_eq!(server.port, 8080);
+    }
+}
@@ -0,0 +1,94 @@
+use tracing_subscriber::{EnvFilter, Layer, layer::SubscriberExt, util::SubscriberInitExt};
+
+use crate::config::settings::{LogFormat, TelemetrySettings};
+
+// ต้องเก็บไว้จนปิดโปรแกรม เพื่อ flush span ที่ค้างอยู่ไปยัง OTLP collector
+pub struct TelemetryGuard {
+    #[cfg(feature = "otlp")]
+    otlp_enabled: bool,
+}
+
+impl Drop for TelemetryGuard {
+    fn drop(&mut self) {
+        #[cfg(feature = "otlp")]
+        if self.otlp_enabled {
+            opentelemetry::global::shutdown_tracer_provider();
+        }
+    }
+}
+
+// ตั้งค่า tracing ทั้งระบบ (เรียกครั้งเดียวตอน Startup)
+pub fn init(settings: &TelemetrySettings) -> TelemetryGuard {
+    let filter = EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| EnvFilter::new(&settings.env_filter));
+
+    let fmt_layer = match settings.log_format {
+        LogFormat::Json => tracing_subscriber::fmt::layer()
+            .json()
+            .with_current_span(true)
+            .with_span_list(true)
+            .boxed(),
+        LogFormat::Pretty => tracing_subscriber::fmt::layer().boxed(),
+    };
+
+    let registry = tracing_subscriber::registry().with(filter).with(fmt_layer);
+
+    #[cfg(feature = "otlp")]
+    {
+        let otlp_layer = match settings.otlp_endpoint.as_deref() {
+            Some(endpoint) => match otlp::layer(endpoint, &settings.service_name) {
+                Ok(layer) => Some(layer),
+                Err(e) => {
+                    eprintln!("Failed to start OTLP exporter: {}", e);
+                    None
+                }
+            },
+            None => None,
+        };
+        let otlp_enabled = otlp_layer.is_some();
+
+        registry.with(otlp_layer).init();
+        TelemetryGuard { otlp_enabled }
+    }
+
+    #[cfg(not(feature = "otlp"))]
+    {
+        registry.init();
+        if settings.otlp_endpoint.is_some() {
+            tracing::warn!("telemetry.otlp_endpoint is set but the binary was built without the `otlp` feature");
+        }
+        TelemetryGuard {}
+    }
+}
+
+#[cfg(feature = "otlp")]
+mod otlp {
+    use opentelemetry::{KeyValue, trace::TracerProvider as _};
+    use opentelemetry_otlp::WithExportConfig;
+    use opentelemetry_sdk::{Resource, runtime, trace};
+    use tracing::Subscriber;
+    use tracing_subscriber::{Layer, registry::LookupSpan};
+
+    pub fn layer<S>(endpoint: &str, service_name: &str) -> Result<impl Layer<S>, String>
+    where
+        S: Subscriber + for<'span> LookupSpan<'span>,
+    {
+        let provider = opentelemetry_otlp::new_pipeline()
+            .tracing()
+            .with_exporter(
+                opentelemetry_otlp::new_exporter()
+                    .tonic()
+                    .with_endpoint(endpoint),
+            )
+            .with_trace_config(trace::Config::default().with_resource(Resource::new(vec![
+                KeyValue::new("service.name", service_name.to_string()),
+            ])))
+            .install_batch(runtime::Tokio)
+            .map_err(|e| e.to_string())?;
+
+        let tracer = provider.tracer("miniShopAxum");
+        opentelemetry::global::set_tracer_provider(provider);
+
+        Ok(tracing_opentelemetry::layer().with_tracer(tracer))
+    }
+}
@@ -1,2 +1,21 @@
-pub const WRONG_PASSWORD_MSG: &str = "Invalid username or password";
-pub const USER_EXISTS_MSG: &str = "Username already exists";
\ No newline at end of file
+pub const WRONG_PASSWORD_MSG: &str = "error.auth.wrong_password";
+pub const USER_EXISTS_MSG: &str = "error.user.exists";
+pub const EMAIL_EXISTS_MSG: &str = "error.user.email_exists";
+pub const DEFAULT_LOW_STOCK_THRESHOLD: i32 = 5;
+pub const DEFAULT_TAX_CLASS: &str = "standard";
+pub const ROLE_ADMIN: &str = "admin";
+
+// สถานะ Order
+pub const ORDER_PENDING_PAYMENT: &str = "pending_payment";
+pub const ORDER_PAID: &str = "paid";
+pub const ORDER_PAYMENT_FAILED: &str = "payment_failed";
+pub const ORDER_REFUNDED: &str = "refunded";
+
+// สถานะ Payment
+pub const PAYMENT_PENDING: &str = "pending";
+pub const PAYMENT_AUTHORIZED: &str = "authorized";
+pub const PAYMENT_CAPTURED: &str = "captured";
+pub const PAYMENT_FAILED: &str = "failed";
+pub const PAYMENT_REFUND_PENDING: &str = "refund_pending";
+pub const PAYMENT_REFUNDED: &str = "refunded";
+pub const PAYMENT_CANCELED: &str = "canceled"; // ถูกแทนด้วยรายการใหม่ตอนลองจ่ายซ้ำ
@@ -0,0 +1,164 @@
+use crate::config::AppState;
+use crate::models::dto::{AddressRequest, AddressResponse, UpdateAddressRequest};
+use crate::models::error::AppError;
+use crate::models::response::ApiResponse;
+use crate::openapi::{ErrorResponses, MessageResponse};
+use crate::utils::jwt::Claims;
+use crate::extractors::{Json, Path};
+use axum::{
+    Extension,
+    extract::State,
+    response::IntoResponse,
+};
+use uuid::Uuid;
+
+// GET /users/me/addresses
+#[utoipa::path(
+    get,
+    path = "/users/me/addresses",
+    tag = "addresses",
+    summary = "List saved addresses",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Success", body = ApiResponse<Vec<AddressResponse>>),
+        ErrorResponses,
+    )
+)]
+pub async fn list_addresses_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = claims.get_user_id()?;
+    let addresses = state.address_service.list_addresses(user_id).await?;
+
+    Ok(ApiResponse::success(
+        addresses,
+        "1000",
+        "address.list",
+    ))
+}
+
+// POST /users/me/addresses
+#[utoipa::path(
+    post,
+    path = "/users/me/addresses",
+    tag = "addresses",
+    summary = "Add an address",
+    request_body = AddressRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Success", body = ApiResponse<AddressResponse>),
+        ErrorResponses,
+    )
+)]
+pub async fn create_address_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<AddressRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = claims.get_user_id()?;
+    let address = state
+        .address_service
+        .create_address(user_id, payload)
+        .await?;
+
+    Ok(ApiResponse::success(
+        address,
+        "1000",
+        "address.create",
+    ))
+}
+
+// GET /users/me/addresses/:id
+#[utoipa::path(
+    get,
+    path = "/users/me/addresses/{id}",
+    tag = "addresses",
+    summary = "Get an address",
+    params(
+        ("id" = Uuid, Path, description = "Address ID"),
+    ),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Success", body = ApiResponse<AddressResponse>),
+        ErrorResponses,
+    )
+)]
+pub async fn get_address_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = claims.get_user_id()?;
+    let address = state.address_service.get_address(user_id, id).await?;
+
+    Ok(ApiResponse::success(
+        address,
+        "1000",
+        "address.get",
+    ))
+}
+
+// PATCH /users/me/addresses/:id
+#[utoipa::path(
+    patch,
+    path = "/users/me/addresses/{id}",
+    tag = "addresses",
+    summary = "Update an address",
+    params(
+        ("id" = Uuid, Path, description = "Address ID"),
+    ),
+    request_body = UpdateAddressRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Success", body = ApiResponse<AddressResponse>),
+        ErrorResponses,
+    )
+)]
+pub async fn update_address_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<UpdateAddressRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = claims.get_user_id()?;
+    let address = state
+        .address_service
+        .update_address(user_id, id, payload)
+        .await?;
+
+    Ok(ApiResponse::success(
+        address,
+        "1000",
+        "address.update",
+    ))
+}
+
+// DELETE /users/me/addresses/:id
+#[utoipa::path(
+    delete,
+    path = "/users/me/addresses/{id}",
+    tag = "addresses",
+    summary = "Delete an address",
+    params(
+        ("id" = Uuid, Path, description = "Address ID"),
+    ),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Success", body = MessageResponse),
+        ErrorResponses,
+    )
+)]
+pub async fn delete_address_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = claims.get_user_id()?;
+    state.address_service.delete_address(user_id, id).await?;
+
+    Ok(ApiResponse::<()>::success_no_data(
+        "1000",
+        "address.delete",
+    ))
+}
@@ -1,30 +1,94 @@
-use axum::{extract::State, Json, response::IntoResponse};
+use crate::extractors::{Json, Query};
+use axum::{extract::State, response::IntoResponse};
 use crate::config::AppState;
-use crate::models::dto::{RegisterRequest, LoginRequest};
-use crate::services::auth_service::AuthService;
+use crate::models::dto::{LoginRequest, LoginResponse, RegisterRequest, UserResponse, VerifyEmailRequest};
 use crate::models::error::AppError;
 use crate::models::response::ApiResponse;
+use crate::openapi::{ErrorResponses, MessageResponse};
 
+#[utoipa::path(
+    post,
+    path = "/auth/register",
+    tag = "auth",
+    summary = "Register a new account",
+    request_body = RegisterRequest,
+    responses(
+        (status = 200, description = "Success", body = MessageResponse),
+        ErrorResponses,
+    )
+)]
 pub async fn register_handler(
     State(state): State<AppState>,
     Json(payload): Json<RegisterRequest>,
 ) -> Result<impl IntoResponse, AppError> {
-    let service = AuthService::new(state.db);
-    service.register(payload).await?;
-    
-    let response = ApiResponse::<()>::success_no_data("1000", "Register successfully.");
+    let user_id = state.auth_service.register(payload).await?;
+
+    // สมัครสำเร็จแล้ว ส่งอีเมลไม่ได้ก็ไม่ต้อง fail (ขอส่งใหม่ได้ที่ /users/me/email/verification)
+    if let Err(e) = state.user_service.send_email_verification(user_id).await {
+        tracing::warn!(error = %e, "failed to send verification email");
+    }
+
+    let response = ApiResponse::<()>::success_no_data("1000", "auth.register");
     Ok(response)
 }
 
+#[utoipa::path(
+    post,
+    path = "/auth/login",
+    tag = "auth",
+    summary = "Log in with username or email",
+    request_body = LoginRequest,
+    responses(
+        (status = 200, description = "Success", body = ApiResponse<LoginResponse>),
+        ErrorResponses,
+    )
+)]
 pub async fn login_handler(
     State(state): State<AppState>,
     Json(payload): Json<LoginRequest>,
 ) -> Result<impl IntoResponse, AppError> {
-    let service = AuthService::new(state.db);
+    let login_data = state.auth_service.login(payload).await?;
     
-    let login_data = service.login(payload).await?; 
-    
-    let response = ApiResponse::success(login_data, "1000", "Login successfully.");
+    let response = ApiResponse::success(login_data, "1000", "auth.login");
     
     Ok(response)
-}
\ No newline at end of file
+}
+// GET /auth/verify-email?token=... (ลิงก์ในอีเมล)
+#[utoipa::path(
+    get,
+    path = "/auth/verify-email",
+    tag = "auth",
+    summary = "Verify email from the emailed link",
+    params(VerifyEmailRequest),
+    responses(
+        (status = 200, description = "Success", body = ApiResponse<UserResponse>),
+        ErrorResponses,
+    )
+)]
+pub async fn verify_email_link_handler(
+    State(state): State<AppState>,
+    Query(payload): Query<VerifyEmailRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let user = state.user_service.verify_email(&payload.token).await?;
+    Ok(ApiResponse::success(user, "1000", "auth.email_verified"))
+}
+
+// POST /auth/verify-email {"token": "..."}
+#[utoipa::path(
+    post,
+    path = "/auth/verify-email",
+    tag = "auth",
+    summary = "Verify email with a token",
+    request_body = VerifyEmailRequest,
+    responses(
+        (status = 200, description = "Success", body = ApiResponse<UserResponse>),
+        ErrorResponses,
+    )
+)]
+pub async fn verify_email_handler(
+    State(state): State<AppState>,
+    Json(payload): Json<VerifyEmailRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let user = state.user_service.verify_email(&payload.token).await?;
+    Ok(ApiResponse::success(user, "1000", "auth.email_verified"))
+}
@@ -1,80 +1,210 @@
 use crate::config::AppState;
-use crate::models::dto::{AddToCartRequest, UpdateCartItemRequest};
+use crate::models::dto::{
+    AddToCartRequest, CartRecoveryQuery, CartRecoveryResponse, CartResponse, CurrencyOptions,
+    ShippingOptionsQuery, ShippingOptionsResponse, UpdateCartItemRequest,
+};
 use crate::models::error::AppError;
 use crate::models::response::ApiResponse;
+use crate::openapi::ErrorResponses;
+use crate::utils::jwt::Claims;
+use crate::extractors::{Currency, Json, Path, Query};
 use axum::{
-    extract::{Path, State},
-    Extension, Json,
+    extract::State,
+    Extension,
     response::IntoResponse,
 };
 use uuid::Uuid;
 
-#[derive(Clone)]
-pub struct Claims {
-    pub user_id: Uuid,
-}
-
+#[utoipa::path(
+    get,
+    path = "/cart",
+    tag = "cart",
+    summary = "Get the current cart",
+    params(
+        CurrencyOptions,
+        ("X-Currency" = Option<String>, Header, description = "Currency for prices, same as ?currency="),
+    ),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Success", body = ApiResponse<CartResponse>),
+        ErrorResponses,
+    )
+)]
 pub async fn get_cart_handler(
     State(state): State<AppState>,
     Extension(claims): Extension<Claims>,
+    Currency(currency): Currency,
 ) -> Result<impl IntoResponse, AppError> {
-    let response = state.cart_service.get_cart(claims.user_id).await?;
+    let user_id = claims.get_user_id()?;
+    let response = state.cart_service.get_cart(user_id).await?;
 
     Ok(ApiResponse::success(
-        response,
+        response.in_currency(&currency)?,
         "1000",
-        "Get cart successfully.",
+        "cart.get",
     ))
 }
 
+#[utoipa::path(
+    post,
+    path = "/cart",
+    tag = "cart",
+    summary = "Add a product to the cart",
+    params(
+        CurrencyOptions,
+        ("X-Currency" = Option<String>, Header, description = "Currency for prices, same as ?currency="),
+        ("Idempotency-Key" = Option<String>, Header, description = "Retries with the same key replay the first response"),
+    ),
+    request_body = AddToCartRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Success", body = ApiResponse<CartResponse>),
+        ErrorResponses,
+    )
+)]
 pub async fn add_to_cart_handler(
     State(state): State<AppState>,
     Extension(claims): Extension<Claims>,
+    Currency(currency): Currency,
     Json(payload): Json<AddToCartRequest>,
 ) -> Result<impl IntoResponse, AppError> {
+    let user_id = claims.get_user_id()?;
     let response = state
         .cart_service
-        .add_to_cart(claims.user_id, payload)
+        .add_to_cart(user_id, payload)
         .await?;
 
     Ok(ApiResponse::success(
-        response,
+        response.in_currency(&currency)?,
         "1000",
-        "Add item to cart successfully.",
+        "cart.add",
     ))
 }
 
+#[utoipa::path(
+    patch,
+    path = "/cart/items/{id}",
+    tag = "cart",
+    summary = "Change the quantity of a cart item",
+    params(
+        CurrencyOptions,
+        ("X-Currency" = Option<String>, Header, description = "Currency for prices, same as ?currency="),
+        ("id" = Uuid, Path, description = "Cart item ID"),
+    ),
+    request_body = UpdateCartItemRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Success", body = ApiResponse<CartResponse>),
+        ErrorResponses,
+    )
+)]
 pub async fn update_cart_item_handler(
     State(state): State<AppState>,
     Extension(claims): Extension<Claims>,
+    Currency(currency): Currency,
     Path(id): Path<Uuid>,
     Json(payload): Json<UpdateCartItemRequest>,
 ) -> Result<impl IntoResponse, AppError> {
+    let user_id = claims.get_user_id()?;
     let response = state
         .cart_service
-        .update_item(claims.user_id, id, payload)
+        .update_item(user_id, id, payload)
         .await?;
 
     Ok(ApiResponse::success(
-        response,
+        response.in_currency(&currency)?,
         "1000",
-        "Update cart item successfully.",
+        "cart.update",
     ))
 }
 
+#[utoipa::path(
+    delete,
+    path = "/cart/items/{id}",
+    tag = "cart",
+    summary = "Remove a cart item",
+    params(
+        CurrencyOptions,
+        ("X-Currency" = Option<String>, Header, description = "Currency for prices, same as ?currency="),
+        ("id" = Uuid, Path, description = "Cart item ID"),
+    ),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Success", body = ApiResponse<CartResponse>),
+        ErrorResponses,
+    )
+)]
 pub async fn remove_cart_item_handler(
     State(state): State<AppState>,
     Extension(claims): Extension<Claims>,
+    Currency(currency): Currency,
     Path(id): Path<Uuid>,
 ) -> Result<impl IntoResponse, AppError> {
+    let user_id = claims.get_user_id()?;
     let response = state
         .cart_service
-        .remove_item(claims.user_id, id)
+        .remove_item(user_id, id)
+        .await?;
+
+    Ok(ApiResponse::success(
+        response.in_currency(&currency)?,
+        "1000",
+        "cart.remove",
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/cart/shipping-options",
+    tag = "cart",
+    summary = "Quote every shipping method for the current cart",
+    params(
+        ShippingOptionsQuery,
+        CurrencyOptions,
+        ("X-Currency" = Option<String>, Header, description = "Currency for prices, same as ?currency="),
+    ),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Success", body = ApiResponse<ShippingOptionsResponse>),
+        ErrorResponses,
+    )
+)]
+pub async fn shipping_options_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Currency(currency): Currency,
+    Query(query): Query<ShippingOptionsQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = claims.get_user_id()?;
+    let response = state
+        .shipping_service
+        .options_for_cart(user_id, query.address_id)
         .await?;
 
     Ok(ApiResponse::success(
-        response,
+        response.in_currency(&currency)?,
         "1000",
-        "Remove cart item successfully.",
+        "cart.shipping_options",
     ))
-}
\ No newline at end of file
+}
+
+// GET /cart/recover?token=... (ลิงก์ในข้อความแจ้งเตือนตะกร้าที่ถูกทิ้ง ไม่ต้อง login)
+#[utoipa::path(
+    get,
+    path = "/cart/recover",
+    tag = "cart",
+    summary = "Open an abandoned cart recovery link",
+    params(CartRecoveryQuery),
+    responses(
+        (status = 200, description = "Success", body = ApiResponse<CartRecoveryResponse>),
+        ErrorResponses,
+    )
+)]
+pub async fn recover_cart_handler(
+    State(state): State<AppState>,
+    Query(query): Query<CartRecoveryQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let recovery = state.cart_recovery_service.open(&query.token).await?;
+
+    Ok(ApiResponse::success(recovery, "1000", "cart.recover"))
+}
@@ -1,15 +1,32 @@
-use crate::models::dto::{CategoryRequest, UpdateCategoryRequest};
+use crate::models::dto::{
+    CategoryRequest, CategoryResponse, DeleteCategoryOptions, PagedResponse, UpdateCategoryRequest,
+};
 use crate::models::error::AppError;
 use crate::models::response::ApiResponse;
+use crate::openapi::{ErrorResponses, MessageResponse};
+use crate::utils::etag;
 use crate::{config::AppState, models::dto::FilterOptions};
-use axum::extract::Path;
+use crate::extractors::{Json, Path, Query};
 use axum::{
-    Extension, Json,
-    extract::{Query, State},
+    Extension,
+    extract::State,
+    http::HeaderMap,
     response::IntoResponse,
 };
 use uuid::Uuid;
 
+#[utoipa::path(
+    get,
+    path = "/categories",
+    tag = "categories",
+    summary = "List categories",
+    params(FilterOptions),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Success", body = ApiResponse<PagedResponse<CategoryResponse>>),
+        ErrorResponses,
+    )
+)]
 pub async fn get_categories_handler(
     State(state): State<AppState>,
     Query(opts): Query<FilterOptions>,
@@ -19,10 +36,22 @@ pub async fn get_categories_handler(
     Ok(ApiResponse::success(
         response,
         "1000",
-        "List categories successfully.",
+        "category.list",
     ))
 }
 
+#[utoipa::path(
+    post,
+    path = "/categories",
+    tag = "categories",
+    summary = "Create a category",
+    request_body = CategoryRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Success", body = MessageResponse),
+        ErrorResponses,
+    )
+)]
 pub async fn create_categories_handler(
     State(state): State<AppState>,
     Json(payload): Json<CategoryRequest>,
@@ -31,48 +60,136 @@ pub async fn create_categories_handler(
 
     Ok(ApiResponse::<()>::success_no_data(
         "1000",
-        "Create category successfully.",
+        "category.create",
     ))
 }
 
+#[utoipa::path(
+    get,
+    path = "/categories/{id}",
+    tag = "categories",
+    summary = "Get a category",
+    params(
+        ("id" = Uuid, Path, description = "Category ID"),
+        ("If-None-Match" = Option<String>, Header, description = "ETag from the last read, returns 304 if unchanged"),
+    ),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Success", body = ApiResponse<CategoryResponse>),
+        ErrorResponses,
+    )
+)]
 pub async fn get_category_handler(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
+    headers: HeaderMap,
 ) -> Result<impl IntoResponse, AppError> {
     let category = state.categories_service.get_categories_by_id(id).await?;
+    let version = category.version;
 
-    Ok(ApiResponse::success(
-        category,
-        "1000",
-        "Get category successfully.",
+    Ok(etag::conditional_get(
+        &headers,
+        version,
+        ApiResponse::success(category, "1000", "category.get"),
     ))
 }
 
+// DELETE /categories/:id?mode=restrict|deactivate|reassign&reassign_to=<uuid>
+#[utoipa::path(
+    delete,
+    path = "/categories/{id}",
+    tag = "categories",
+    summary = "Deactivate a category",
+    params(
+        ("id" = Uuid, Path, description = "Category ID"),
+        DeleteCategoryOptions,
+        ("If-Match" = Option<String>, Header, description = "ETag from the last read, rejects the write with 412 if the resource changed"),
+    ),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Success", body = MessageResponse),
+        ErrorResponses,
+    )
+)]
 pub async fn delete_category_handler(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
+    Query(opts): Query<DeleteCategoryOptions>,
+    headers: HeaderMap,
 ) -> Result<impl IntoResponse, AppError> {
-    let delete = state
+    let expected_versions = etag::if_match_versions(&headers)?;
+    let changes = state
         .categories_service
-        .delete_categories(id)
+        .delete_categories(id, expected_versions, opts)
         .await?;
+    let _ = state.search_service.apply_changes(changes).await;
 
     Ok(ApiResponse::<()>::success_no_data(
         "1000",
-        "Delete category successfully.",
+        "category.delete",
     ))
 }
 
+#[utoipa::path(
+    patch,
+    path = "/categories/{id}",
+    tag = "categories",
+    summary = "Update a category",
+    params(
+        ("id" = Uuid, Path, description = "Category ID"),
+        ("If-Match" = Option<String>, Header, description = "ETag from the last read, rejects the write with 412 if the resource changed"),
+    ),
+    request_body = UpdateCategoryRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Success", body = ApiResponse<CategoryResponse>),
+        ErrorResponses,
+    )
+)]
 pub async fn update_categories_handler(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
+    headers: HeaderMap,
     Json(payload): Json<UpdateCategoryRequest>,
 ) -> Result<impl IntoResponse, AppError> {
-    let update_data = state.categories_service.update_categories(id, payload).await?;
+    let expected_versions = etag::if_match_versions(&headers)?;
+    let update_data = state
+        .categories_service
+        .update_categories(id, payload, expected_versions)
+        .await?;
+    let version = update_data.version;
 
-    Ok(ApiResponse::success(
-        update_data,
-        "1000",
-        "Update category successfully.",
+    Ok(etag::with_etag(
+        version,
+        ApiResponse::success(update_data, "1000", "category.update"),
     ))
-}
\ No newline at end of file
+}
+
+// POST /categories/:id/restore
+#[utoipa::path(
+    post,
+    path = "/categories/{id}/restore",
+    tag = "categories",
+    summary = "Restore a deactivated category",
+    params(
+        ("id" = Uuid, Path, description = "Category ID"),
+    ),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Success", body = ApiResponse<CategoryResponse>),
+        ErrorResponses,
+    )
+)]
+pub async fn restore_category_handler(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let (category, changes) = state.categories_service.restore_category(id).await?;
+    let _ = state.search_service.apply_changes(changes).await;
+    let version = category.version;
+
+    Ok(etag::with_etag(
+        version,
+        ApiResponse::success(category, "1000", "category.restore"),
+    ))
+}
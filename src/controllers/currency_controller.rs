@@ -0,0 +1,83 @@
+use crate::config::AppState;
+use crate::extractors::{Json, Path};
+use crate::models::dto::{CurrencyRatesResponse, ExchangeRateRequest, ExchangeRateResponse};
+use crate::models::error::AppError;
+use crate::models::response::ApiResponse;
+use crate::openapi::{ErrorResponses, MessageResponse};
+use crate::utils::jwt::Claims;
+use axum::{Extension, extract::State, response::IntoResponse};
+
+// GET /admin/exchange-rates
+#[utoipa::path(
+    get,
+    path = "/admin/exchange-rates",
+    tag = "admin",
+    summary = "List exchange rates from the store base currency",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Success", body = ApiResponse<CurrencyRatesResponse>),
+        ErrorResponses,
+    )
+)]
+pub async fn list_rates_handler(
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, AppError> {
+    let rates = state.currency_service.list_rates().await?;
+
+    Ok(ApiResponse::success(rates, "1000", "currency.rates_list"))
+}
+
+// PUT /admin/exchange-rates/:currency (เพิ่มหรือแก้อัตรา)
+#[utoipa::path(
+    put,
+    path = "/admin/exchange-rates/{currency}",
+    tag = "admin",
+    summary = "Create or update the exchange rate of a currency",
+    params(
+        ("currency" = String, Path, description = "ISO 4217 code, e.g. USD"),
+    ),
+    request_body = ExchangeRateRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Success", body = ApiResponse<ExchangeRateResponse>),
+        ErrorResponses,
+    )
+)]
+pub async fn set_rate_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(currency): Path<String>,
+    Json(payload): Json<ExchangeRateRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let admin_id = claims.get_user_id()?;
+    let rate = state
+        .currency_service
+        .set_rate(&currency, payload.rate, admin_id)
+        .await?;
+
+    Ok(ApiResponse::success(rate, "1000", "currency.rate_update"))
+}
+
+// DELETE /admin/exchange-rates/:currency
+#[utoipa::path(
+    delete,
+    path = "/admin/exchange-rates/{currency}",
+    tag = "admin",
+    summary = "Stop offering a currency",
+    params(
+        ("currency" = String, Path, description = "ISO 4217 code, e.g. USD"),
+    ),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Success", body = MessageResponse),
+        ErrorResponses,
+    )
+)]
+pub async fn delete_rate_handler(
+    State(state): State<AppState>,
+    Path(currency): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    state.currency_service.delete_rate(&currency).await?;
+
+    Ok(ApiResponse::<()>::success_no_data("1000", "currency.rate_delete"))
+}
@@ -0,0 +1,7 @@
+use crate::openapi::OPENAPI;
+use axum::{Json, response::IntoResponse};
+
+// GET /openapi.json (OpenAPI 3.1 สร้างจาก #[utoipa::path] ของทุก handler)
+pub async fn openapi_handler() -> impl IntoResponse {
+    Json(&*OPENAPI)
+}
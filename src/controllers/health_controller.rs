@@ -0,0 +1,52 @@
+use crate::config::AppState;
+use crate::models::dto::ReadinessReport;
+use crate::models::error_code::ErrorCode;
+use crate::models::response::ApiResponse;
+use crate::openapi::MessageResponse;
+use axum::{
+    Json,
+    extract::State,
+    http::StatusCode,
+    response::IntoResponse,
+};
+
+// GET /healthz/live : process ยังตอบได้ (ไม่แตะ dependency ใด ๆ)
+#[utoipa::path(
+    get,
+    path = "/healthz/live",
+    tag = "health",
+    summary = "Liveness probe",
+    responses(
+        (status = 200, description = "Success", body = MessageResponse),
+    )
+)]
+pub async fn live_handler() -> impl IntoResponse {
+    ApiResponse::<()>::success_no_data("1000", "health.alive")
+}
+
+// GET /healthz/ready : พร้อมรับ traffic หรือยัง (DB, Meilisearch, Migration)
+#[utoipa::path(
+    get,
+    path = "/healthz/ready",
+    tag = "health",
+    summary = "Readiness probe (database, Meilisearch, migrations)",
+    responses(
+        (status = 200, description = "Ready", body = ApiResponse<ReadinessReport>),
+        (status = 503, description = "A required dependency is down", body = ApiResponse<ReadinessReport>),
+    )
+)]
+pub async fn ready_handler(State(state): State<AppState>) -> impl IntoResponse {
+    let report = state.health_service.readiness().await;
+
+    if report.ready {
+        (
+            StatusCode::OK,
+            Json(ApiResponse::success(report, "1000", "health.ready")),
+        )
+    } else {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ApiResponse::failure(report, ErrorCode::ServiceUnavailable, "health.not_ready")),
+        )
+    }
+}
@@ -0,0 +1,19 @@
+use crate::models::error_code::{ErrorCode, ErrorCodeInfo};
+use crate::models::response::ApiResponse;
+use axum::response::IntoResponse;
+
+// GET /meta/error-codes (รายการรหัส Error ทั้งหมด ให้ client ใช้แปลงเป็นข้อความ/ตัดสินใจ retry)
+#[utoipa::path(
+    get,
+    path = "/meta/error-codes",
+    tag = "meta",
+    summary = "List all application error codes",
+    responses(
+        (status = 200, description = "Success", body = ApiResponse<Vec<ErrorCodeInfo>>),
+    )
+)]
+pub async fn error_codes_handler() -> impl IntoResponse {
+    let codes: Vec<ErrorCodeInfo> = ErrorCode::ALL.into_iter().map(ErrorCodeInfo::from).collect();
+
+    ApiResponse::success(codes, "1000", "meta.error_codes")
+}
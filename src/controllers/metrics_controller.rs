@@ -0,0 +1,31 @@
+use crate::config::AppState;
+use axum::{
+    extract::State,
+    http::header,
+    response::IntoResponse,
+};
+
+// GET /metrics (Prometheus text format)
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    tag = "metrics",
+    summary = "Prometheus metrics",
+    responses(
+        (status = 200, description = "Prometheus text format", content_type = "text/plain", body = String),
+    )
+)]
+pub async fn metrics_handler(State(state): State<AppState>) -> impl IntoResponse {
+    // ค่า pool เป็น snapshot ตอนถูก scrape
+    let size = state.db.size() as f64;
+    let idle = state.db.num_idle() as f64;
+    metrics::gauge!("db_pool_connections", "state" => "idle").set(idle);
+    metrics::gauge!("db_pool_connections", "state" => "active").set(size - idle);
+    metrics::gauge!("db_pool_max_connections")
+        .set(state.settings.database.max_connections as f64);
+
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        state.metrics.render(),
+    )
+}
@@ -2,4 +2,13 @@ pub mod auth_controller;
 pub mod user_controller;
 pub mod categories_controller;
 pub mod products_controller;
-pub mod cart_controller;
\ No newline at end of file
+pub mod cart_controller;
+pub mod metrics_controller;
+pub mod health_controller;
+pub mod address_controller;
+pub mod order_controller;
+pub mod payment_controller;
+pub mod docs_controller;
+pub mod meta_controller;
+pub mod currency_controller;
+pub mod stats_controller;
@@ -0,0 +1,160 @@
+use crate::config::AppState;
+use crate::models::dto::{
+    CheckoutRequest, CheckoutResponse, FilterOptions, OrderResponse, PagedResponse,
+    PaymentIntentResponse, PaymentResponse,
+};
+use crate::models::error::AppError;
+use crate::models::response::ApiResponse;
+use crate::openapi::ErrorResponses;
+use crate::utils::jwt::Claims;
+use crate::extractors::{Json, Path, Query};
+use axum::{
+    Extension,
+    extract::State,
+    response::IntoResponse,
+};
+use uuid::Uuid;
+
+// POST /orders/checkout
+#[utoipa::path(
+    post,
+    path = "/orders/checkout",
+    tag = "orders",
+    summary = "Create an order from the cart and start payment",
+    params(
+        ("Idempotency-Key" = Option<String>, Header, description = "Retries with the same key replay the first response"),
+    ),
+    request_body = CheckoutRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Success", body = ApiResponse<CheckoutResponse>),
+        ErrorResponses,
+    )
+)]
+pub async fn checkout_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<CheckoutRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = claims.get_user_id()?;
+    let response = state.order_service.checkout(user_id, payload).await?;
+
+    Ok(ApiResponse::success(
+        response,
+        "1000",
+        "order.checkout",
+    ))
+}
+
+// GET /orders
+#[utoipa::path(
+    get,
+    path = "/orders",
+    tag = "orders",
+    summary = "List my orders",
+    params(FilterOptions),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Success", body = ApiResponse<PagedResponse<OrderResponse>>),
+        ErrorResponses,
+    )
+)]
+pub async fn list_orders_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Query(opts): Query<FilterOptions>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = claims.get_user_id()?;
+    let response = state.order_service.list_orders(user_id, opts).await?;
+
+    Ok(ApiResponse::success(
+        response,
+        "1000",
+        "order.list",
+    ))
+}
+
+// GET /orders/:id
+#[utoipa::path(
+    get,
+    path = "/orders/{id}",
+    tag = "orders",
+    summary = "Get an order",
+    params(
+        ("id" = Uuid, Path, description = "Order ID"),
+    ),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Success", body = ApiResponse<OrderResponse>),
+        ErrorResponses,
+    )
+)]
+pub async fn get_order_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = claims.get_user_id()?;
+    let order = state.order_service.get_order(user_id, id).await?;
+
+    Ok(ApiResponse::success(order, "1000", "order.get"))
+}
+
+// POST /orders/:id/payments
+#[utoipa::path(
+    post,
+    path = "/orders/{id}/payments",
+    tag = "orders",
+    summary = "Start a new payment attempt for an unpaid order",
+    params(
+        ("id" = Uuid, Path, description = "Order ID"),
+        ("Idempotency-Key" = Option<String>, Header, description = "Retries with the same key replay the first response"),
+    ),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Success", body = ApiResponse<PaymentIntentResponse>),
+        ErrorResponses,
+    )
+)]
+pub async fn retry_payment_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = claims.get_user_id()?;
+    let payment = state.order_service.retry_payment(user_id, id).await?;
+
+    Ok(ApiResponse::success(
+        payment,
+        "1000",
+        "order.payment_started",
+    ))
+}
+
+// POST /admin/orders/:id/refund
+#[utoipa::path(
+    post,
+    path = "/admin/orders/{id}/refund",
+    tag = "admin",
+    summary = "Refund a paid order in full",
+    params(
+        ("id" = Uuid, Path, description = "Order ID"),
+    ),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Success", body = ApiResponse<PaymentResponse>),
+        ErrorResponses,
+    )
+)]
+pub async fn refund_order_handler(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let payment = state.payment_service.refund_order(id).await?;
+
+    Ok(ApiResponse::success(
+        payment,
+        "1000",
+        "order.refund_requested",
+    ))
+}
@@ -0,0 +1,44 @@
+use crate::config::AppState;
+use crate::models::error::AppError;
+use crate::models::response::ApiResponse;
+use crate::openapi::{ErrorResponses, MessageResponse};
+use crate::extractors::Path;
+use axum::{
+    body::Bytes,
+    extract::State,
+    http::HeaderMap,
+    response::IntoResponse,
+};
+
+// POST /payments/webhook/:provider (ไม่ผ่าน auth: ยืนยันตัวตนด้วยลายเซ็นของ provider แทน)
+// รับ body ดิบ เพราะลายเซ็นคำนวณจาก byte ที่ provider ส่งมาตรง ๆ
+#[utoipa::path(
+    post,
+    path = "/payments/webhook/{provider}",
+    tag = "payments",
+    summary = "Payment provider webhook (signed by the provider)",
+    params(
+        ("provider" = String, Path, description = "Payment provider name, e.g. mock"),
+    ),
+    request_body(content = String, content_type = "application/json", description = "Raw provider payload (the signature covers the exact bytes)"),
+    responses(
+        (status = 200, description = "Success", body = MessageResponse),
+        ErrorResponses,
+    )
+)]
+pub async fn webhook_handler(
+    State(state): State<AppState>,
+    Path(provider): Path<String>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<impl IntoResponse, AppError> {
+    state
+        .payment_service
+        .handle_webhook(&provider, &headers, &body)
+        .await?;
+
+    Ok(ApiResponse::<()>::success_no_data(
+        "1000",
+        "payment.webhook_processed",
+    ))
+}
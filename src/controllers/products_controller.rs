@@ -1,37 +1,97 @@
 use crate::config::AppState;
+use crate::i18n;
 use crate::models::{
-    dto::{FilterOptions, ProductRequest, ProductSearchDocument, UpdateProductRequest},
+    dto::{
+        CurrencyOptions, ExportOptions, FilterOptions, ImportOptions, ImportReport, PagedResponse,
+        ProductRequest, ProductResponse, ProductSearchDocument, RestockSubscriptionResponse,
+        TransferFormat, UpdateProductRequest,
+    },
     error::AppError,
+    error_code::ErrorCode,
     response::ApiResponse,
 };
+use crate::openapi::{ErrorResponses, MessageResponse};
+use crate::utils::etag;
+use crate::utils::jwt::Claims;
+use crate::utils::money::CurrencyConverter;
+use crate::extractors::{Currency, Json, Path, Query};
 use axum::{
-    Json,
-    extract::{Path, Query, State},
-    response::IntoResponse,
+    Extension,
+    body::{Body, Bytes},
+    extract::State,
+    http::{HeaderMap, StatusCode, header},
+    response::{IntoResponse, Response},
 };
+use futures::TryStreamExt;
 use meilisearch_sdk::search::SearchResults;
+use rust_decimal::Decimal;
 use uuid::Uuid;
-#[derive(serde::Deserialize)]
+#[derive(serde::Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct SearchQuery {
     pub q: Option<String>,      
     pub filter: Option<String>, 
     pub sort: Option<String>,   
     pub limit: Option<usize>,   
     pub offset: Option<usize>,
+    // ช่วงราคาในสกุลเงินที่ขอ (แปลงเป็นสกุลหลักก่อนส่งให้ Meilisearch)
+    pub min_price: Option<Decimal>,
+    pub max_price: Option<Decimal>,
 }
 
+// ข้อมูลที่ Admin แก้ตอบกลับเป็นสกุลหลักเสมอ (ราคาที่ส่งมาก็เป็นสกุลหลัก)
+fn base_currency(state: &AppState) -> CurrencyConverter {
+    CurrencyConverter::base(state.currency_service.base_currency())
+}
+
+#[utoipa::path(
+    get,
+    path = "/products",
+    tag = "products",
+    summary = "List products",
+    params(
+        FilterOptions,
+        CurrencyOptions,
+        ("X-Currency" = Option<String>, Header, description = "Currency for prices, same as ?currency="),
+    ),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Success", body = ApiResponse<PagedResponse<ProductResponse>>),
+        ErrorResponses,
+    )
+)]
 pub async fn list_products_handler(
     State(state): State<AppState>,
     Query(opts): Query<FilterOptions>,
+    Currency(currency): Currency,
 ) -> Result<impl IntoResponse, AppError> {
-    let response = state.products_service.list_products(opts).await?;
+    let response = state
+        .products_service
+        .list_products(opts)
+        .await?
+        .try_map(|product| product.in_currency(&currency))?;
     Ok(ApiResponse::success(
         response,
         "1000",
-        "List products successfully.",
+        "product.list",
     ))
 }
 
+#[utoipa::path(
+    post,
+    path = "/products",
+    tag = "products",
+    summary = "Create a product",
+    params(
+        ("Idempotency-Key" = Option<String>, Header, description = "Retries with the same key replay the first response"),
+    ),
+    request_body = ProductRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Success", body = ApiResponse<ProductResponse>),
+        ErrorResponses,
+    )
+)]
 pub async fn create_product_handler(
     State(state): State<AppState>,
     Json(payload): Json<ProductRequest>,
@@ -46,36 +106,82 @@ pub async fn create_product_handler(
         price: product.price,
         category_id: product.category_id,
         image_url: None,
+        currency: None,
     };
 
     // เรียกแบบ Fire-and-forget
     let _ = state.search_service.add_product(search_doc).await;
 
     Ok(ApiResponse::success(
-        product,
+        product.in_currency(&base_currency(&state))?,
         "1000",
-        "Create product successfully.",
+        "product.create",
     ))
 }
 
+#[utoipa::path(
+    get,
+    path = "/products/{id}",
+    tag = "products",
+    summary = "Get a product",
+    params(
+        ("id" = Uuid, Path, description = "Product ID"),
+        ("If-None-Match" = Option<String>, Header, description = "ETag from the last read, returns 304 if unchanged"),
+        CurrencyOptions,
+        ("X-Currency" = Option<String>, Header, description = "Currency for prices, same as ?currency="),
+    ),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Success", body = ApiResponse<ProductResponse>),
+        ErrorResponses,
+    )
+)]
 pub async fn get_product_handler(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
+    headers: HeaderMap,
+    Currency(currency): Currency,
 ) -> Result<impl IntoResponse, AppError> {
     let product = state.products_service.get_product_by_id(id).await?;
-    Ok(ApiResponse::success(
-        product,
-        "1000",
-        "Get product successfully.",
-    ))
+    let version = product.version;
+    let is_base = currency.currency == state.currency_service.base_currency();
+    let response = ApiResponse::success(product.in_currency(&currency)?, "1000", "product.get");
+
+    // ราคาที่แปลงแล้วเปลี่ยนตามอัตราแลกเปลี่ยนได้โดยที่ version ไม่เปลี่ยน จึงใช้ ETag เฉพาะสกุลหลัก
+    if is_base {
+        Ok(etag::conditional_get(&headers, version, response))
+    } else {
+        Ok(response.into_response())
+    }
 }
 
+#[utoipa::path(
+    patch,
+    path = "/products/{id}",
+    tag = "products",
+    summary = "Update a product",
+    params(
+        ("id" = Uuid, Path, description = "Product ID"),
+        ("If-Match" = Option<String>, Header, description = "ETag from the last read, rejects the write with 412 if the resource changed"),
+    ),
+    request_body = UpdateProductRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Success", body = ApiResponse<ProductResponse>),
+        ErrorResponses,
+    )
+)]
 pub async fn update_product_handler(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
+    headers: HeaderMap,
     Json(payload): Json<UpdateProductRequest>,
 ) -> Result<impl IntoResponse, AppError> {
-    let product = state.products_service.update_product(id, payload).await?;
+    let expected_versions = etag::if_match_versions(&headers)?;
+    let product = state
+        .products_service
+        .update_product(id, payload, expected_versions)
+        .await?;
 
     let search_doc = ProductSearchDocument {
         id: product.id,
@@ -84,42 +190,198 @@ pub async fn update_product_handler(
         price: product.price,
         category_id: product.category_id,
         image_url: None,
+        currency: None,
     };
     let _ = state.search_service.add_product(search_doc).await;
 
-    Ok(ApiResponse::success(
-        product,
-        "1000",
-        "Update product successfully.",
+    let version = product.version;
+    Ok(etag::with_etag(
+        version,
+        ApiResponse::success(product.in_currency(&base_currency(&state))?, "1000", "product.update"),
     ))
 }
 
+#[utoipa::path(
+    delete,
+    path = "/products/{id}",
+    tag = "products",
+    summary = "Deactivate a product",
+    params(
+        ("id" = Uuid, Path, description = "Product ID"),
+        ("If-Match" = Option<String>, Header, description = "ETag from the last read, rejects the write with 412 if the resource changed"),
+    ),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Success", body = MessageResponse),
+        ErrorResponses,
+    )
+)]
 pub async fn delete_product_handler(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
+    headers: HeaderMap,
 ) -> Result<impl IntoResponse, AppError> {
-    state.products_service.delete_product(id).await?;
+    let expected_versions = etag::if_match_versions(&headers)?;
+    state
+        .products_service
+        .delete_product(id, expected_versions)
+        .await?;
     let _ = state.search_service.delete_product(id).await;
     Ok(ApiResponse::<()>::success_no_data(
         "1000",
-        "Delete product successfully.",
+        "product.delete",
+    ))
+}
+
+// POST /products/:id/restore
+#[utoipa::path(
+    post,
+    path = "/products/{id}/restore",
+    tag = "products",
+    summary = "Restore a deactivated product",
+    params(
+        ("id" = Uuid, Path, description = "Product ID"),
+    ),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Success", body = ApiResponse<ProductResponse>),
+        ErrorResponses,
+    )
+)]
+pub async fn restore_product_handler(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let product = state.products_service.restore_product(id).await?;
+
+    let search_doc = ProductSearchDocument {
+        id: product.id,
+        name: product.name.clone(),
+        description: product.description.clone().unwrap_or_default(),
+        price: product.price,
+        category_id: product.category_id,
+        image_url: None,
+        currency: None,
+    };
+    let _ = state.search_service.add_product(search_doc).await;
+
+    let version = product.version;
+    Ok(etag::with_etag(
+        version,
+        ApiResponse::success(product.in_currency(&base_currency(&state))?, "1000", "product.restore"),
+    ))
+}
+
+// POST /products/:id/restock-subscription
+#[utoipa::path(
+    post,
+    path = "/products/{id}/restock-subscription",
+    tag = "products",
+    summary = "Get notified when a product is back in stock",
+    params(
+        ("id" = Uuid, Path, description = "Product ID"),
+    ),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Success", body = ApiResponse<RestockSubscriptionResponse>),
+        ErrorResponses,
+    )
+)]
+pub async fn subscribe_restock_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = claims.get_user_id()?;
+    let subscription = state
+        .products_service
+        .subscribe_restock(user_id, id)
+        .await?;
+
+    Ok(ApiResponse::success(
+        subscription,
+        "1000",
+        "product.restock_subscribe",
+    ))
+}
+
+// DELETE /products/:id/restock-subscription
+#[utoipa::path(
+    delete,
+    path = "/products/{id}/restock-subscription",
+    tag = "products",
+    summary = "Cancel a restock notification",
+    params(
+        ("id" = Uuid, Path, description = "Product ID"),
+    ),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Success", body = MessageResponse),
+        ErrorResponses,
+    )
+)]
+pub async fn unsubscribe_restock_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = claims.get_user_id()?;
+    state
+        .products_service
+        .unsubscribe_restock(user_id, id)
+        .await?;
+
+    Ok(ApiResponse::<()>::success_no_data(
+        "1000",
+        "product.restock_unsubscribe",
     ))
 }
 
 // GET /products/search?q=iphone
+#[utoipa::path(
+    get,
+    path = "/products/search",
+    tag = "products",
+    summary = "Full-text product search",
+    params(
+        SearchQuery,
+        CurrencyOptions,
+        ("X-Currency" = Option<String>, Header, description = "Currency for prices and min_price/max_price, same as ?currency="),
+    ),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Success", body = ApiResponse<Vec<ProductSearchDocument>>),
+        ErrorResponses,
+    )
+)]
 pub async fn search_products_handler(
     State(state): State<AppState>,
     Query(params): Query<SearchQuery>,
+    Currency(currency): Currency,
 ) -> Result<impl IntoResponse, AppError> {
-    let results = state.search_service.search_products(params).await?;
+    let results = state
+        .search_service
+        .search_products(params, &currency)
+        .await?;
 
     Ok(ApiResponse::success(
         results,
         "1000",
-        "Search successfully.",
+        "product.search",
     ))
 }
 
+#[utoipa::path(
+    get,
+    path = "/products/sync",
+    tag = "products",
+    summary = "Push all products to the search index",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Success", body = MessageResponse),
+        ErrorResponses,
+    )
+)]
 pub async fn sync_products_handler(
     State(state): State<AppState>,
 ) -> Result<impl IntoResponse, AppError> {
@@ -147,6 +409,7 @@ pub async fn sync_products_handler(
             price: p.price,
             category_id: p.category_id,
             image_url: None,
+            currency: None,
         });
     }
 
@@ -158,59 +421,126 @@ pub async fn sync_products_handler(
 
     Ok(ApiResponse::<()>::success_no_data(
         "1000",
-        "Sync products to Meilisearch successfully.",
+        "product.sync",
     ))
 }
 
 pub async fn reindex_handler(
     State(state): State<AppState>,
 ) -> Result<impl IntoResponse, AppError> {
-    let batch_size = 1000;
-    let mut page = 1;
-
-    state.search_service.delete_all_documents().await?;
-
-    loop {
-        let filter = FilterOptions {
-            page: Some(page),
-            limit: Some(batch_size),
-            search: None,
-            sort_by: Some("created_at".to_string()),
-            sort_dir: Some("asc".to_string()),
-            is_active: None,
-        };
-
-        let products_response = state.products_service.list_products(filter).await?;
-        let products = products_response.data;
-
-        if products.is_empty() {
-            break;
+    state
+        .products_service
+        .reindex_search(&state.search_service)
+        .await?;
+
+    Ok(ApiResponse::<()>::success_no_data(
+        "1000",
+        "product.sync_completed",
+    ))
+}
+
+// POST /admin/products/import?format=csv&dry_run=true
+#[utoipa::path(
+    post,
+    path = "/admin/products/import",
+    tag = "admin",
+    summary = "Import products from CSV or NDJSON",
+    params(ImportOptions),
+    request_body(content = String, content_type = "text/csv", description = "CSV or NDJSON (application/x-ndjson), one product per row"),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Success", body = ApiResponse<ImportReport>),
+        ErrorResponses,
+    )
+)]
+pub async fn import_products_handler(
+    State(state): State<AppState>,
+    Query(opts): Query<ImportOptions>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Response, AppError> {
+    // ใช้ ?format= ก่อน ถ้าไม่มีค่อยดูจาก Content-Type
+    let format = opts
+        .format
+        .or_else(|| {
+            headers
+                .get(header::CONTENT_TYPE)
+                .and_then(|v| v.to_str().ok())
+                .and_then(TransferFormat::from_content_type)
+        })
+        .ok_or(AppError::ValidationError(
+            "error.product.unknown_import_format".into(),
+        ))?;
+    let dry_run = opts.dry_run.unwrap_or(false);
+
+    let (report, search_docs) = state
+        .products_service
+        .import_products(format, &body, dry_run)
+        .await?;
+
+    // แถวที่เขียนลง DB ไปแล้วต้องขึ้น index ด้วย แม้บางแถวจะถูกข้าม
+    if !search_docs.is_empty() {
+        let _ = state.search_service.add_documents(&search_docs).await;
+    }
+
+    if !report.errors.is_empty() {
+        let count = report.errors.len().to_string();
+        // ไม่มีแถวไหนถูกเขียนเลย = 400, เขียนได้บางส่วน = 200 พร้อมรายการแถวที่ถูกข้าม
+        if report.created + report.updated > 0 {
+            let description = i18n::t_args("product.import_partial", &[("count", &count)]);
+            return Ok(ApiResponse::success(report, "1000", &description).into_response());
         }
 
-        // แปลงข้อมูล
-        let docs: Vec<ProductSearchDocument> = products
-            .into_iter()
-            .map(|p| ProductSearchDocument {
-                id: p.id,
-                name: p.name,
-                description: p.description.unwrap_or_default(),
-                price: p.price,
-                category_id: p.category_id,
-                image_url: None,
-            })
-            .collect();
-
-
-        // ถ้าใช้ blocking ในลูปนี้ API อาจจะ Timeout ได้ถ้าข้อมูลเยอะจัด
-        // แนะนำให้ใช้ add_documents ธรรมดา แล้วปล่อยให้ Meilisearch จัดคิวเอง
-        state.search_service.add_documents(&docs).await?;
-
-        // ขยับไปหน้าถัดไป
-        page += 1;
+        let description = i18n::t_args("product.import_invalid_rows", &[("count", &count)]);
+        return Ok((
+            StatusCode::BAD_REQUEST,
+            ApiResponse::failure(report, ErrorCode::ValidationFailed, &description),
+        )
+            .into_response());
     }
 
-    Ok(ApiResponse::<()>::success_no_data(
-        "1000",
-        "Sync Completed! Index is now up-to-date.",
+    let description = if dry_run {
+        "product.import_dry_run"
+    } else {
+        "product.import"
+    };
+    Ok(ApiResponse::success(report, "1000", description).into_response())
+}
+
+// GET /admin/products/export?format=ndjson
+#[utoipa::path(
+    get,
+    path = "/admin/products/export",
+    tag = "admin",
+    summary = "Export products as CSV or NDJSON",
+    params(ExportOptions),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "File download", content_type = "text/csv", body = String),
+        ErrorResponses,
+    )
+)]
+pub async fn export_products_handler(
+    State(state): State<AppState>,
+    Query(opts): Query<ExportOptions>,
+) -> Result<impl IntoResponse, AppError> {
+    let format = opts.format.unwrap_or(TransferFormat::Csv);
+
+    let stream = state
+        .products_service
+        .export_products(format)
+        .map_err(|e| std::io::Error::other(format!("{:?}", e)));
+
+    let disposition = format!(
+        "attachment; filename=\"products.{}\"",
+        format.file_extension()
+    );
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, format.content_type().to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        Body::from_stream(stream),
     ))
 }
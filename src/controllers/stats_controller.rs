@@ -0,0 +1,140 @@
+use crate::config::AppState;
+use crate::extractors::Query;
+use crate::models::dto::{
+    AbandonedCartStatsResponse, CartStatsResponse, CategoryProductCount, RevenueStatsResponse,
+    SignupStatsResponse, StatsRangeQuery, TopProductResponse,
+};
+use crate::models::error::AppError;
+use crate::models::response::ApiResponse;
+use crate::openapi::ErrorResponses;
+use axum::{extract::State, response::IntoResponse};
+
+// GET /admin/stats/signups
+#[utoipa::path(
+    get,
+    path = "/admin/stats/signups",
+    tag = "admin",
+    summary = "New user signups per day",
+    params(StatsRangeQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Success", body = ApiResponse<SignupStatsResponse>),
+        ErrorResponses,
+    )
+)]
+pub async fn signups_handler(
+    State(state): State<AppState>,
+    Query(query): Query<StatsRangeQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let stats = state.stats_service.signups(&query).await?;
+
+    Ok(ApiResponse::success(stats, "1000", "stats.signups"))
+}
+
+// GET /admin/stats/carts
+#[utoipa::path(
+    get,
+    path = "/admin/stats/carts",
+    tag = "admin",
+    summary = "Active carts and their current value",
+    params(StatsRangeQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Success", body = ApiResponse<CartStatsResponse>),
+        ErrorResponses,
+    )
+)]
+pub async fn carts_handler(
+    State(state): State<AppState>,
+    Query(query): Query<StatsRangeQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let stats = state.stats_service.carts(&query).await?;
+
+    Ok(ApiResponse::success(stats, "1000", "stats.carts"))
+}
+
+// GET /admin/stats/top-products
+#[utoipa::path(
+    get,
+    path = "/admin/stats/top-products",
+    tag = "admin",
+    summary = "Products added to carts most often",
+    params(StatsRangeQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Success", body = ApiResponse<Vec<TopProductResponse>>),
+        ErrorResponses,
+    )
+)]
+pub async fn top_products_handler(
+    State(state): State<AppState>,
+    Query(query): Query<StatsRangeQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let products = state.stats_service.top_products(&query).await?;
+
+    Ok(ApiResponse::success(products, "1000", "stats.top_products"))
+}
+
+// GET /admin/stats/categories
+#[utoipa::path(
+    get,
+    path = "/admin/stats/categories",
+    tag = "admin",
+    summary = "Product counts per category",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Success", body = ApiResponse<Vec<CategoryProductCount>>),
+        ErrorResponses,
+    )
+)]
+pub async fn categories_handler(
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, AppError> {
+    let categories = state.stats_service.categories().await?;
+
+    Ok(ApiResponse::success(categories, "1000", "stats.categories"))
+}
+
+// GET /admin/stats/revenue
+#[utoipa::path(
+    get,
+    path = "/admin/stats/revenue",
+    tag = "admin",
+    summary = "Paid order revenue per day and per category",
+    params(StatsRangeQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Success", body = ApiResponse<RevenueStatsResponse>),
+        ErrorResponses,
+    )
+)]
+pub async fn revenue_handler(
+    State(state): State<AppState>,
+    Query(query): Query<StatsRangeQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let stats = state.stats_service.revenue(&query).await?;
+
+    Ok(ApiResponse::success(stats, "1000", "stats.revenue"))
+}
+
+// GET /admin/stats/abandoned-carts
+#[utoipa::path(
+    get,
+    path = "/admin/stats/abandoned-carts",
+    tag = "admin",
+    summary = "Abandoned cart value and recovery notification results",
+    params(StatsRangeQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Success", body = ApiResponse<AbandonedCartStatsResponse>),
+        ErrorResponses,
+    )
+)]
+pub async fn abandoned_carts_handler(
+    State(state): State<AppState>,
+    Query(query): Query<StatsRangeQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let stats = state.stats_service.abandoned_carts(&query).await?;
+
+    Ok(ApiResponse::success(stats, "1000", "stats.abandoned_carts"))
+}
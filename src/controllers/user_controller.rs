@@ -1,19 +1,37 @@
 use crate::{config::AppState, models::dto::FilterOptions};
-use crate::models::dto::UpdateUserRequest;
+use crate::models::dto::{PagedResponse, UpdateUserRequest, UserResponse};
 use crate::models::error::AppError;
 use crate::models::response::ApiResponse;
+use crate::openapi::{ErrorResponses, MessageResponse};
+use crate::utils::etag;
 use crate::utils::jwt::Claims;
+use crate::extractors::{Json, Query};
 use axum::{
     Extension, //ใช้ดึงข้อมูลจาก Middleware
-    Json,
-    extract::{Query, State},
+    extract::State,
+    http::HeaderMap,
     response::IntoResponse,
 };
 
 //GET /users/me
+#[utoipa::path(
+    get,
+    path = "/users/me",
+    tag = "users",
+    summary = "Get the current user",
+    params(
+        ("If-None-Match" = Option<String>, Header, description = "ETag from the last read, returns 304 if unchanged"),
+    ),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Success", body = ApiResponse<UserResponse>),
+        ErrorResponses,
+    )
+)]
 pub async fn get_me_handler(
     State(state): State<AppState>,
     Extension(claims): Extension<Claims>,
+    headers: HeaderMap,
 ) -> Result<impl IntoResponse, AppError> {
     // let service = UserService::new(state.db);
 
@@ -22,15 +40,52 @@ pub async fn get_me_handler(
     let user_id = claims.get_user_id()?;
 
     let user = state.user_service.get_current_user(user_id).await?;
+    let version = user.version;
 
-    Ok(ApiResponse::success(
-        user,
+    Ok(etag::conditional_get(
+        &headers,
+        version,
+        ApiResponse::success(user, "1000", "profile.get"),
+    ))
+}
+
+//POST /users/me/email/verification (ขอลิงก์ยืนยันอีเมลใหม่)
+#[utoipa::path(
+    post,
+    path = "/users/me/email/verification",
+    tag = "users",
+    summary = "Resend the email verification link",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Success", body = MessageResponse),
+        ErrorResponses,
+    )
+)]
+pub async fn resend_verification_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = claims.get_user_id()?;
+    state.user_service.send_email_verification(user_id).await?;
+
+    Ok(ApiResponse::<()>::success_no_data(
         "1000",
-        "Get profile successfully.",
+        "profile.verification_sent",
     ))
 }
 
 //GET /users
+#[utoipa::path(
+    get,
+    path = "/users/all",
+    tag = "users",
+    summary = "List all users",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Success", body = ApiResponse<Vec<UserResponse>>),
+        ErrorResponses,
+    )
+)]
 pub async fn list_users_handler(
     State(state): State<AppState>,
 ) -> Result<impl IntoResponse, AppError> {
@@ -39,40 +94,91 @@ pub async fn list_users_handler(
     Ok(ApiResponse::success(
         users,
         "1000",
-        "List users successfully.",
+        "users.list",
     ))
 }
 
 //PUT /users/me
+#[utoipa::path(
+    put,
+    path = "/users/me",
+    tag = "users",
+    summary = "Update the current user",
+    params(
+        ("If-Match" = Option<String>, Header, description = "ETag from the last read, rejects the write with 412 if the resource changed"),
+    ),
+    request_body = UpdateUserRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Success", body = ApiResponse<UserResponse>),
+        ErrorResponses,
+    )
+)]
 pub async fn update_me_handler(
     State(state): State<AppState>,
     Extension(claims): Extension<Claims>,
+    headers: HeaderMap,
     Json(payload): Json<UpdateUserRequest>,
 ) -> Result<impl IntoResponse, AppError> {
     let user_id = claims.get_user_id()?;
-    let updated_user = state.user_service.update_user(user_id, payload).await?;
+    let expected_versions = etag::if_match_versions(&headers)?;
+    let updated_user = state
+        .user_service
+        .update_user(user_id, payload, expected_versions)
+        .await?;
+    let version = updated_user.version;
 
-    Ok(ApiResponse::success(
-        updated_user,
-        "1000",
-        "Update profile successfully.",
+    Ok(etag::with_etag(
+        version,
+        ApiResponse::success(updated_user, "1000", "profile.update"),
     ))
 }
 
 //DELETE /users/me
+#[utoipa::path(
+    delete,
+    path = "/users/me",
+    tag = "users",
+    summary = "Delete the current user",
+    params(
+        ("If-Match" = Option<String>, Header, description = "ETag from the last read, rejects the write with 412 if the resource changed"),
+    ),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Success", body = MessageResponse),
+        ErrorResponses,
+    )
+)]
 pub async fn delete_me_handler(
     State(state): State<AppState>,
     Extension(claims): Extension<Claims>,
+    headers: HeaderMap,
 ) -> Result<impl IntoResponse, AppError> {
     let user_id = claims.get_user_id()?;
-    state.user_service.delete_user(user_id).await?;
+    let expected_versions = etag::if_match_versions(&headers)?;
+    state
+        .user_service
+        .delete_user(user_id, expected_versions)
+        .await?;
 
     Ok(ApiResponse::<()>::success_no_data(
         "1000",
-        "User deleted successfully.",
+        "profile.delete",
     ))
 }
 
+#[utoipa::path(
+    get,
+    path = "/users",
+    tag = "users",
+    summary = "Search users with pagination",
+    params(FilterOptions),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Success", body = ApiResponse<PagedResponse<UserResponse>>),
+        ErrorResponses,
+    )
+)]
 pub async fn get_users_handler(
     State(state): State<AppState>,
     Query(opts): Query<FilterOptions>, //Query extractor
@@ -82,6 +188,6 @@ pub async fn get_users_handler(
     Ok(ApiResponse::success(
         response,
         "1000",
-        "Get users list successfully.",
+        "users.page",
     ))
 }
@@ -0,0 +1,133 @@
+use crate::config::AppState;
+use crate::models::dto::CurrencyOptions;
+use crate::models::error::AppError;
+use crate::models::error_code::ErrorCode;
+use crate::utils::money::CurrencyConverter;
+use axum::{
+    async_trait,
+    extract::{
+        FromRequest, FromRequestParts, Request,
+        rejection::{JsonRejection, PathRejection, QueryRejection},
+    },
+    http::{StatusCode, request::Parts},
+};
+use serde::de::DeserializeOwned;
+
+// ใช้แทน axum::Json / Path / Query ใน Controller
+// ตอน parse ไม่ผ่านจะตอบเป็น AppError (envelope {status, data}) แทนข้อความ plain text ของ axum
+
+pub struct Json<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for Json<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        axum::Json::<T>::from_request(req, state)
+            .await
+            .map(|axum::Json(value)| Json(value))
+            .map_err(json_rejection)
+    }
+}
+
+fn json_rejection(rejection: JsonRejection) -> AppError {
+    match rejection {
+        // JSON ถูกรูปแบบแต่ field ขาด/ชนิดไม่ตรง (ข้อความมีชื่อ field เช่น "price: invalid type ...")
+        JsonRejection::JsonDataError(e) => AppError::ValidationError(e.body_text()),
+        JsonRejection::JsonSyntaxError(e) => AppError::Coded(ErrorCode::MalformedJson, e.body_text()),
+        JsonRejection::MissingJsonContentType(_) => AppError::Coded(
+            ErrorCode::MalformedJson,
+            "error.request.json_content_type".into(),
+        ),
+        // อ่าน body ไม่ได้ (ส่วนใหญ่คือเกิน DefaultBodyLimit)
+        JsonRejection::BytesRejection(e) if e.status() == StatusCode::PAYLOAD_TOO_LARGE => {
+            AppError::Coded(ErrorCode::PayloadTooLarge, e.body_text())
+        }
+        other => AppError::Coded(ErrorCode::MalformedJson, other.body_text()),
+    }
+}
+
+pub struct Path<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequestParts<S> for Path<T>
+where
+    T: DeserializeOwned + Send,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        axum::extract::Path::<T>::from_request_parts(parts, state)
+            .await
+            .map(|axum::extract::Path(value)| Path(value))
+            .map_err(path_rejection)
+    }
+}
+
+fn path_rejection(rejection: PathRejection) -> AppError {
+    match rejection {
+        PathRejection::FailedToDeserializePathParams(e) => {
+            AppError::Coded(ErrorCode::InvalidPathParameter, e.body_text())
+        }
+        // route กับ handler ไม่ตรงกัน (bug ฝั่งเรา ไม่ใช่ของ client)
+        other => AppError::InternalServerError(other.body_text()),
+    }
+}
+
+pub struct Query<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequestParts<S> for Query<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        axum::extract::Query::<T>::from_request_parts(parts, state)
+            .await
+            .map(|axum::extract::Query(value)| Query(value))
+            .map_err(query_rejection)
+    }
+}
+
+fn query_rejection(rejection: QueryRejection) -> AppError {
+    // เช่น "Failed to deserialize query string: page: invalid digit found in string"
+    AppError::Coded(ErrorCode::InvalidQueryParameter, rejection.body_text())
+}
+
+pub const CURRENCY_HEADER: &str = "x-currency";
+
+// สกุลเงินที่ client ขอ (?currency=USD หรือ header X-Currency, ไม่ส่ง = สกุลหลักของร้าน)
+pub struct Currency(pub CurrencyConverter);
+
+#[async_trait]
+impl FromRequestParts<AppState> for Currency {
+    type Rejection = AppError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let Query(opts) = Query::<CurrencyOptions>::from_request_parts(parts, state).await?;
+        let requested = opts.currency.or_else(|| {
+            parts
+                .headers
+                .get(CURRENCY_HEADER)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        });
+
+        let converter = state
+            .currency_service
+            .converter(requested.as_deref())
+            .await?;
+        Ok(Currency(converter))
+    }
+}
@@ -0,0 +1,121 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::LazyLock;
+
+// ข้อความที่ส่งให้ client (status.description) เก็บแยกภาษาใน en.toml / th.toml
+// โค้ดส่ง message id (เช่น "profile.get") แล้วแปลตอนสร้าง response ตามภาษาของ request นั้น
+// id ที่ไม่มีใน catalog (ข้อความจาก format! หรือ error ของ library) ส่งกลับไปตามเดิม
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Locale {
+    #[default]
+    En,
+    Th,
+}
+
+impl Locale {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::Th => "th",
+        }
+    }
+
+    // รับทั้ง "th" และ "th-TH" (ไม่สนตัวพิมพ์)
+    pub fn parse(tag: &str) -> Option<Locale> {
+        let language = tag.trim().split(['-', '_']).next()?.to_ascii_lowercase();
+        match language.as_str() {
+            "en" => Some(Locale::En),
+            "th" => Some(Locale::Th),
+            _ => None,
+        }
+    }
+
+    // Accept-Language: "th-TH,th;q=0.9,en;q=0.8" -> เลือกภาษาที่รองรับและมี q สูงสุด
+    pub fn from_accept_language(header: &str) -> Option<Locale> {
+        header
+            .split(',')
+            .filter_map(|part| {
+                let mut pieces = part.split(';');
+                let locale = Locale::parse(pieces.next()?)?;
+                let quality = pieces
+                    .find_map(|p| p.trim().strip_prefix("q="))
+                    .and_then(|q| q.parse::<f32>().ok())
+                    .unwrap_or(1.0);
+                (quality > 0.0).then_some((locale, quality))
+            })
+            // max_by เลือกตัวท้ายเมื่อ q เท่ากัน จึงกลับลำดับก่อนให้ตัวที่มาก่อนชนะ
+            .rev()
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(locale, _)| locale)
+    }
+
+    fn bundle(self) -> &'static HashMap<String, String> {
+        match self {
+            Locale::En => &EN,
+            Locale::Th => &TH,
+        }
+    }
+}
+
+static EN: LazyLock<HashMap<String, String>> = LazyLock::new(|| load(include_str!("en.toml")));
+static TH: LazyLock<HashMap<String, String>> = LazyLock::new(|| load(include_str!("th.toml")));
+
+// [profile] get = "..." -> "profile.get"
+fn load(source: &str) -> HashMap<String, String> {
+    fn flatten(prefix: &str, table: &toml::Table, out: &mut HashMap<String, String>) {
+        for (key, value) in table {
+            let id = if prefix.is_empty() {
+                key.clone()
+            } else {
+                format!("{}.{}", prefix, key)
+            };
+            match value {
+                toml::Value::String(text) => {
+                    out.insert(id, text.clone());
+                }
+                toml::Value::Table(table) => flatten(&id, table, out),
+                _ => panic!("message '{}' must be a string", id),
+            }
+        }
+    }
+
+    let table: toml::Table = source.parse().expect("invalid message catalog");
+    let mut messages = HashMap::new();
+    flatten("", &table, &mut messages);
+    messages
+}
+
+tokio::task_local! {
+    static LOCALE: Locale;
+}
+
+// ภาษาของ request ปัจจุบัน (นอก request เช่น Background job ใช้ภาษาอังกฤษ)
+pub fn current() -> Locale {
+    LOCALE.try_with(|locale| *locale).unwrap_or_default()
+}
+
+// รัน future ทั้งก้อนด้วยภาษาที่กำหนด (ใช้ใน middleware)
+pub async fn scope<F: Future>(locale: Locale, f: F) -> F::Output {
+    LOCALE.scope(locale, f).await
+}
+
+pub fn t(id: &str) -> String {
+    lookup(current(), id).unwrap_or(id).to_string()
+}
+
+// แทน {name} ในข้อความด้วยค่าที่ส่งมา
+pub fn t_args(id: &str, args: &[(&str, &str)]) -> String {
+    args.iter()
+        .fold(t(id), |text, (name, value)| text.replace(&format!("{{{}}}", name), value))
+}
+
+// ไม่มีในภาษาที่เลือกให้ใช้ภาษาอังกฤษแทน
+fn lookup(locale: Locale, id: &str) -> Option<&'static str> {
+    locale
+        .bundle()
+        .get(id)
+        .or_else(|| Locale::En.bundle().get(id))
+        .map(String::as_str)
+}
+
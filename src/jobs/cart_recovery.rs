@@ -0,0 +1,30 @@
+use crate::services::cart_recovery_service::CartRecoveryService;
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+// Background Job: หาตะกร้าที่ถูกทิ้งไว้ทุก ๆ `interval` แล้วส่งลิงก์กลับมาซื้อต่อให้เจ้าของ
+pub fn spawn(
+    cart_recovery_service: CartRecoveryService,
+    interval: Duration,
+    mut shutdown: watch::Receiver<bool>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {}
+                _ = shutdown.changed() => break,
+            }
+
+            match cart_recovery_service.notify_abandoned().await {
+                Ok(0) => {}
+                Ok(count) => tracing::info!(count, "cart recovery notifications sent"),
+                Err(e) => tracing::error!(error = %e, "cart recovery job failed"),
+            }
+        }
+
+        tracing::info!("cart recovery job stopped");
+    })
+}
@@ -0,0 +1,30 @@
+use crate::services::idempotency_service::IdempotencyService;
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+// Background Job: ลบ Idempotency-Key ที่หมดอายุแล้วทุก ๆ `interval`
+pub fn spawn(
+    idempotency_service: IdempotencyService,
+    interval: Duration,
+    mut shutdown: watch::Receiver<bool>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {}
+                _ = shutdown.changed() => break,
+            }
+
+            match idempotency_service.purge_expired().await {
+                Ok(0) => {}
+                Ok(count) => tracing::info!(count, "expired idempotency keys purged"),
+                Err(e) => tracing::error!(error = %e, "idempotency cleanup job failed"),
+            }
+        }
+
+        tracing::info!("idempotency cleanup job stopped");
+    })
+}
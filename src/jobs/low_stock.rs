@@ -0,0 +1,31 @@
+use crate::services::products_service::ProductsService;
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+// Background Job: ตรวจสินค้าใกล้หมดทุก ๆ `interval` แล้วรายงานให้ Admin
+// หยุดเมื่อ `shutdown` เปลี่ยนเป็น true (รอบที่กำลังทำอยู่จะทำจนเสร็จก่อน)
+pub fn spawn(
+    products_service: ProductsService,
+    interval: Duration,
+    mut shutdown: watch::Receiver<bool>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {}
+                _ = shutdown.changed() => break,
+            }
+
+            match products_service.report_low_stock().await {
+                Ok(0) => {}
+                Ok(count) => tracing::info!(count, "low stock report sent"),
+                Err(e) => tracing::error!(error = %e, "low stock job failed"),
+            }
+        }
+
+        tracing::info!("low stock job stopped");
+    })
+}
@@ -0,0 +1,3 @@
+pub mod cart_recovery;
+pub mod idempotency_cleanup;
+pub mod low_stock;
@@ -1,8 +1,13 @@
+mod cli;
 mod config;
 mod constants;
 mod controllers;
+mod extractors;
+mod i18n;
+mod jobs;
 mod middleware;
 mod models;
+mod openapi;
 mod repositories;
 mod routes;
 mod services;
@@ -12,18 +17,40 @@ use axum::{
     Router, middleware as axum_middleware,
     routing::{get, post},
 };
-use config::{AppState, init_db};
+use cli::Command;
+use config::settings::Settings;
+use config::{AppState, init_db, run_migrations};
 use controllers::auth_controller::{login_handler, register_handler};
 use dotenvy::dotenv;
 use middleware::auth::auth_middleware;
 use routes::create_routes;
 use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
+use utils::password_policy::PasswordPolicy;
 
 use meilisearch_sdk::client::Client;
 
+use crate::services::address_service::AddressService;
+use crate::services::auth_service::AuthService;
+use crate::services::cart_recovery_service::CartRecoveryService;
 use crate::services::cart_service::CartService;
 use crate::services::categories_service::CategoriesService;
+use crate::services::currency_service::CurrencyService;
+use crate::services::health_service::HealthService;
+use crate::services::idempotency_service::IdempotencyService;
+use crate::services::notifier::{LogNotifier, Notifier};
+use crate::services::order_service::OrderService;
+use crate::services::payment_gateway::PaymentGateways;
+use crate::services::payment_service::PaymentService;
+use crate::services::password_hasher::{ConfiguredPasswordHasher, PasswordHasher};
 use crate::services::products_service::ProductsService;
+use crate::services::rate_limiter::{InMemoryRateLimitStore, RateLimiter};
+use crate::services::shipping_carrier::ShippingCarriers;
+use crate::services::shipping_service::ShippingService;
+use crate::services::stats_service::StatsService;
+use crate::services::tax_service::TaxService;
 use crate::services::{
     cart_service,
     search_service::{self, SearchService},
@@ -35,40 +62,290 @@ async fn main() {
     //Load Environment Variables
     dotenv().ok();
 
+    let command = match Command::from_args(std::env::args().skip(1)) {
+        Ok(command) => command,
+        Err(e) => {
+            eprintln!("{}\n{}", e, cli::USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    // โหลด Settings ครั้งเดียว ถ้าค่าไม่ครบ/ไม่ถูกต้องให้หยุดทันทีพร้อมบอกว่าผิดตรงไหน
+    let settings = match Settings::load() {
+        Ok(settings) => Arc::new(settings),
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+    // Logging / Tracing (ต้องเก็บ guard ไว้จนจบ main เพื่อ flush span ก่อนออก)
+    let _telemetry = config::telemetry::init(&settings.telemetry);
+    tracing::info!(profile = settings.profile.as_str(), ?command, "settings loaded");
+
+    let result = match command {
+        Command::Serve => serve(settings).await,
+        Command::Migrate => migrate(&settings).await,
+        Command::Reindex => reindex(&settings).await,
+        Command::CreateAdmin { username } => create_admin(&settings, &username).await,
+    };
+
+    if let Err(e) = result {
+        tracing::error!(error = %e, "command failed");
+        std::process::exit(1);
+    }
+}
+
+async fn serve(settings: Arc<Settings>) -> Result<(), String> {
+    // Prometheus recorder (ต้องติดตั้งก่อนเริ่มบันทึก metrics ใด ๆ)
+    let metrics_handle = config::prometheus::init();
+
     // Init Database Connection Pool
-    let pool = init_db().await;
+    let pool = init_db(&settings.database).await;
+    if settings.database.run_migrations {
+        run_migrations(&pool).await.map_err(|e| e.to_string())?;
+        tracing::info!("migrations applied");
+    }
 
-    // เพิ่มส่วนการเชื่อมต่อ Meilisearch
-    let meili_url = std::env::var("MEILISEARCH_URL").expect("MEILISEARCH_URL must be set");
-    let meili_key = std::env::var("MEILISEARCH_API_KEY").expect("MEILISEARCH_API_KEY must be set");
+    let meili_client = meilisearch_client(&settings)?;
 
-    let meili_client = Client::new(meili_url, Some(meili_key))
-        .expect("Failed to create Meilisearch client: Invalid URL");
+    let notifier: Arc<dyn Notifier> = Arc::new(LogNotifier);
+    let rate_limiter = RateLimiter::new(
+        Arc::new(InMemoryRateLimitStore::default()),
+        settings.rate_limit.clone(),
+    );
 
-    let user_service = UserService::new(pool.clone());
+    let auth_service = build_auth_service(&settings, pool.clone(), rate_limiter.clone());
+    let user_service = UserService::new(
+        pool.clone(),
+        notifier.clone(),
+        settings.jwt.clone(),
+        settings.email.clone(),
+    );
+    let address_service = AddressService::new(pool.clone());
     let categories_service = CategoriesService::new(pool.clone());
-    let cart_service = CartService::new(pool.clone());
+    let tax_service = TaxService::new(
+        pool.clone(),
+        settings.payments.currency.clone(),
+        settings.tax.default_country.clone(),
+    );
+    let cart_service = CartService::new(pool.clone(), tax_service.clone());
+    let cart_recovery_service = CartRecoveryService::new(
+        pool.clone(),
+        notifier.clone(),
+        settings.cart_recovery.clone(),
+        settings.jwt.clone(),
+        settings.payments.currency.clone(),
+    );
+    // สกุลเงินหลักของร้าน = สกุลที่ใช้เก็บราคาและรับชำระเงิน
+    let currency_service = CurrencyService::new(pool.clone(), settings.payments.currency.clone());
+    let product_service = ProductsService::new(pool.clone(), notifier);
+    let payment_service = PaymentService::new(
+        pool.clone(),
+        PaymentGateways::from_settings(&settings.payments),
+        product_service.clone(),
+    );
+    let shipping_service = ShippingService::new(
+        pool.clone(),
+        address_service.clone(),
+        ShippingCarriers::default(),
+    );
+    let order_service = OrderService::new(
+        pool.clone(),
+        address_service.clone(),
+        payment_service.clone(),
+        shipping_service.clone(),
+        tax_service,
+        settings.payments.currency.clone(),
+    );
+    let idempotency_service = IdempotencyService::new(pool.clone(), settings.idempotency.clone());
     let search_service = SearchService::new(meili_client.clone());
     if let Err(e) = search_service.setup_settings().await {
-        println!("Warning: Could not setup Meilisearch settings: {:?}", e);
+        tracing::warn!(error = %e, "could not setup meilisearch settings");
     }
-    let product_service = ProductsService::new(pool.clone());
+    let health_service = HealthService::new(
+        pool.clone(),
+        meili_client.clone(),
+        settings.health.clone(),
+    );
+    let stats_service = StatsService::new(
+        pool.clone(),
+        settings.stats.clone(),
+        settings.cart_recovery.idle_hours,
+        settings.payments.currency.clone(),
+    );
+
+    // Background Jobs (หยุดผ่าน shutdown_tx ตอนปิดโปรแกรม)
+    let (shutdown_tx, shutdown_rx) = watch::channel(false);
+    let low_stock_job = jobs::low_stock::spawn(
+        product_service.clone(),
+        Duration::from_secs(settings.jobs.low_stock_interval_secs),
+        shutdown_rx.clone(),
+    );
+    let idempotency_job = jobs::idempotency_cleanup::spawn(
+        idempotency_service.clone(),
+        Duration::from_secs(settings.jobs.idempotency_cleanup_interval_secs),
+        shutdown_rx.clone(),
+    );
+    let cart_recovery_job = jobs::cart_recovery::spawn(
+        cart_recovery_service.clone(),
+        Duration::from_secs(settings.jobs.cart_recovery_interval_secs),
+        shutdown_rx,
+    );
+
     let state = AppState {
-        db: pool,
+        settings: settings.clone(),
+        db: pool.clone(),
         meilisearch: meili_client,
-        user_service: user_service,
-        categories_service: categories_service,
+        metrics: metrics_handle,
+        auth_service,
+        user_service,
+        address_service,
+        categories_service,
         products_service: product_service,
-        cart_service: cart_service,
-        search_service: search_service,
+        cart_service,
+        cart_recovery_service,
+        currency_service,
+        order_service,
+        shipping_service,
+        payment_service,
+        idempotency_service,
+        search_service,
+        health_service,
+        stats_service,
+        rate_limiter,
     };
 
-    let app = create_routes().with_state(state);
+    let app = create_routes(state);
 
     //Start Server
-    let addr = SocketAddr::from(([127, 0, 0, 1], 3000));
-    println!("🚀 Server listening on {}", addr);
+    let addr = settings.bind_address();
+    tracing::info!(%addr, "🚀 server listening");
+
+    let listener = tokio::net::TcpListener::bind(&addr)
+        .await
+        .map_err(|e| format!("Failed to bind {}: {}", addr, e))?;
+
+    // หยุดรับ connection ใหม่เมื่อได้ signal แล้วรอ request ที่ค้างอยู่ให้จบก่อน
+    // ต้องมี ConnectInfo เพื่อให้ rate limit รู้ IP ของ client
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(shutdown_signal())
+    .await
+    .map_err(|e| e.to_string())?;
+
+    tracing::info!("http server drained, stopping background jobs");
+    let _ = shutdown_tx.send(true);
+    let timeout = Duration::from_secs(settings.server.shutdown_timeout_secs);
+    let background_jobs = async {
+        let _ = tokio::join!(low_stock_job, idempotency_job, cart_recovery_job);
+    };
+    if tokio::time::timeout(timeout, background_jobs).await.is_err() {
+        tracing::warn!("background jobs did not stop in time");
+    }
+
+    pool.close().await;
+    tracing::info!("shutdown complete");
+    Ok(())
+}
+
+async fn migrate(settings: &Settings) -> Result<(), String> {
+    let pool = init_db(&settings.database).await;
+    run_migrations(&pool).await.map_err(|e| e.to_string())?;
+    pool.close().await;
+
+    tracing::info!("migrations applied");
+    Ok(())
+}
+
+async fn reindex(settings: &Settings) -> Result<(), String> {
+    let pool = init_db(&settings.database).await;
+    let search_service = SearchService::new(meilisearch_client(settings)?);
+    let product_service = ProductsService::new(pool.clone(), Arc::new(LogNotifier));
+
+    search_service
+        .setup_settings()
+        .await
+        .map_err(|e| e.to_string())?;
+    let indexed = product_service
+        .reindex_search(&search_service)
+        .await
+        .map_err(|e| e.to_string())?;
+    pool.close().await;
+
+    tracing::info!(indexed, "reindex finished");
+    Ok(())
+}
+
+async fn create_admin(settings: &Settings, username: &str) -> Result<(), String> {
+    let password = cli::read_admin_password()?;
+    let pool = init_db(&settings.database).await;
+    // CLI ไม่ผ่าน login จึงใช้ store ชั่วคราวได้
+    let rate_limiter = RateLimiter::new(
+        Arc::new(InMemoryRateLimitStore::default()),
+        settings.rate_limit.clone(),
+    );
+    let auth_service = build_auth_service(settings, pool.clone(), rate_limiter);
+
+    let user_id = auth_service
+        .create_admin(username, &password)
+        .await
+        .map_err(|e| e.to_string())?;
+    pool.close().await;
+
+    tracing::info!(%user_id, username, "admin user created");
+    Ok(())
+}
+
+fn build_auth_service(
+    settings: &Settings,
+    pool: sqlx::PgPool,
+    rate_limiter: RateLimiter,
+) -> AuthService {
+    let hasher: Arc<dyn PasswordHasher> =
+        Arc::new(ConfiguredPasswordHasher::new(settings.password.clone()));
+
+    AuthService::new(
+        pool,
+        settings.jwt.clone(),
+        rate_limiter,
+        hasher,
+        PasswordPolicy::new(&settings.password),
+    )
+}
+
+fn meilisearch_client(settings: &Settings) -> Result<Client, String> {
+    Client::new(
+        settings.meilisearch.url.clone(),
+        Some(settings.meilisearch.api_key.expose().to_string()),
+    )
+    .map_err(|e| format!("Failed to create Meilisearch client: {}", e))
+}
+
+// รอ Ctrl+C (SIGINT) หรือ SIGTERM (จาก Docker / Kubernetes)
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("Failed to install Ctrl+C handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("Failed to install SIGTERM handler")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
 
-    let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
-    axum::serve(listener, app).await.unwrap();
+    tracing::info!("shutdown signal received");
 }
@@ -0,0 +1,19 @@
+use axum::{extract::Request, middleware::Next, response::Response};
+
+use crate::models::error::AppError;
+use crate::utils::jwt::Claims;
+
+// ต้องวางไว้ "ใน" auth_middleware เสมอ (auth ต้องใส่ Claims ให้ก่อน)
+pub async fn admin_middleware(req: Request, next: Next) -> Result<Response, AppError> {
+    let is_admin = req
+        .extensions()
+        .get::<Claims>()
+        .map(|claims| claims.is_admin())
+        .unwrap_or(false);
+
+    if !is_admin {
+        return Err(AppError::Forbidden("error.auth.admin_required".into()));
+    }
+
+    Ok(next.run(req).await)
+}
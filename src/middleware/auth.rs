@@ -1,43 +1,54 @@
 use axum::{
-    extract::Request,
-    http::{header, StatusCode},
+    extract::{Request, State},
+    http::header,
     middleware::Next,
     response::Response,
 };
+use crate::config::AppState;
+use crate::i18n::{self, Locale};
+use crate::models::error::AppError;
+use crate::models::error_code::ErrorCode;
 use crate::utils::jwt::decode_jwt;
 
 pub async fn auth_middleware(
+    State(state): State<AppState>,
     mut req: Request,
     next: Next,
-) -> Result<Response, StatusCode> {
+) -> Result<Response, AppError> {
     //ดึง Header Authorization
     let auth_header = req
         .headers()
         .get(header::AUTHORIZATION)
         .and_then(|header| header.to_str().ok());
 
-    let auth_header = if let Some(auth_header) = auth_header {
-        auth_header
-    } else {
-        return Err(StatusCode::UNAUTHORIZED);
-    };
-
     //เช็คว่าเป็น Bearer token ไหม
-    if !auth_header.starts_with("Bearer ") {
-        return Err(StatusCode::UNAUTHORIZED);
-    }
-
-    let token = &auth_header[7..];
+    let token = auth_header
+        .and_then(|header| header.strip_prefix("Bearer "))
+        .ok_or(AppError::Coded(
+            ErrorCode::MissingToken,
+            "error.auth.missing_token".into(),
+        ))?;
 
     //Validate Token
-    let claims = match decode_jwt(token) {
+    let claims = match decode_jwt(token, &state.settings.jwt) {
         Ok(claims) => claims,
-        Err(_) => return Err(StatusCode::UNAUTHORIZED),
+        Err(_) => {
+            return Err(AppError::Coded(
+                ErrorCode::InvalidToken,
+                "error.auth.invalid_token".into(),
+            ));
+        }
     };
 
+    // ภาษาที่ผู้ใช้ตั้งไว้ชนะ Accept-Language (locale middleware ตั้งค่าจาก header ไว้ก่อนแล้ว)
+    let preferred = claims.locale.as_deref().and_then(Locale::parse);
+
     // (Optional) ใส่ user_id ลงใน Request context เพื่อให้ Controller ใช้ต่อได้
     req.extensions_mut().insert(claims);
 
     // ปล่อยผ่านไป Controller
-    Ok(next.run(req).await)
+    match preferred {
+        Some(locale) => Ok(i18n::scope(locale, next.run(req)).await),
+        None => Ok(next.run(req).await),
+    }
 }
\ No newline at end of file
@@ -0,0 +1,33 @@
+use axum::{
+    extract::{MatchedPath, Request},
+    middleware::Next,
+    response::Response,
+};
+use metrics::Label;
+use std::time::Instant;
+
+// นับ request และจับเวลาแยกตาม route pattern + status
+pub async fn track_metrics(req: Request, next: Next) -> Response {
+    let route = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_owned())
+        .unwrap_or_else(|| "unmatched".to_owned());
+    let method = req.method().to_string();
+    let started = Instant::now();
+
+    let response = next.run(req).await;
+
+    let status = response.status().as_u16().to_string();
+    let labels = vec![
+        Label::new("method", method),
+        Label::new("route", route),
+        Label::new("status", status),
+    ];
+
+    metrics::counter!("http_requests_total", labels.clone()).increment(1);
+    metrics::histogram!("http_request_duration_seconds", labels)
+        .record(started.elapsed().as_secs_f64());
+
+    response
+}
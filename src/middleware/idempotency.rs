@@ -0,0 +1,123 @@
+use axum::{
+    body::{Body, to_bytes},
+    extract::{Request, State},
+    http::{HeaderValue, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::config::AppState;
+use crate::models::entity::IdempotencyKeyEntity;
+use crate::models::error::AppError;
+use crate::models::error_code::ErrorCode;
+use crate::repositories::idempotency_repository::KeyClaim;
+use crate::services::idempotency_service::{IdempotencyClaim, fingerprint};
+use crate::utils::jwt::Claims;
+
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+pub const IDEMPOTENT_REPLAYED_HEADER: &str = "idempotent-replayed";
+
+// ใส่ที่ route ที่สร้างข้อมูลใหม่ (ต้องอยู่ "ใน" auth_middleware เพื่อแยก key ตาม user)
+// ไม่มี header = ทำงานปกติ, key เดิม + body เดิม = ตอบผลเดิม, key เดิม + body ต่าง = 422
+pub async fn idempotency(
+    State(state): State<AppState>,
+    req: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    let Some(value) = req.headers().get(IDEMPOTENCY_KEY_HEADER) else {
+        return Ok(next.run(req).await);
+    };
+    let key = value
+        .to_str()
+        .map_err(|_| AppError::ValidationError("error.request.invalid_idempotency_key".into()))?
+        .to_string();
+
+    let service = state.idempotency_service.clone();
+    service.validate_key(&key)?;
+
+    let scope = req
+        .extensions()
+        .get::<Claims>()
+        .map(|claims| claims.sub.clone())
+        .ok_or(AppError::Coded(ErrorCode::MissingToken, "error.auth.missing_authentication".into()))?;
+    let method = req.method().to_string();
+    // query string เป็นส่วนหนึ่งของ request (เช่น ?coupon=) ต้องนับใน fingerprint ด้วย
+    let path = req
+        .uri()
+        .path_and_query()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| req.uri().path().to_string());
+
+    // ต้องอ่าน body ทั้งก้อนมาทำ fingerprint แล้วค่อยประกอบ request กลับ
+    let (parts, body) = req.into_parts();
+    let body = to_bytes(body, service.max_body_bytes())
+        .await
+        .map_err(|_| AppError::Coded(ErrorCode::PayloadTooLarge, "error.request.body_too_large".into()))?;
+    let request_hash = fingerprint(&method, &path, &body);
+
+    let claim = KeyClaim {
+        scope: &scope,
+        key: &key,
+        method: &method,
+        path: &path,
+        request_hash: &request_hash,
+    };
+    let claim_token = match service.claim(&claim).await? {
+        IdempotencyClaim::Replay(stored) => return Ok(replay(stored)),
+        IdempotencyClaim::New(claim_token) => claim_token,
+    };
+
+    let response = next.run(Request::from_parts(parts, Body::from(body))).await;
+    let status = response.status();
+
+    // 5xx อาจเป็นปัญหาชั่วคราว ไม่จำผลไว้ ให้ retry ด้วย key เดิมแล้วทำงานจริงอีกครั้ง
+    if status.is_server_error() {
+        if let Err(e) = service.release(&scope, &key, claim_token).await {
+            tracing::warn!(error = %e, "failed to release idempotency key");
+        }
+        return Ok(response);
+    }
+
+    let (parts, body) = response.into_parts();
+    let body = match to_bytes(body, usize::MAX).await {
+        Ok(body) => body,
+        Err(e) => {
+            let _ = service.release(&scope, &key, claim_token).await;
+            return Err(AppError::InternalServerError(e.to_string()));
+        }
+    };
+    let content_type = parts
+        .headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok());
+
+    if let Err(e) = service
+        .complete(&scope, &key, claim_token, status.as_u16(), content_type, &body)
+        .await
+    {
+        tracing::warn!(error = %e, "failed to store idempotent response");
+        let _ = service.release(&scope, &key, claim_token).await;
+    }
+
+    Ok(Response::from_parts(parts, Body::from(body)))
+}
+
+fn replay(stored: IdempotencyKeyEntity) -> Response {
+    let status = stored
+        .status_code
+        .and_then(|code| StatusCode::from_u16(code as u16).ok())
+        .unwrap_or(StatusCode::OK);
+
+    let mut response = (status, stored.response_body.unwrap_or_default()).into_response();
+    let headers = response.headers_mut();
+    if let Some(content_type) = stored
+        .content_type
+        .and_then(|value| HeaderValue::from_str(&value).ok())
+    {
+        headers.insert(header::CONTENT_TYPE, content_type);
+    }
+    headers.insert(IDEMPOTENT_REPLAYED_HEADER, HeaderValue::from_static("true"));
+
+    metrics::counter!("idempotent_replays_total").increment(1);
+    response
+}
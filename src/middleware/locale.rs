@@ -0,0 +1,26 @@
+use axum::{
+    extract::Request,
+    http::{HeaderValue, header},
+    middleware::Next,
+    response::Response,
+};
+
+use crate::i18n::{self, Locale};
+
+// เลือกภาษาจาก Accept-Language (ไม่มี/ไม่รองรับ = อังกฤษ) ใช้กับทุกข้อความที่สร้างระหว่าง request นี้
+// ต้องอยู่นอกสุด เพื่อให้ Error จาก middleware อื่น (auth, rate limit) ถูกแปลด้วย
+pub async fn locale(req: Request, next: Next) -> Response {
+    let locale = req
+        .headers()
+        .get(header::ACCEPT_LANGUAGE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(Locale::from_accept_language)
+        .unwrap_or_default();
+
+    let mut response = i18n::scope(locale, next.run(req)).await;
+    // cache ต้องแยกตามภาษา
+    response
+        .headers_mut()
+        .append(header::VARY, HeaderValue::from_static("accept-language"));
+    response
+}
@@ -1 +1,7 @@
-pub mod auth;
\ No newline at end of file
+pub mod admin;
+pub mod auth;
+pub mod http_metrics;
+pub mod idempotency;
+pub mod locale;
+pub mod rate_limit;
+pub mod request_trace;
@@ -0,0 +1,41 @@
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    middleware::Next,
+    response::Response,
+};
+use std::net::{IpAddr, SocketAddr};
+
+use crate::config::AppState;
+use crate::models::error::AppError;
+
+// จำกัดจำนวน request ต่อ IP (ใช้กับ /auth)
+pub async fn auth_rate_limit(
+    State(state): State<AppState>,
+    req: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    if let Some(ip) = client_ip(&req, state.rate_limiter.trust_forwarded_for()) {
+        state.rate_limiter.check_ip(ip).await?;
+    }
+
+    Ok(next.run(req).await)
+}
+
+// X-Forwarded-For ปลอมได้ ใช้เฉพาะเมื่อบอกไว้ว่าอยู่หลัง proxy ที่เชื่อถือได้
+fn client_ip(req: &Request, trust_forwarded_for: bool) -> Option<IpAddr> {
+    if trust_forwarded_for {
+        let forwarded = req
+            .headers()
+            .get("x-forwarded-for")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split(',').next())
+            .and_then(|ip| ip.trim().parse().ok());
+        if forwarded.is_some() {
+            return forwarded;
+        }
+    }
+
+    req.extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip())
+}
@@ -0,0 +1,36 @@
+use axum::{
+    extract::MatchedPath,
+    http::{Request, Response},
+};
+use std::time::Duration;
+use tracing::Span;
+
+// Span ต่อ 1 request (ใช้กับ TraceLayer ของ tower-http)
+// route ใช้ path pattern เช่น /products/:id ไม่ใช่ path จริง เพื่อไม่ให้ cardinality บาน
+pub fn make_request_span<B>(req: &Request<B>) -> Span {
+    let route = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str())
+        .unwrap_or("unmatched");
+    let request_id = req
+        .headers()
+        .get("x-request-id")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("-");
+
+    tracing::info_span!(
+        "http_request",
+        request_id = %request_id,
+        method = %req.method(),
+        route = %route,
+        status = tracing::field::Empty,
+        latency_ms = tracing::field::Empty,
+    )
+}
+
+pub fn on_response<B>(res: &Response<B>, latency: Duration, span: &Span) {
+    span.record("status", res.status().as_u16());
+    span.record("latency_ms", latency.as_millis() as u64);
+    tracing::info!("request completed");
+}
@@ -1,32 +1,100 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use rust_decimal::Decimal;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
 use crate::models::entity::{
-    CartItemDetail, CartWithItems, CategoryEntity, ProductEntity, ProductWithCategory,
+    CartItemDetail, CartRecoveryEntity, CartWithItems, CategoryEntity, ExchangeRateEntity,
+    OrderEntity, OrderItemEntity, PaymentEntity, ProductEntity, ProductWithCategory,
+    UserAddressEntity, UserEntity,
 };
+use crate::models::error::AppError;
+use crate::utils::money::CurrencyConverter;
+use crate::utils::tax::{LineTax, TaxBreakdown, TaxSummary};
+
+// ค่าสำหรับ PATCH ที่แยก "ไม่ได้ส่งมา" กับ "ส่ง null มาเพื่อล้างค่า" ออกจากกัน
+// ใช้กับ field ที่เป็น NULL ได้ใน DB และต้องใส่ #[serde(default)] ที่ field เสมอ
+//   {}                   -> Absent   (ไม่แก้)
+//   {"description": null} -> Null     (ล้างเป็น NULL)
+//   {"description": "x"}  -> Value(x) (ตั้งค่าใหม่)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum Patch<T> {
+    #[default]
+    Absent,
+    Null,
+    Value(T),
+}
+
+impl<T> Patch<T> {
+    // ค่าหลังแก้ เมื่อรู้ค่าเดิมอยู่แล้ว (ใช้ตอนต้อง validate ทั้งก้อนก่อนบันทึก)
+    pub fn apply_to(self, current: Option<T>) -> Option<T> {
+        match self {
+            Patch::Absent => current,
+            Patch::Null => None,
+            Patch::Value(value) => Some(value),
+        }
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Patch<T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        // ถูกเรียกเฉพาะตอนมี key ใน JSON เท่านั้น (ถ้าไม่มี key จะได้ Default = Absent)
+        Ok(match Option::<T>::deserialize(deserializer)? {
+            Some(value) => Patch::Value(value),
+            None => Patch::Null,
+        })
+    }
+}
 
 // Request
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct RegisterRequest {
     pub username: String,
     pub password: String,
+    pub email: String,
+    pub display_name: Option<String>,
+    pub phone: Option<String>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct LoginRequest {
-    pub username: String,
+    #[serde(alias = "email")]
+    pub username: String, // ใส่ username หรืออีเมลก็ได้
     pub password: String,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct UpdateUserRequest {
     pub username: Option<String>,
+    pub email: Option<String>, // เปลี่ยนแล้วต้องยืนยันอีเมลใหม่
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    pub display_name: Patch<String>,
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    pub phone: Patch<String>,
+    // ภาษาของข้อความใน response: "en" / "th" (null = ใช้ตาม Accept-Language)
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    pub locale: Patch<String>,
     // pub password: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct VerifyEmailRequest {
+    pub token: String,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct FilterOptions {
     pub page: Option<usize>,
     pub limit: Option<usize>,
@@ -36,21 +104,40 @@ pub struct FilterOptions {
     pub is_active: Option<bool>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct CategoryRequest {
     pub name: String,
     pub description: Option<String>,
     pub is_active: Option<bool>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct UpdateCategoryRequest {
     pub name: Option<String>,
-    pub description: Option<String>,
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    pub description: Patch<String>,
     pub is_active: Option<bool>,
 }
 
-#[derive(Deserialize)]
+// วิธีจัดการสินค้าในหมวดหมู่ตอนปิดหมวดหมู่
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CategoryDeleteMode {
+    #[default]
+    Restrict, // ปฏิเสธถ้ายังมีสินค้าที่เปิดขายอยู่
+    Deactivate, // ปิดสินค้าทั้งหมดในหมวดหมู่ตามไปด้วย
+    Reassign, // ย้ายสินค้าไปหมวดหมู่อื่น (ต้องส่ง reassign_to)
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DeleteCategoryOptions {
+    pub mode: Option<CategoryDeleteMode>,
+    pub reassign_to: Option<Uuid>,
+}
+
+#[derive(Deserialize, ToSchema)]
 pub struct ProductRequest {
     pub category_id: Uuid,
     pub name: String,
@@ -58,36 +145,85 @@ pub struct ProductRequest {
     pub is_active: Option<bool>,
     pub price: Decimal,
     pub stock: i32,
+    pub low_stock_threshold: Option<i32>,
+    pub tax_class: Option<String>, // ไม่ส่งมา = standard
+    // น้ำหนัก (กรัม) / ขนาด (มม.) ต่อชิ้น สำหรับคิดค่าส่ง
+    pub weight_grams: Option<i32>,
+    pub length_mm: Option<i32>,
+    pub width_mm: Option<i32>,
+    pub height_mm: Option<i32>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct UpdateProductRequest {
     pub category_id: Option<Uuid>,
     pub name: Option<String>,
-    pub description: Option<String>,
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    pub description: Patch<String>,
     pub is_active: Option<bool>,
     pub price: Option<Decimal>,
     pub stock: Option<i32>,
+    pub low_stock_threshold: Option<i32>,
+    pub tax_class: Option<String>,
+    #[serde(default)]
+    #[schema(value_type = Option<i32>)]
+    pub weight_grams: Patch<i32>,
+    #[serde(default)]
+    #[schema(value_type = Option<i32>)]
+    pub length_mm: Patch<i32>,
+    #[serde(default)]
+    #[schema(value_type = Option<i32>)]
+    pub width_mm: Patch<i32>,
+    #[serde(default)]
+    #[schema(value_type = Option<i32>)]
+    pub height_mm: Patch<i32>,
 }
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct LoginResponse {
     pub token: String,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct UserResponse {
     pub id: Uuid,
     pub username: String,
+    pub email: Option<String>,
+    pub email_verified: bool,
+    pub display_name: Option<String>,
+    pub phone: Option<String>,
+    pub locale: Option<String>,
+    pub role: String,
+    pub version: i32,
     pub created_at: DateTime<Utc>,
     pub updated_at: Option<DateTime<Utc>>,
 }
 
-#[derive(Serialize)]
+impl From<UserEntity> for UserResponse {
+    fn from(user: UserEntity) -> Self {
+        Self {
+            id: user.id,
+            username: user.username,
+            email: user.email,
+            email_verified: user.email_verified_at.is_some(),
+            display_name: user.display_name,
+            phone: user.phone,
+            locale: user.locale,
+            role: user.role,
+            version: user.version,
+            created_at: user.created_at,
+            updated_at: user.updated_at,
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema)]
 pub struct CategoryResponse {
     pub id: Uuid,
     pub name: String,
     pub description: Option<String>,
     pub is_active: bool,
+    pub version: i32,
     pub created_at: DateTime<Utc>,
     pub updated_at: Option<DateTime<Utc>>,
 }
@@ -99,24 +235,39 @@ impl From<CategoryEntity> for CategoryResponse {
             name: entity.name,
             description: entity.description,
             is_active: entity.is_active,
+            version: entity.version,
             created_at: entity.created_at,
             updated_at: entity.updated_at,
         }
     }
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct ProductResponse {
     pub id: Uuid,
+    pub sku: Option<String>,
+    pub external_id: Option<String>,
     pub category_id: Uuid,
     pub category_name: String,
     pub name: String,
     pub description: Option<String>,
     pub is_active: bool,
     pub price: Decimal,
+    // สกุลเงินของ price (ตาม ?currency= / X-Currency) และข้อความราคาตามภาษาของ request
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub currency: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub formatted_price: Option<String>,
+    pub tax_class: String,
+    pub weight_grams: Option<i32>,
+    pub length_mm: Option<i32>,
+    pub width_mm: Option<i32>,
+    pub height_mm: Option<i32>,
     pub stock: i32,
+    pub low_stock_threshold: i32,
     pub average_rating: f64,
     pub review_count: i32,
+    pub version: i32,
     pub created_at: DateTime<Utc>,
     pub updated_at: Option<DateTime<Utc>>,
 }
@@ -125,22 +276,43 @@ impl From<ProductWithCategory> for ProductResponse {
     fn from(data: ProductWithCategory) -> Self {
         Self {
             id: data.product.id,
+            sku: data.product.sku,
+            external_id: data.product.external_id,
             category_id: data.product.category_id,
             category_name: data.category_name,
             name: data.product.name,
             description: data.product.description,
             is_active: data.product.is_active,
             price: data.product.price,
+            currency: None,
+            formatted_price: None,
+            tax_class: data.product.tax_class,
+            weight_grams: data.product.weight_grams,
+            length_mm: data.product.length_mm,
+            width_mm: data.product.width_mm,
+            height_mm: data.product.height_mm,
             stock: data.product.stock,
+            low_stock_threshold: data.product.low_stock_threshold,
             average_rating: data.product.average_rating,
             review_count: data.product.review_count,
+            version: data.product.version,
             created_at: data.product.created_at,
             updated_at: data.product.updated_at,
         }
     }
 }
 
-#[derive(Debug, Serialize)]
+impl ProductResponse {
+    // ราคาใน DB เป็นสกุลหลักของร้านเสมอ แปลงตอนตอบกลับเท่านั้น
+    pub fn in_currency(mut self, converter: &CurrencyConverter) -> Result<Self, AppError> {
+        self.price = converter.convert(self.price)?;
+        self.formatted_price = Some(converter.format(self.price));
+        self.currency = Some(converter.currency.clone());
+        Ok(self)
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
 pub struct PagedResponse<T> {
     pub data: Vec<T>,
     pub total: i64,
@@ -149,19 +321,41 @@ pub struct PagedResponse<T> {
     pub total_pages: i64,
 }
 
+impl<T> PagedResponse<T> {
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> PagedResponse<U> {
+        PagedResponse {
+            data: self.data.into_iter().map(f).collect(),
+            total: self.total,
+            page: self.page,
+            limit: self.limit,
+            total_pages: self.total_pages,
+        }
+    }
+
+    pub fn try_map<U, E>(self, f: impl FnMut(T) -> Result<U, E>) -> Result<PagedResponse<U>, E> {
+        Ok(PagedResponse {
+            data: self.data.into_iter().map(f).collect::<Result<_, _>>()?,
+            total: self.total,
+            page: self.page,
+            limit: self.limit,
+            total_pages: self.total_pages,
+        })
+    }
+}
+
 // Cart
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct AddToCartRequest {
     pub product_id: Uuid,
     pub quantity: i32,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct UpdateCartItemRequest {
     pub quantity: i32,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct CartItemResponse {
     pub item_id: Uuid,
     pub product_id: Uuid,
@@ -169,28 +363,685 @@ pub struct CartItemResponse {
     pub price: Decimal,
     pub quantity: i32,
     pub subtotal: Decimal,
+    // ภาษีตามที่อยู่จัดส่ง (inclusive = รวมอยู่ใน subtotal แล้ว)
+    pub tax_class: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tax_name: Option<String>,
+    pub tax_rate: Decimal,
+    pub tax_inclusive: bool,
+    pub tax_amount: Decimal,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct TaxBreakdownResponse {
+    pub name: String,
+    pub rate: Decimal,
+    pub inclusive: bool,
+    pub taxable_amount: Decimal, // ยอดก่อนภาษี
+    pub tax: Decimal,
 }
 
-#[derive(Serialize)]
+impl From<TaxBreakdown> for TaxBreakdownResponse {
+    fn from(entry: TaxBreakdown) -> Self {
+        Self {
+            name: entry.name,
+            rate: entry.rate,
+            inclusive: entry.inclusive,
+            taxable_amount: entry.taxable_amount,
+            tax: entry.tax,
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema)]
 pub struct CartResponse {
     pub id: Uuid,
     pub user_id: Uuid,
     pub items: Vec<CartItemResponse>,
-    pub total_price: Decimal, 
+    pub total_price: Decimal, // ผลรวม subtotal ตามราคาสินค้า
     pub total_items: i32,
+    pub tax_total: Decimal,
+    pub grand_total: Decimal, // ยอดที่ต้องจ่าย = total_price + ภาษีแบบ exclusive
+    pub taxes: Vec<TaxBreakdownResponse>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub currency: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub formatted_total: Option<String>,
+}
+
+impl CartResponse {
+    pub fn apply_tax_summary(&mut self, summary: TaxSummary) {
+        self.tax_total = summary.tax_total;
+        self.grand_total = summary.gross_total;
+        self.taxes = summary.breakdown.into_iter().map(Into::into).collect();
+    }
+
+    // แปลงราคาต่อชิ้นก่อนแล้วค่อยคูณ/รวม ยอดแต่ละบรรทัดจะบวกกันได้ตรงกับยอดรวมเสมอ
+    // ภาษีคิดใหม่จากยอดที่แปลงแล้ว (ปัดตามหลักของสกุลปลายทาง)
+    pub fn in_currency(mut self, converter: &CurrencyConverter) -> Result<Self, AppError> {
+        self.total_price = Decimal::ZERO;
+        let mut lines = Vec::with_capacity(self.items.len());
+        for item in &mut self.items {
+            item.price = converter.convert(item.price)?;
+            item.subtotal = item.price * Decimal::from(item.quantity);
+            self.total_price += item.subtotal;
+
+            let line = LineTax::new(
+                item.subtotal,
+                item.tax_rate,
+                item.tax_inclusive,
+                item.tax_name.clone(),
+                &converter.currency,
+            );
+            item.tax_amount = line.tax;
+            lines.push(line);
+        }
+        self.apply_tax_summary(TaxSummary::from_lines(&lines));
+        self.formatted_total = Some(converter.format(self.grand_total));
+        self.currency = Some(converter.currency.clone());
+        Ok(self)
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct RestockSubscriptionResponse {
+    pub product_id: Uuid,
+    pub subscribed_at: DateTime<Utc>,
+}
+
+// Import / Export สินค้า (CSV และ NDJSON ใช้ field ชุดเดียวกัน)
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum TransferFormat {
+    Csv,
+    Ndjson,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ImportOptions {
+    pub format: Option<TransferFormat>,
+    pub dry_run: Option<bool>,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ExportOptions {
+    pub format: Option<TransferFormat>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProductTransferRow {
+    pub sku: Option<String>,
+    pub external_id: Option<String>,
+    pub category_id: Option<Uuid>,
+    pub category_name: Option<String>,
+    pub name: String,
+    pub description: Option<String>,
+    pub price: Decimal,
+    pub stock: i32,
+    pub is_active: Option<bool>,
+    pub low_stock_threshold: Option<i32>,
+}
+
+impl From<ProductWithCategory> for ProductTransferRow {
+    fn from(data: ProductWithCategory) -> Self {
+        Self {
+            sku: data.product.sku,
+            external_id: data.product.external_id,
+            category_id: Some(data.product.category_id),
+            category_name: Some(data.category_name),
+            name: data.product.name,
+            description: data.product.description,
+            price: data.product.price,
+            stock: data.product.stock,
+            is_active: Some(data.product.is_active),
+            low_stock_threshold: Some(data.product.low_stock_threshold),
+        }
+    }
+}
+
+// แถวที่ผ่าน Validation แล้ว พร้อม Upsert ลง DB
+#[derive(Debug)]
+pub struct ProductImportRecord {
+    pub sku: Option<String>,
+    pub external_id: Option<String>,
+    pub category_id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub price: Decimal,
+    pub stock: i32,
+    pub is_active: bool,
+    pub low_stock_threshold: i32,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ImportRowError {
+    pub row: usize, // เลขแถวของข้อมูล (เริ่มที่ 1 ไม่นับ header)
+    pub errors: Vec<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ImportReport {
+    pub dry_run: bool,
+    pub total_rows: usize,
+    pub valid_rows: usize,
+    pub created: usize,
+    pub updated: usize,
+    pub errors: Vec<ImportRowError>,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct ProductSearchDocument {
     pub id: Uuid,
     pub name: String,
     pub description: String,
+    // เก็บใน index เป็นตัวเลข (ไม่ใช่ string) ไม่งั้น filter/sort ด้วย price ใน Meilisearch ไม่ได้
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schema(value_type = f64)]
     pub price: Decimal,
     pub category_id: Uuid,
     pub image_url: Option<String>,
+    // ใส่เฉพาะตอนตอบผลค้นหา (ไม่ได้เก็บใน index)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub currency: Option<String>,
 }
 
 // Implement trait เพื่อระบุว่า field ไหนคือ ID (Primary Key ใน Meilisearch)
 impl ProductSearchDocument {
     pub const INDEX_NAME: &'static str = "products";
-}
\ No newline at end of file
+}
+
+impl From<ProductEntity> for ProductSearchDocument {
+    fn from(product: ProductEntity) -> Self {
+        Self {
+            id: product.id,
+            name: product.name,
+            description: product.description.unwrap_or_default(),
+            price: product.price,
+            category_id: product.category_id,
+            image_url: None,
+            currency: None,
+        }
+    }
+}
+
+// สิ่งที่ต้องแก้ใน Search Index หลังเปลี่ยนสินค้าหลายตัวพร้อมกัน
+#[derive(Default)]
+pub struct ProductIndexChanges {
+    pub removed: Vec<Uuid>,
+    pub upserted: Vec<ProductSearchDocument>,
+}
+
+// Health Check (/healthz/ready)
+#[derive(Serialize, ToSchema)]
+pub struct DependencyCheck {
+    #[schema(value_type = String)]
+    pub name: &'static str,
+    pub up: bool,
+    pub required: bool,
+    pub latency_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ReadinessReport {
+    pub ready: bool,
+    pub checks: Vec<DependencyCheck>,
+}
+
+// สมุดที่อยู่ (/users/me/addresses)
+#[derive(Deserialize, ToSchema)]
+pub struct AddressRequest {
+    pub label: Option<String>,
+    pub recipient_name: String,
+    pub line1: String,
+    pub line2: Option<String>,
+    pub city: String,
+    pub region: Option<String>, // จังหวัด / รัฐ (บางประเทศบังคับ)
+    pub postal_code: String,
+    pub country: String, // ISO 3166-1 alpha-2
+    pub phone: Option<String>,
+    #[serde(default)]
+    pub is_default_shipping: bool,
+    #[serde(default)]
+    pub is_default_billing: bool,
+}
+
+// ยกเลิก default ตรง ๆ ไม่ได้ ต้องตั้งที่อยู่อื่นเป็น default แทน
+#[derive(Deserialize, ToSchema)]
+pub struct UpdateAddressRequest {
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    pub label: Patch<String>,
+    pub recipient_name: Option<String>,
+    pub line1: Option<String>,
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    pub line2: Patch<String>,
+    pub city: Option<String>,
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    pub region: Patch<String>,
+    pub postal_code: Option<String>,
+    pub country: Option<String>,
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    pub phone: Patch<String>,
+    pub is_default_shipping: Option<bool>,
+    pub is_default_billing: Option<bool>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct AddressResponse {
+    pub id: Uuid,
+    pub label: Option<String>,
+    pub recipient_name: String,
+    pub line1: String,
+    pub line2: Option<String>,
+    pub city: String,
+    pub region: Option<String>,
+    pub postal_code: String,
+    pub country: String,
+    pub phone: Option<String>,
+    pub is_default_shipping: bool,
+    pub is_default_billing: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl From<UserAddressEntity> for AddressResponse {
+    fn from(address: UserAddressEntity) -> Self {
+        Self {
+            id: address.id,
+            label: address.label,
+            recipient_name: address.recipient_name,
+            line1: address.line1,
+            line2: address.line2,
+            city: address.city,
+            region: address.region,
+            postal_code: address.postal_code,
+            country: address.country,
+            phone: address.phone,
+            is_default_shipping: address.is_default_shipping,
+            is_default_billing: address.is_default_billing,
+            created_at: address.created_at,
+            updated_at: address.updated_at,
+        }
+    }
+}
+
+// ที่อยู่ที่ copy ไปเก็บใน Order (JSONB) ตอน Checkout
+// user แก้/ลบที่อยู่ในสมุดทีหลัง Order เดิมต้องไม่เปลี่ยนตาม
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AddressSnapshot {
+    pub recipient_name: String,
+    pub line1: String,
+    pub line2: Option<String>,
+    pub city: String,
+    pub region: Option<String>,
+    pub postal_code: String,
+    pub country: String,
+    pub phone: Option<String>,
+}
+
+impl From<UserAddressEntity> for AddressSnapshot {
+    fn from(address: UserAddressEntity) -> Self {
+        Self {
+            recipient_name: address.recipient_name,
+            line1: address.line1,
+            line2: address.line2,
+            city: address.city,
+            region: address.region,
+            postal_code: address.postal_code,
+            country: address.country,
+            phone: address.phone,
+        }
+    }
+}
+
+// Order / Checkout
+#[derive(Deserialize, ToSchema)]
+pub struct CheckoutRequest {
+    pub shipping_address_id: Uuid,
+    pub billing_address_id: Option<Uuid>, // ไม่ส่งมา = ใช้ที่อยู่เดียวกับที่อยู่จัดส่ง
+    pub shipping_method: Option<String>,   // code จาก GET /cart/shipping-options, ไม่ส่งมา = ถูกที่สุด
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct OrderItemResponse {
+    pub id: Uuid,
+    pub product_id: Option<Uuid>, // None ถ้าสินค้าถูกลบไปแล้ว
+    pub product_name: String,
+    pub unit_price: Decimal,
+    pub quantity: i32,
+    pub line_total: Decimal,
+    pub tax_rate: Decimal,
+    pub tax_inclusive: bool,
+    pub tax_amount: Decimal,
+}
+
+impl From<OrderItemEntity> for OrderItemResponse {
+    fn from(item: OrderItemEntity) -> Self {
+        Self {
+            id: item.id,
+            product_id: item.product_id,
+            product_name: item.product_name,
+            unit_price: item.unit_price,
+            quantity: item.quantity,
+            line_total: item.line_total,
+            tax_rate: item.tax_rate,
+            tax_inclusive: item.tax_inclusive,
+            tax_amount: item.tax_amount,
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct PaymentResponse {
+    pub id: Uuid,
+    pub provider: String,
+    pub provider_payment_id: String,
+    pub status: String,
+    pub amount: Decimal,
+    pub currency: String,
+    pub failure_reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl From<PaymentEntity> for PaymentResponse {
+    fn from(payment: PaymentEntity) -> Self {
+        Self {
+            id: payment.id,
+            provider: payment.provider,
+            provider_payment_id: payment.provider_payment_id,
+            status: payment.status,
+            amount: payment.amount,
+            currency: payment.currency,
+            failure_reason: payment.failure_reason,
+            created_at: payment.created_at,
+            updated_at: payment.updated_at,
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct OrderResponse {
+    pub id: Uuid,
+    pub user_id: Option<Uuid>, // None ถ้าบัญชีถูกลบไปแล้ว
+    pub status: String,
+    pub currency: String,
+    pub subtotal: Decimal,
+    pub tax_total: Decimal,
+    pub shipping_method: Option<String>, // None = Order ก่อนมีการเลือกวิธีจัดส่ง
+    pub shipping_method_name: Option<String>,
+    pub shipping_cost: Decimal,
+    pub total: Decimal, // subtotal + ภาษีแบบ exclusive + shipping_cost
+    #[schema(value_type = AddressSnapshot)]
+    pub shipping_address: serde_json::Value,
+    #[schema(value_type = AddressSnapshot)]
+    pub billing_address: serde_json::Value,
+    pub items: Vec<OrderItemResponse>,
+    pub payments: Vec<PaymentResponse>,
+    pub paid_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl OrderResponse {
+    pub fn new(order: OrderEntity, items: Vec<OrderItemEntity>, payments: Vec<PaymentEntity>) -> Self {
+        Self {
+            id: order.id,
+            user_id: order.user_id,
+            status: order.status,
+            currency: order.currency,
+            subtotal: order.subtotal,
+            tax_total: order.tax_total,
+            shipping_method: order.shipping_method,
+            shipping_method_name: order.shipping_method_name,
+            shipping_cost: order.shipping_cost,
+            total: order.total,
+            shipping_address: order.shipping_address,
+            billing_address: order.billing_address,
+            items: items.into_iter().map(OrderItemResponse::from).collect(),
+            payments: payments.into_iter().map(PaymentResponse::from).collect(),
+            paid_at: order.paid_at,
+            created_at: order.created_at,
+            updated_at: order.updated_at,
+        }
+    }
+}
+
+// ข้อมูลที่ frontend ต้องใช้ยืนยันการจ่ายเงินกับ provider
+#[derive(Serialize, ToSchema)]
+pub struct PaymentIntentResponse {
+    pub payment: PaymentResponse,
+    pub client_secret: String,
+}
+
+// payment = None ถ้าเปิดรายการกับ provider ไม่สำเร็จ (Order ยังจองสต็อกไว้ ลองใหม่ที่ POST /orders/:id/payments)
+#[derive(Serialize, ToSchema)]
+pub struct CheckoutResponse {
+    pub order: OrderResponse,
+    pub payment: Option<PaymentIntentResponse>,
+}
+
+// การจัดส่ง
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ShippingOptionsQuery {
+    /// ที่อยู่จัดส่ง (ไม่ส่งมา = default shipping ของ user)
+    pub address_id: Option<Uuid>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ShippingOptionResponse {
+    pub code: String,
+    pub name: String,
+    pub carrier: String,
+    pub cost: Decimal,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub formatted_cost: Option<String>,
+    pub min_days: Option<i32>,
+    pub max_days: Option<i32>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ShippingOptionsResponse {
+    pub country: String,
+    pub weight_grams: i64, // น้ำหนักที่ใช้คิดค่าส่ง (รวมน้ำหนักตามปริมาตร)
+    pub options: Vec<ShippingOptionResponse>, // เรียงตามที่ร้านตั้งไว้ (sort_order)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub currency: Option<String>,
+}
+
+impl ShippingOptionsResponse {
+    pub fn in_currency(mut self, converter: &CurrencyConverter) -> Result<Self, AppError> {
+        for option in &mut self.options {
+            option.cost = converter.convert(option.cost)?;
+            option.formatted_cost = Some(converter.format(option.cost));
+        }
+        self.currency = Some(converter.currency.clone());
+        Ok(self)
+    }
+}
+
+// สกุลเงิน / อัตราแลกเปลี่ยน
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CurrencyOptions {
+    /// ISO 4217 เช่น USD (ใช้แทน header X-Currency ได้)
+    pub currency: Option<String>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct ExchangeRateRequest {
+    pub rate: Decimal, // จำนวนเงินสกุลนี้ต่อ 1 หน่วยของสกุลหลัก
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ExchangeRateResponse {
+    pub currency: String,
+    pub rate: Decimal,
+    pub updated_by: Option<Uuid>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<ExchangeRateEntity> for ExchangeRateResponse {
+    fn from(rate: ExchangeRateEntity) -> Self {
+        Self {
+            currency: rate.currency,
+            rate: rate.rate,
+            updated_by: rate.updated_by,
+            updated_at: rate.updated_at,
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct CurrencyRatesResponse {
+    pub base_currency: String,
+    pub rates: Vec<ExchangeRateResponse>,
+}
+
+// สถิติสำหรับ Admin (วันที่เป็น UTC, from/to รวมวันนั้นด้วย)
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct StatsRangeQuery {
+    /// วันแรก (ไม่ส่งมา = ย้อนหลังตาม stats.default_range_days)
+    pub from: Option<NaiveDate>,
+    /// วันสุดท้าย (ไม่ส่งมา = วันนี้)
+    pub to: Option<NaiveDate>,
+    /// จำนวนอันดับ (ใช้กับ top-products, ค่าเริ่มต้น 10)
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, ToSchema)]
+pub struct StatsRange {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+}
+
+#[derive(Clone, Serialize, ToSchema)]
+pub struct DailyCount {
+    pub date: NaiveDate,
+    pub count: i64,
+}
+
+#[derive(Clone, Serialize, ToSchema)]
+pub struct SignupStatsResponse {
+    pub range: StatsRange,
+    pub total: i64,
+    pub days: Vec<DailyCount>,
+}
+
+// ตะกร้าที่มีของและมีการหยิบ/แก้สินค้าในช่วงวันที่ (มูลค่าตามราคาปัจจุบัน)
+#[derive(Clone, Serialize, ToSchema)]
+pub struct CartStatsResponse {
+    pub range: StatsRange,
+    pub currency: String,
+    pub active_carts: i64,
+    pub total_items: i64,
+    pub total_value: Decimal,
+}
+
+#[derive(Clone, Serialize, ToSchema)]
+pub struct TopProductResponse {
+    pub product_id: Uuid,
+    pub product_name: String,
+    pub additions: i64, // จำนวนครั้งที่ถูกหยิบใส่ตะกร้า
+    pub quantity: i64,  // จำนวนชิ้นรวม
+}
+
+#[derive(Clone, Serialize, ToSchema)]
+pub struct CategoryProductCount {
+    pub category_id: Uuid,
+    pub category_name: String,
+    pub is_active: bool,
+    pub product_count: i64,
+    pub active_product_count: i64,
+}
+
+#[derive(Clone, Serialize, ToSchema)]
+pub struct DailyRevenue {
+    pub date: NaiveDate,
+    pub orders: i64,
+    pub revenue: Decimal,
+}
+
+// product/category ที่ถูกลบไปแล้วจะเป็น None
+#[derive(Clone, Serialize, ToSchema)]
+pub struct CategoryRevenue {
+    pub category_id: Option<Uuid>,
+    pub category_name: Option<String>,
+    pub quantity: i64,
+    pub revenue: Decimal,
+}
+
+// รายได้จาก Order ที่จ่ายแล้ว (ไม่รวมที่คืนเงิน) ตามวันที่จ่าย
+// days ใช้ยอด total ของ Order, categories ใช้ยอด line_total ของสินค้า (ไม่รวมค่าส่ง/ภาษีแบบ exclusive)
+#[derive(Clone, Serialize, ToSchema)]
+pub struct RevenueStatsResponse {
+    pub range: StatsRange,
+    pub currency: String,
+    pub order_count: i64,
+    pub total_revenue: Decimal,
+    pub days: Vec<DailyRevenue>,
+    pub categories: Vec<CategoryRevenue>,
+}
+
+
+// ตะกร้าที่ถูกทิ้ง: ตอนนี้ = ตะกร้าที่มีของและไม่เคลื่อนไหวเกิน cart_recovery.idle_hours (ไม่ขึ้นกับช่วงวันที่)
+// ที่เหลือนับจากการแจ้งเตือนที่ส่งในช่วงวันที่ (recovered_value = ยอด Order ที่เกิดหลังแจ้งเตือน)
+#[derive(Clone, Serialize, ToSchema)]
+pub struct AbandonedCartStatsResponse {
+    pub range: StatsRange,
+    pub currency: String,
+    pub abandoned_carts: i64,
+    pub abandoned_value: Decimal,
+    pub notified: i64,
+    pub notified_value: Decimal,
+    pub clicked: i64,
+    pub converted: i64,
+    pub recovered_value: Decimal,
+    pub conversion_rate: Decimal, // converted / notified
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CartRecoveryQuery {
+    /// token จากลิงก์ในข้อความแจ้งเตือน
+    pub token: String,
+}
+
+// ผลของการเปิดลิงก์กู้ตะกร้า (client พาไปหน้าตะกร้าต่อหลัง login)
+#[derive(Serialize, ToSchema)]
+pub struct CartRecoveryResponse {
+    pub recovery_id: Uuid,
+    pub item_count: i32,
+    pub cart_value: Decimal, // มูลค่าตอนแจ้งเตือน
+    pub currency: String,
+    pub cart_updated_at: DateTime<Utc>, // ตะกร้าเปลี่ยนล่าสุดก่อนแจ้งเตือน
+    pub notified_at: DateTime<Utc>,
+    pub clicked_at: Option<DateTime<Utc>>, // เปิดลิงก์ครั้งแรก
+    pub converted: bool,
+    pub order_id: Option<Uuid>, // Order ที่เกิดจากการแจ้งเตือนนี้
+}
+
+impl From<CartRecoveryEntity> for CartRecoveryResponse {
+    fn from(recovery: CartRecoveryEntity) -> Self {
+        Self {
+            recovery_id: recovery.id,
+            item_count: recovery.item_count,
+            cart_value: recovery.cart_value,
+            currency: recovery.currency,
+            cart_updated_at: recovery.cart_updated_at,
+            notified_at: recovery.created_at,
+            clicked_at: recovery.clicked_at,
+            converted: recovery.converted_at.is_some(),
+            order_id: recovery.order_id,
+        }
+    }
+}
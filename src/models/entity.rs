@@ -11,6 +11,13 @@ pub struct UserEntity {
     pub password_hash: String,
     pub created_at: DateTime<Utc>,
     pub updated_at: Option<DateTime<Utc>>,
+    pub role: String,
+    pub version: i32,
+    pub email: Option<String>,
+    pub email_verified_at: Option<DateTime<Utc>>,
+    pub display_name: Option<String>,
+    pub phone: Option<String>,
+    pub locale: Option<String>,
 }
 
 #[derive(Debug, FromRow, Serialize, Deserialize)]
@@ -21,6 +28,7 @@ pub struct CategoryEntity {
     pub is_active: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: Option<DateTime<Utc>>,
+    pub version: i32,
 }
 
 #[derive(Debug, FromRow, Serialize, Deserialize)]
@@ -36,6 +44,16 @@ pub struct ProductEntity {
     pub is_active: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: Option<DateTime<Utc>>,
+    pub low_stock_threshold: i32,
+    pub sku: Option<String>,
+    pub external_id: Option<String>,
+    pub version: i32,
+    pub deactivated_via_category: bool,
+    pub tax_class: String,
+    pub weight_grams: Option<i32>,
+    pub length_mm: Option<i32>,
+    pub width_mm: Option<i32>,
+    pub height_mm: Option<i32>,
 }
 
 #[derive(sqlx::FromRow)]
@@ -46,6 +64,16 @@ pub struct ProductWithCategory {
     pub category_name: String,
 }
 
+// ผลลัพธ์จาก Upsert ตอน Import (inserted = แถวใหม่, previous_stock = สต็อกก่อนอัปเดต)
+#[derive(sqlx::FromRow)]
+pub struct UpsertedProduct {
+    #[sqlx(flatten)]
+    pub product: ProductEntity,
+
+    pub inserted: bool,
+    pub previous_stock: Option<i32>,
+}
+
 #[derive(Debug, FromRow, Serialize, Deserialize)]
 pub struct CartsEntity {
     pub id: Uuid,
@@ -78,4 +106,164 @@ pub struct CartItemDetail {
     pub product_name: String,
     pub price: Decimal,
     pub quantity: i32,
+    pub tax_class: String,
+}
+
+#[derive(Debug, FromRow, Serialize, Deserialize)]
+pub struct RestockSubscriptionEntity {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub product_id: Uuid,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, FromRow)]
+pub struct UserAddressEntity {
+    pub id: Uuid,
+    pub label: Option<String>,
+    pub recipient_name: String,
+    pub line1: String,
+    pub line2: Option<String>,
+    pub city: String,
+    pub region: Option<String>,
+    pub postal_code: String,
+    pub country: String,
+    pub phone: Option<String>,
+    pub is_default_shipping: bool,
+    pub is_default_billing: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, FromRow)]
+pub struct OrderEntity {
+    pub id: Uuid,
+    pub user_id: Option<Uuid>,
+    pub status: String,
+    pub currency: String,
+    pub subtotal: Decimal,
+    pub total: Decimal,
+    pub shipping_address: serde_json::Value,
+    pub billing_address: serde_json::Value,
+    pub paid_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub tax_total: Decimal,
+    pub shipping_method: Option<String>,
+    pub shipping_method_name: Option<String>,
+    pub shipping_cost: Decimal,
+}
+
+#[derive(Debug, FromRow)]
+pub struct OrderItemEntity {
+    pub id: Uuid,
+    pub order_id: Uuid,
+    pub product_id: Option<Uuid>,
+    pub product_name: String,
+    pub unit_price: Decimal,
+    pub quantity: i32,
+    pub line_total: Decimal,
+    pub tax_rate: Decimal,
+    pub tax_inclusive: bool,
+    pub tax_amount: Decimal,
+}
+
+#[derive(Debug, FromRow)]
+pub struct PaymentEntity {
+    pub id: Uuid,
+    pub order_id: Uuid,
+    pub provider: String,
+    pub provider_payment_id: String,
+    pub status: String,
+    pub amount: Decimal,
+    pub currency: String,
+    pub failure_reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+// ราคา/สต็อกปัจจุบันของสินค้าในตะกร้า (ล็อกแถวสินค้าไว้ตอน Checkout)
+#[derive(Debug, FromRow)]
+pub struct CheckoutLine {
+    pub product_id: Uuid,
+    pub product_name: String,
+    pub price: Decimal,
+    pub stock: i32,
+    pub is_active: bool,
+    pub quantity: i32,
+    pub tax_class: String,
+}
+
+// สินค้าในตะกร้าพร้อมน้ำหนัก/ขนาด สำหรับ quote ค่าส่ง
+#[derive(Debug, FromRow)]
+pub struct ShipmentLine {
+    pub product_id: Uuid,
+    pub price: Decimal,
+    pub quantity: i32,
+    pub weight_grams: Option<i32>,
+    pub length_mm: Option<i32>,
+    pub width_mm: Option<i32>,
+    pub height_mm: Option<i32>,
+}
+
+// แถวเดิมของ key (เฉพาะคอลัมน์ที่ใช้ตัดสินว่าตอบซ้ำได้หรือไม่)
+#[derive(Debug, FromRow)]
+pub struct IdempotencyKeyEntity {
+    pub request_hash: String,
+    pub status_code: Option<i16>,
+    pub content_type: Option<String>,
+    pub response_body: Option<Vec<u8>>,
+}
+
+#[derive(Debug, FromRow)]
+pub struct ExchangeRateEntity {
+    pub currency: String,
+    pub rate: Decimal,
+    pub updated_by: Option<Uuid>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, FromRow)]
+pub struct TaxRateEntity {
+    pub tax_class: String,
+    pub region: Option<String>,
+    pub name: String,
+    pub rate: Decimal,
+    pub inclusive: bool,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct ShippingMethodEntity {
+    pub id: Uuid,
+    pub code: String,
+    pub name: String,
+    pub carrier: String,
+    pub rate_type: String,
+    pub base_cost: Decimal,
+    pub free_threshold: Option<Decimal>,
+    pub min_days: Option<i32>,
+    pub max_days: Option<i32>,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct ShippingWeightTierEntity {
+    pub method_id: Uuid,
+    pub up_to_grams: i32,
+    pub cost: Decimal,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct CartRecoveryEntity {
+    pub id: Uuid,
+    pub cart_id: Uuid,
+    pub user_id: Uuid,
+    pub item_count: i32,
+    pub cart_value: Decimal,
+    pub currency: String,
+    pub cart_updated_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub clicked_at: Option<DateTime<Utc>>,
+    pub converted_at: Option<DateTime<Utc>>,
+    pub order_id: Option<Uuid>,
 }
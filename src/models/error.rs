@@ -1,41 +1,130 @@
+use crate::i18n;
+use crate::models::error_code::ErrorCode;
 use axum::{
-    http::StatusCode,
+    http::header,
     response::{IntoResponse, Response},
     Json,
 };
 use serde_json::json;
+use std::fmt;
 
 // สร้าง Enum เพื่อรวม Error ทุกประเภทในระบบ
 #[derive(Debug)]
 pub enum AppError {
     AuthError(String),
+    Forbidden(String),
     NotFound(String),
+    Conflict(String),
     DatabaseError(String),
     InternalServerError(String),
     ValidationError(String),
+    PreconditionFailed(String),
+    UnprocessableEntity(String),
+    TooManyRequests(String, u64), // ข้อความ, จำนวนวินาทีที่ต้องรอ (Retry-After)
+    Coded(ErrorCode, String),     // ใช้เมื่อต้องการรหัสที่ละเอียดกว่าตัวทั่วไปข้างบน
+}
+
+impl AppError {
+    // ชื่อประเภท error (ใช้ใน log / metrics)
+    pub fn kind(&self) -> &'static str {
+        match self {
+            AppError::AuthError(_) => "auth_error",
+            AppError::Forbidden(_) => "forbidden",
+            AppError::NotFound(_) => "not_found",
+            AppError::Conflict(_) => "conflict",
+            AppError::DatabaseError(_) => "database_error",
+            AppError::InternalServerError(_) => "internal_server_error",
+            AppError::ValidationError(_) => "validation_error",
+            AppError::PreconditionFailed(_) => "precondition_failed",
+            AppError::UnprocessableEntity(_) => "unprocessable_entity",
+            AppError::TooManyRequests(..) => "too_many_requests",
+            AppError::Coded(code, _) => code.as_str(),
+        }
+    }
+
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            AppError::AuthError(_) => ErrorCode::Unauthorized,
+            AppError::Forbidden(_) => ErrorCode::Forbidden,
+            AppError::NotFound(_) => ErrorCode::NotFound,
+            AppError::Conflict(_) => ErrorCode::Conflict,
+            AppError::DatabaseError(_) => ErrorCode::DatabaseError,
+            AppError::InternalServerError(_) => ErrorCode::InternalError,
+            AppError::ValidationError(_) => ErrorCode::ValidationFailed,
+            AppError::PreconditionFailed(_) => ErrorCode::PreconditionFailed,
+            AppError::UnprocessableEntity(_) => ErrorCode::UnprocessableEntity,
+            AppError::TooManyRequests(..) => ErrorCode::TooManyRequests,
+            AppError::Coded(code, _) => *code,
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            AppError::AuthError(msg)
+            | AppError::Forbidden(msg)
+            | AppError::NotFound(msg)
+            | AppError::Conflict(msg)
+            | AppError::DatabaseError(msg)
+            | AppError::InternalServerError(msg)
+            | AppError::ValidationError(msg)
+            | AppError::PreconditionFailed(msg)
+            | AppError::UnprocessableEntity(msg)
+            | AppError::TooManyRequests(msg, _)
+            | AppError::Coded(_, msg) => msg,
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.kind(), self.message())
+    }
 }
 
 // บอก Axum ว่า Error แต่ละตัวคือ HTTP Status Code อะไร
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
-        let (status_code, app_code, message) = match self {
-            AppError::AuthError(msg) => (StatusCode::UNAUTHORIZED, "4001", msg),
-            AppError::NotFound(msg) => (StatusCode::NOT_FOUND, "4004", msg),
-            AppError::ValidationError(msg) => (StatusCode::BAD_REQUEST, "4000", msg),
-            AppError::DatabaseError(msg) => (StatusCode::INTERNAL_SERVER_ERROR, "5001", format!("Database error: {}", msg)),
-            AppError::InternalServerError(msg) => (StatusCode::INTERNAL_SERVER_ERROR, "5000", msg),
+        metrics::counter!("app_errors_total", "kind" => self.kind()).increment(1);
+
+        if self.code().http_status().is_server_error() {
+            tracing::error!(error = %self, "request failed");
+        }
+
+        let retry_after = match &self {
+            AppError::TooManyRequests(_, secs) => Some(*secs),
+            _ => None,
+        };
+
+        let code = self.code();
+        let message = match self {
+            AppError::DatabaseError(msg) => format!("Database error: {}", msg),
+            AppError::AuthError(msg)
+            | AppError::Forbidden(msg)
+            | AppError::NotFound(msg)
+            | AppError::Conflict(msg)
+            | AppError::InternalServerError(msg)
+            | AppError::ValidationError(msg)
+            | AppError::PreconditionFailed(msg)
+            | AppError::UnprocessableEntity(msg)
+            | AppError::TooManyRequests(msg, _)
+            | AppError::Coded(_, msg) => i18n::t(&msg),
         };
 
-        // สร้าง JSON ให้ตรงกับ Format ที่ต้องการ
+        // สร้าง JSON ให้ตรงกับ Format ที่ต้องการ (error คือชื่อรหัส ดูทั้งหมดที่ GET /meta/error-codes)
+        let status_code = code.http_status();
         let body = Json(json!({
             "status": {
-                "code": app_code,
+                "code": code.code().to_string(),
+                "error": code.as_str(),
                 "description": message
             },
             "data": null
         }));
 
-        (status_code, body).into_response()
+        match retry_after {
+            Some(secs) => (status_code, [(header::RETRY_AFTER, secs.to_string())], body).into_response(),
+            None => (status_code, body).into_response(),
+        }
     }
 }
 
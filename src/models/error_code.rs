@@ -0,0 +1,159 @@
+use axum::http::StatusCode;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+// รหัส Error ที่ client ใช้ตัดสินใจได้ (ห้ามเปลี่ยนเลข/ชื่อของตัวที่มีอยู่แล้ว เพิ่มใหม่ได้อย่างเดียว)
+// เลข 4xxx/5xxx ตัวท้ายตรงกับ HTTP status สำหรับรหัสทั่วไป ส่วน 41xx/42xx เป็นรหัสเฉพาะที่ละเอียดขึ้น
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCode {
+    ValidationFailed,
+    Unauthorized,
+    Forbidden,
+    NotFound,
+    Conflict,
+    PreconditionFailed,
+    PayloadTooLarge,
+    UnprocessableEntity,
+    TooManyRequests,
+    MalformedJson,
+    InvalidPathParameter,
+    InvalidQueryParameter,
+    MissingToken,
+    InvalidToken,
+    InternalError,
+    DatabaseError,
+    ServiceUnavailable,
+}
+
+impl ErrorCode {
+    pub const ALL: [ErrorCode; 17] = [
+        ErrorCode::ValidationFailed,
+        ErrorCode::Unauthorized,
+        ErrorCode::Forbidden,
+        ErrorCode::NotFound,
+        ErrorCode::Conflict,
+        ErrorCode::PreconditionFailed,
+        ErrorCode::PayloadTooLarge,
+        ErrorCode::UnprocessableEntity,
+        ErrorCode::TooManyRequests,
+        ErrorCode::MalformedJson,
+        ErrorCode::InvalidPathParameter,
+        ErrorCode::InvalidQueryParameter,
+        ErrorCode::MissingToken,
+        ErrorCode::InvalidToken,
+        ErrorCode::InternalError,
+        ErrorCode::DatabaseError,
+        ErrorCode::ServiceUnavailable,
+    ];
+
+    // เลขที่ส่งใน status.code
+    pub fn code(self) -> u16 {
+        match self {
+            ErrorCode::ValidationFailed => 4000,
+            ErrorCode::Unauthorized => 4001,
+            ErrorCode::Forbidden => 4003,
+            ErrorCode::NotFound => 4004,
+            ErrorCode::Conflict => 4009,
+            ErrorCode::PreconditionFailed => 4012,
+            ErrorCode::PayloadTooLarge => 4013,
+            ErrorCode::UnprocessableEntity => 4022,
+            ErrorCode::TooManyRequests => 4029,
+            ErrorCode::MalformedJson => 4101,
+            ErrorCode::InvalidPathParameter => 4102,
+            ErrorCode::InvalidQueryParameter => 4103,
+            ErrorCode::MissingToken => 4201,
+            ErrorCode::InvalidToken => 4202,
+            ErrorCode::InternalError => 5000,
+            ErrorCode::DatabaseError => 5001,
+            ErrorCode::ServiceUnavailable => 5003,
+        }
+    }
+
+    // ชื่อที่ส่งใน status.error
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorCode::ValidationFailed => "VALIDATION_FAILED",
+            ErrorCode::Unauthorized => "UNAUTHORIZED",
+            ErrorCode::Forbidden => "FORBIDDEN",
+            ErrorCode::NotFound => "NOT_FOUND",
+            ErrorCode::Conflict => "CONFLICT",
+            ErrorCode::PreconditionFailed => "PRECONDITION_FAILED",
+            ErrorCode::PayloadTooLarge => "PAYLOAD_TOO_LARGE",
+            ErrorCode::UnprocessableEntity => "UNPROCESSABLE_ENTITY",
+            ErrorCode::TooManyRequests => "TOO_MANY_REQUESTS",
+            ErrorCode::MalformedJson => "MALFORMED_JSON",
+            ErrorCode::InvalidPathParameter => "INVALID_PATH_PARAMETER",
+            ErrorCode::InvalidQueryParameter => "INVALID_QUERY_PARAMETER",
+            ErrorCode::MissingToken => "MISSING_TOKEN",
+            ErrorCode::InvalidToken => "INVALID_TOKEN",
+            ErrorCode::InternalError => "INTERNAL_ERROR",
+            ErrorCode::DatabaseError => "DATABASE_ERROR",
+            ErrorCode::ServiceUnavailable => "SERVICE_UNAVAILABLE",
+        }
+    }
+
+    pub fn http_status(self) -> StatusCode {
+        match self {
+            ErrorCode::ValidationFailed
+            | ErrorCode::MalformedJson
+            | ErrorCode::InvalidPathParameter
+            | ErrorCode::InvalidQueryParameter => StatusCode::BAD_REQUEST,
+            ErrorCode::Unauthorized | ErrorCode::MissingToken | ErrorCode::InvalidToken => {
+                StatusCode::UNAUTHORIZED
+            }
+            ErrorCode::Forbidden => StatusCode::FORBIDDEN,
+            ErrorCode::NotFound => StatusCode::NOT_FOUND,
+            ErrorCode::Conflict => StatusCode::CONFLICT,
+            ErrorCode::PreconditionFailed => StatusCode::PRECONDITION_FAILED,
+            ErrorCode::PayloadTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+            ErrorCode::UnprocessableEntity => StatusCode::UNPROCESSABLE_ENTITY,
+            ErrorCode::TooManyRequests => StatusCode::TOO_MANY_REQUESTS,
+            ErrorCode::InternalError | ErrorCode::DatabaseError => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+            ErrorCode::ServiceUnavailable => StatusCode::SERVICE_UNAVAILABLE,
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            ErrorCode::ValidationFailed => "Request data is invalid",
+            ErrorCode::Unauthorized => "Authentication failed",
+            ErrorCode::Forbidden => "Permission denied",
+            ErrorCode::NotFound => "Resource not found",
+            ErrorCode::Conflict => "Resource already exists or is in the wrong state",
+            ErrorCode::PreconditionFailed => "If-Match does not match the current version",
+            ErrorCode::PayloadTooLarge => "Request body is too large",
+            ErrorCode::UnprocessableEntity => "Idempotency-Key was already used with a different request",
+            ErrorCode::TooManyRequests => "Too many requests, retry after the Retry-After header",
+            ErrorCode::MalformedJson => "Request body is not valid JSON for this endpoint",
+            ErrorCode::InvalidPathParameter => "A path parameter has the wrong format",
+            ErrorCode::InvalidQueryParameter => "A query parameter has the wrong format",
+            ErrorCode::MissingToken => "Authorization: Bearer token is required",
+            ErrorCode::InvalidToken => "Access token is invalid or expired",
+            ErrorCode::InternalError => "Internal server error",
+            ErrorCode::DatabaseError => "Database error",
+            ErrorCode::ServiceUnavailable => "A required dependency is unavailable",
+        }
+    }
+}
+
+// GET /meta/error-codes
+#[derive(Serialize, ToSchema)]
+pub struct ErrorCodeInfo {
+    pub code: u16,
+    pub error: &'static str,
+    pub http_status: u16,
+    pub description: &'static str,
+}
+
+impl From<ErrorCode> for ErrorCodeInfo {
+    fn from(code: ErrorCode) -> Self {
+        Self {
+            code: code.code(),
+            error: code.as_str(),
+            http_status: code.http_status().as_u16(),
+            description: code.description(),
+        }
+    }
+}
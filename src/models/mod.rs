@@ -1,4 +1,5 @@
 pub mod dto;
 pub mod entity;
 pub mod error;
+pub mod error_code;
 pub mod response;
\ No newline at end of file
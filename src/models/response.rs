@@ -1,24 +1,30 @@
+use crate::i18n;
+use crate::models::error_code::ErrorCode;
 use serde::Serialize;
+use utoipa::ToSchema;
 use axum::{
     http::StatusCode,
     response::{IntoResponse, Response},
     Json,
 };
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct Status {
     pub code: String,
+    #[serde(skip_serializing_if = "Option::is_none")] // มีเฉพาะตอน Error (ชื่อของ code เช่น NOT_FOUND)
+    pub error: Option<String>,
     pub description: String,
 }
 
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct ApiResponse<T> {
     pub status: Status,
     #[serde(skip_serializing_if = "Option::is_none")] // ถ้าไม่มี data ไม่ต้องส่ง field นี้ไป
     pub data: Option<T>,
 }
 
+// description รับเป็น message id (ดู i18n/en.toml) แล้วแปลตามภาษาของ request
 impl<T> ApiResponse<T>
 where
     T: Serialize,
@@ -28,7 +34,8 @@ where
         Self {
             status: Status {
                 code: code.to_string(),
-                description: description.to_string(),
+                error: None,
+                description: i18n::t(description),
             },
             data: Some(data),
         }
@@ -39,11 +46,24 @@ where
         Self {
             status: Status {
                 code: code.to_string(),
-                description: description.to_string(),
+                error: None,
+                description: i18n::t(description),
             },
             data: None,
         }
     }
+
+    // มี data แต่เป็นผลลัพธ์ที่ไม่สำเร็จ (เช่น รายงาน Import ที่มีแถวผิด, Readiness ที่ยังไม่พร้อม)
+    pub fn failure(data: T, code: ErrorCode, description: &str) -> Self {
+        Self {
+            status: Status {
+                code: code.code().to_string(),
+                error: Some(code.as_str().to_string()),
+                description: i18n::t(description),
+            },
+            data: Some(data),
+        }
+    }
 }
 
 // Implement IntoResponse เพื่อให้ Controller return struct นี้ออกไปได้เลย
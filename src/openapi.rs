@@ -0,0 +1,302 @@
+use crate::controllers::{
+    address_controller, auth_controller, cart_controller, categories_controller,
+    currency_controller, health_controller, meta_controller, metrics_controller,
+    order_controller, payment_controller, products_controller, stats_controller,
+    user_controller,
+};
+use crate::models::error_code::ErrorCode;
+use crate::models::response::Status;
+use serde::Serialize;
+use std::sync::LazyLock;
+use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{IntoResponses, Modify, OpenApi, ToSchema};
+
+// Response ที่ไม่มี data (success_no_data) และ Error ทุกตัวใช้รูปแบบเดียวกัน
+#[derive(Serialize, ToSchema)]
+pub struct MessageResponse {
+    pub status: Status,
+}
+
+// Error ของ AppError (แยกประเภทด้วย status.code / status.error ดูตารางใน description ของ API)
+#[allow(dead_code)]
+#[derive(IntoResponses)]
+pub enum ErrorResponses {
+    #[response(status = "4XX", description = "Client error: validation, auth, not found, conflict, rate limit")]
+    ClientError(MessageResponse),
+    #[response(status = "5XX", description = "Server error")]
+    ServerError(MessageResponse),
+}
+
+struct SecurityAddon;
+
+impl Modify for SecurityAddon {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer_auth",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .bearer_format("JWT")
+                    .build(),
+            ),
+        );
+    }
+}
+
+// ต่อท้าย description ด้วยตารางรหัส Error จาก ErrorCode (ไม่ต้องแก้เอกสารแยกเมื่อเพิ่มรหัสใหม่)
+struct ErrorCodesAddon;
+
+impl Modify for ErrorCodesAddon {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let mut table = String::from(
+            "\n\n| code | error | HTTP | description |\n|------|-------|------|-------------|\n",
+        );
+        for code in ErrorCode::ALL {
+            table.push_str(&format!(
+                "| {} | {} | {} | {} |\n",
+                code.code(),
+                code.as_str(),
+                code.http_status().as_u16(),
+                code.description()
+            ));
+        }
+
+        let description = openapi.info.description.get_or_insert_with(String::new);
+        description.push_str(&table);
+    }
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Shop API",
+        description = "ทุก response อยู่ในรูป `{\"status\": {\"code\", \"error\", \"description\"}, \"data\"}` \
+            (`code` 1000 = สำเร็จ, `error` มีเฉพาะตอนไม่สำเร็จ) \
+            ภาษาของ `description` เลือกจาก `locale` ของผู้ใช้ หรือ header `Accept-Language` (en / th)"
+    ),
+    paths(
+        auth_controller::register_handler,
+        auth_controller::login_handler,
+        auth_controller::verify_email_link_handler,
+        auth_controller::verify_email_handler,
+        user_controller::get_me_handler,
+        user_controller::update_me_handler,
+        user_controller::delete_me_handler,
+        user_controller::resend_verification_handler,
+        user_controller::list_users_handler,
+        user_controller::get_users_handler,
+        address_controller::list_addresses_handler,
+        address_controller::create_address_handler,
+        address_controller::get_address_handler,
+        address_controller::update_address_handler,
+        address_controller::delete_address_handler,
+        categories_controller::get_categories_handler,
+        categories_controller::create_categories_handler,
+        categories_controller::get_category_handler,
+        categories_controller::update_categories_handler,
+        categories_controller::delete_category_handler,
+        categories_controller::restore_category_handler,
+        products_controller::list_products_handler,
+        products_controller::create_product_handler,
+        products_controller::get_product_handler,
+        products_controller::update_product_handler,
+        products_controller::delete_product_handler,
+        products_controller::restore_product_handler,
+        products_controller::subscribe_restock_handler,
+        products_controller::unsubscribe_restock_handler,
+        products_controller::search_products_handler,
+        products_controller::sync_products_handler,
+        products_controller::import_products_handler,
+        products_controller::export_products_handler,
+        cart_controller::get_cart_handler,
+        cart_controller::add_to_cart_handler,
+        cart_controller::update_cart_item_handler,
+        cart_controller::remove_cart_item_handler,
+        cart_controller::shipping_options_handler,
+        cart_controller::recover_cart_handler,
+        order_controller::checkout_handler,
+        order_controller::list_orders_handler,
+        order_controller::get_order_handler,
+        order_controller::retry_payment_handler,
+        order_controller::refund_order_handler,
+        currency_controller::list_rates_handler,
+        currency_controller::set_rate_handler,
+        currency_controller::delete_rate_handler,
+        stats_controller::signups_handler,
+        stats_controller::carts_handler,
+        stats_controller::top_products_handler,
+        stats_controller::categories_handler,
+        stats_controller::revenue_handler,
+        stats_controller::abandoned_carts_handler,
+        payment_controller::webhook_handler,
+        health_controller::live_handler,
+        health_controller::ready_handler,
+        metrics_controller::metrics_handler,
+        meta_controller::error_codes_handler,
+    ),
+    modifiers(&SecurityAddon, &ErrorCodesAddon),
+    tags(
+        (name = "auth", description = "สมัครสมาชิก / Login / ยืนยันอีเมล"),
+        (name = "users", description = "ข้อมูลผู้ใช้"),
+        (name = "addresses", description = "สมุดที่อยู่"),
+        (name = "categories", description = "หมวดหมู่สินค้า"),
+        (name = "products", description = "สินค้าและการค้นหา"),
+        (name = "cart", description = "ตะกร้าสินค้า"),
+        (name = "orders", description = "คำสั่งซื้อและการชำระเงิน"),
+        (name = "payments", description = "Webhook จาก Payment provider"),
+        (name = "admin", description = "สำหรับ Admin เท่านั้น"),
+        (name = "health", description = "Health check"),
+        (name = "metrics", description = "Prometheus"),
+        (name = "meta", description = "ข้อมูลประกอบสำหรับ client"),
+    )
+)]
+pub struct ApiDoc;
+
+// สร้างครั้งเดียวแล้วใช้ซ้ำ (ไม่เปลี่ยนระหว่างรัน)
+pub static OPENAPI: LazyLock<utoipa::openapi::OpenApi> = LazyLock::new(ApiDoc::openapi);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::{BTreeSet, HashMap};
+
+    // Route ที่ตั้งใจไม่ใส่ในเอกสาร
+    const UNDOCUMENTED: [&str; 2] = [
+        "GET /healthz",       // alias เดิมของ /healthz/live
+        "GET /openapi.json", // ตัวเอกสารเอง
+    ];
+
+    // หา string literal ตัวแรกหลังตำแหน่ง start
+    fn first_literal(src: &str, start: usize) -> (String, usize) {
+        let open = start + src[start..].find('"').unwrap();
+        let close = open + 1 + src[open + 1..].find('"').unwrap();
+        (src[open + 1..close].to_string(), close + 1)
+    }
+
+    // ตำแหน่งหลังวงเล็บปิดที่คู่กับวงเล็บเปิดที่ open
+    fn matching_paren(src: &str, open: usize) -> usize {
+        let mut depth = 0;
+        let mut in_string = false;
+        for (i, c) in src[open..].char_indices() {
+            match c {
+                '"' => in_string = !in_string,
+                '(' if !in_string => depth += 1,
+                ')' if !in_string => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return open + i + 1;
+                    }
+                }
+                _ => {}
+            }
+        }
+        panic!("unbalanced parentheses in routes.rs");
+    }
+
+    // อ่าน route ทั้งหมดจาก routes.rs: "METHOD /path" (แปลง :id เป็น {id} แบบ OpenAPI)
+    fn routes_in_source() -> BTreeSet<String> {
+        let src = include_str!("routes.rs");
+
+        let mut prefixes: HashMap<String, String> = HashMap::new();
+        let mut pos = 0;
+        while let Some(found) = src[pos..].find(".nest(") {
+            let (prefix, after) = first_literal(src, pos + found);
+            let rest = src[after..].trim_start_matches([',', ' ']);
+            let name = rest[..rest.find('(').unwrap()].to_string();
+            prefixes.insert(name, prefix);
+            pos = after;
+        }
+
+        let mut routes = BTreeSet::new();
+        let fns: Vec<(usize, &str)> = src.match_indices("\nfn ").collect();
+        let mut starts: Vec<(usize, String)> = fns
+            .iter()
+            .map(|(i, _)| {
+                let name_start = i + 4;
+                let name_end = name_start + src[name_start..].find('(').unwrap();
+                (*i, src[name_start..name_end].to_string())
+            })
+            .collect();
+        if let Some(create) = src.find("pub fn create_routes") {
+            starts.push((create, "create_routes".to_string()));
+        }
+        starts.sort();
+
+        for (index, (start, name)) in starts.iter().enumerate() {
+            let end = starts.get(index + 1).map(|(i, _)| *i).unwrap_or(src.len());
+            let body = &src[*start..end];
+            let prefix = prefixes.get(name).cloned().unwrap_or_default();
+
+            let mut pos = 0;
+            while let Some(found) = body[pos..].find(".route(") {
+                let open = pos + found + ".route".len();
+                let close = matching_paren(body, open);
+                let (path, after_path) = first_literal(body, open);
+                let handlers = &body[after_path..close];
+
+                let full = match (prefix.as_str(), path.as_str()) {
+                    (prefix, "/") if !prefix.is_empty() => prefix.to_string(),
+                    (prefix, path) => format!("{}{}", prefix, path),
+                };
+                let full = full
+                    .split('/')
+                    .map(|segment| match segment.strip_prefix(':') {
+                        Some(param) => format!("{{{}}}", param),
+                        None => segment.to_string(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join("/");
+
+                for method in ["get", "post", "put", "patch", "delete"] {
+                    let call = format!("{}(", method);
+                    let used = handlers.match_indices(&call).any(|(i, _)| {
+                        i == 0 || !handlers[..i].ends_with(|c: char| c.is_alphanumeric() || c == '_')
+                    });
+                    if used {
+                        routes.insert(format!("{} {}", method.to_uppercase(), full));
+                    }
+                }
+                pos = close;
+            }
+        }
+        routes
+    }
+
+    fn documented() -> BTreeSet<String> {
+        let json = serde_json::to_value(&*OPENAPI).unwrap();
+        let mut routes = BTreeSet::new();
+        for (path, item) in json["paths"].as_object().unwrap() {
+            for method in item.as_object().unwrap().keys() {
+                routes.insert(format!("{} {}", method.to_uppercase(), path));
+            }
+        }
+        routes
+    }
+
+    #[test]
+    fn every_route_is_documented() {
+        let documented = documented();
+        let missing: Vec<String> = routes_in_source()
+            .into_iter()
+            .filter(|route| !documented.contains(route) && !UNDOCUMENTED.contains(&route.as_str()))
+            .collect();
+
+        assert!(
+            missing.is_empty(),
+            "routes without #[utoipa::path] (add them to ApiDoc): {:?}",
+            missing
+        );
+    }
+
+    #[test]
+    fn documented_routes_exist() {
+        let routes = routes_in_source();
+        let stale: Vec<String> = documented()
+            .into_iter()
+            .filter(|route| !routes.contains(route))
+            .collect();
+
+        assert!(stale.is_empty(), "documented routes not in routes.rs: {:?}", stale);
+    }
+}
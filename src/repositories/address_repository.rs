@@ -0,0 +1,264 @@
+use crate::models::{dto::AddressRequest, entity::UserAddressEntity};
+use sqlx::{Pool, Postgres, Transaction};
+use tracing::instrument;
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct AddressRepository {
+    pool: Pool<Postgres>,
+}
+
+impl AddressRepository {
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+
+    // ล็อกแถว user ไว้ก่อนแก้ default เพื่อให้ request พร้อมกันของ user เดียวกันเข้าคิว
+    async fn lock_user(tx: &mut Transaction<'_, Postgres>, user_id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!("SELECT id FROM users WHERE id = $1 FOR UPDATE", user_id)
+            .fetch_one(&mut **tx)
+            .await?;
+        Ok(())
+    }
+
+    async fn clear_defaults(
+        tx: &mut Transaction<'_, Postgres>,
+        user_id: Uuid,
+        shipping: bool,
+        billing: bool,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE user_addresses
+            SET is_default_shipping = is_default_shipping AND NOT $2,
+                is_default_billing = is_default_billing AND NOT $3,
+                updated_at = NOW()
+            WHERE user_id = $1
+              AND ((is_default_shipping AND $2) OR (is_default_billing AND $3))
+            "#,
+            user_id,
+            shipping,
+            billing
+        )
+        .execute(&mut **tx)
+        .await?;
+        Ok(())
+    }
+
+    #[instrument(skip_all, err)]
+    pub async fn list(&self, user_id: Uuid) -> Result<Vec<UserAddressEntity>, sqlx::Error> {
+        sqlx::query_as!(
+            UserAddressEntity,
+            r#"
+            SELECT id, label, recipient_name, line1, line2, city, region, postal_code, country, phone,
+                   is_default_shipping, is_default_billing, created_at, updated_at
+            FROM user_addresses
+            WHERE user_id = $1
+            ORDER BY is_default_shipping DESC, is_default_billing DESC, created_at DESC
+            "#,
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    #[instrument(skip_all, err)]
+    pub async fn find(
+        &self,
+        user_id: Uuid,
+        address_id: Uuid,
+    ) -> Result<Option<UserAddressEntity>, sqlx::Error> {
+        sqlx::query_as!(
+            UserAddressEntity,
+            r#"
+            SELECT id, label, recipient_name, line1, line2, city, region, postal_code, country, phone,
+                   is_default_shipping, is_default_billing, created_at, updated_at
+            FROM user_addresses
+            WHERE id = $1 AND user_id = $2
+            "#,
+            address_id,
+            user_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    #[instrument(skip_all, err)]
+    pub async fn find_default_shipping(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Option<UserAddressEntity>, sqlx::Error> {
+        sqlx::query_as!(
+            UserAddressEntity,
+            r#"
+            SELECT id, label, recipient_name, line1, line2, city, region, postal_code, country, phone,
+                   is_default_shipping, is_default_billing, created_at, updated_at
+            FROM user_addresses
+            WHERE user_id = $1 AND is_default_shipping
+            "#,
+            user_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    // ที่อยู่แรกของ user จะเป็น default ทั้งสองแบบเสมอ
+    #[instrument(skip_all, err)]
+    pub async fn create(
+        &self,
+        user_id: Uuid,
+        address: &AddressRequest,
+    ) -> Result<UserAddressEntity, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        Self::lock_user(&mut tx, user_id).await?;
+
+        let existing = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) as "count!" FROM user_addresses WHERE user_id = $1"#,
+            user_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let default_shipping = address.is_default_shipping || existing == 0;
+        let default_billing = address.is_default_billing || existing == 0;
+        Self::clear_defaults(&mut tx, user_id, default_shipping, default_billing).await?;
+
+        let created = sqlx::query_as!(
+            UserAddressEntity,
+            r#"
+            INSERT INTO user_addresses
+            (user_id, label, recipient_name, line1, line2, city, region, postal_code, country, phone, is_default_shipping, is_default_billing)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            RETURNING id, label, recipient_name, line1, line2, city, region, postal_code, country, phone,
+                      is_default_shipping, is_default_billing, created_at, updated_at
+            "#,
+            user_id,
+            address.label,
+            address.recipient_name,
+            address.line1,
+            address.line2,
+            address.city,
+            address.region,
+            address.postal_code,
+            address.country,
+            address.phone,
+            default_shipping,
+            default_billing
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(created)
+    }
+
+    // `address` คือค่าหลังรวมกับของเดิมแล้ว (is_default_* = true คือขอเป็น default)
+    #[instrument(skip_all, err)]
+    pub async fn update(
+        &self,
+        user_id: Uuid,
+        address_id: Uuid,
+        address: &AddressRequest,
+    ) -> Result<Option<UserAddressEntity>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        Self::lock_user(&mut tx, user_id).await?;
+        Self::clear_defaults(
+            &mut tx,
+            user_id,
+            address.is_default_shipping,
+            address.is_default_billing,
+        )
+        .await?;
+
+        let updated = sqlx::query_as!(
+            UserAddressEntity,
+            r#"
+            UPDATE user_addresses
+            SET label = $3,
+                recipient_name = $4,
+                line1 = $5,
+                line2 = $6,
+                city = $7,
+                region = $8,
+                postal_code = $9,
+                country = $10,
+                phone = $11,
+                is_default_shipping = $12,
+                is_default_billing = $13,
+                updated_at = NOW()
+            WHERE id = $1 AND user_id = $2
+            RETURNING id, label, recipient_name, line1, line2, city, region, postal_code, country, phone,
+                      is_default_shipping, is_default_billing, created_at, updated_at
+            "#,
+            address_id,
+            user_id,
+            address.label,
+            address.recipient_name,
+            address.line1,
+            address.line2,
+            address.city,
+            address.region,
+            address.postal_code,
+            address.country,
+            address.phone,
+            address.is_default_shipping,
+            address.is_default_billing
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        // ไม่เจอที่อยู่: rollback (default เดิมที่ถูกล้างไปจะกลับมา)
+        if updated.is_some() {
+            tx.commit().await?;
+        }
+        Ok(updated)
+    }
+
+    // ลบ default แล้วยกที่อยู่ล่าสุดที่เหลือขึ้นมาเป็น default แทน
+    #[instrument(skip_all, err)]
+    pub async fn delete(&self, user_id: Uuid, address_id: Uuid) -> Result<bool, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        Self::lock_user(&mut tx, user_id).await?;
+
+        let deleted = sqlx::query!(
+            r#"
+            DELETE FROM user_addresses
+            WHERE id = $1 AND user_id = $2
+            RETURNING is_default_shipping, is_default_billing
+            "#,
+            address_id,
+            user_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(deleted) = deleted else {
+            return Ok(false);
+        };
+
+        if deleted.is_default_shipping || deleted.is_default_billing {
+            sqlx::query!(
+                r#"
+                UPDATE user_addresses
+                SET is_default_shipping = is_default_shipping OR $2,
+                    is_default_billing = is_default_billing OR $3,
+                    updated_at = NOW()
+                WHERE id = (
+                    SELECT id FROM user_addresses
+                    WHERE user_id = $1
+                    ORDER BY created_at DESC
+                    LIMIT 1
+                )
+                "#,
+                user_id,
+                deleted.is_default_shipping,
+                deleted.is_default_billing
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(true)
+    }
+}
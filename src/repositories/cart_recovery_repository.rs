@@ -0,0 +1,109 @@
+use crate::models::entity::CartRecoveryEntity;
+use sqlx::{Pool, Postgres};
+use tracing::instrument;
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct CartRecoveryRepository {
+    pool: Pool<Postgres>,
+}
+
+impl CartRecoveryRepository {
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+
+    // จองตะกร้าที่มีของแต่ไม่เคลื่อนไหวมา idle_hours (แต่ไม่เกิน max_idle_hours) และยังไม่เคยแจ้งรอบนี้
+    // SKIP LOCKED + NOT EXISTS ใน statement เดียว หลาย replica รันพร้อมกันก็ไม่แจ้งซ้ำ
+    #[instrument(skip_all, err)]
+    pub async fn claim_idle_carts(
+        &self,
+        idle_hours: i64,
+        max_idle_hours: i64,
+        limit: i64,
+        currency: &str,
+        ttl_hours: i64,
+    ) -> Result<Vec<CartRecoveryEntity>, sqlx::Error> {
+        sqlx::query_as!(
+            CartRecoveryEntity,
+            r#"
+            WITH idle AS (
+                SELECT c.id, c.user_id, c.updated_at
+                FROM carts c
+                WHERE c.updated_at < NOW() - make_interval(hours => $1::int4)
+                  AND c.updated_at >= NOW() - make_interval(hours => $2::int4)
+                  AND EXISTS (SELECT 1 FROM cart_items ci WHERE ci.cart_id = c.id)
+                  AND NOT EXISTS (
+                      SELECT 1 FROM cart_recoveries r
+                      WHERE r.cart_id = c.id AND r.cart_updated_at >= c.updated_at
+                  )
+                ORDER BY c.updated_at
+                LIMIT $3
+                FOR UPDATE OF c SKIP LOCKED
+            )
+            INSERT INTO cart_recoveries
+                (cart_id, user_id, item_count, cart_value, currency, cart_updated_at, expires_at)
+            SELECT
+                idle.id,
+                idle.user_id,
+                SUM(ci.quantity)::int4,
+                SUM(p.price * ci.quantity),
+                $4,
+                idle.updated_at,
+                NOW() + make_interval(hours => $5::int4)
+            FROM idle
+            JOIN cart_items ci ON ci.cart_id = idle.id
+            JOIN products p ON p.id = ci.product_id
+            GROUP BY idle.id, idle.user_id, idle.updated_at
+            RETURNING *
+            "#,
+            idle_hours as i32,
+            max_idle_hours as i32,
+            limit,
+            currency,
+            ttl_hours as i32
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    // ภาษาที่เจ้าของตะกร้าตั้งไว้ (None = ยังไม่ตั้ง / ไม่พบผู้ใช้)
+    #[instrument(skip_all, err)]
+    pub async fn user_locale(&self, user_id: Uuid) -> Result<Option<String>, sqlx::Error> {
+        let locale = sqlx::query_scalar!("SELECT locale FROM users WHERE id = $1", user_id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(locale.flatten())
+    }
+
+    // ส่งแจ้งเตือนไม่สำเร็จ: ลบทิ้งให้รอบถัดไปจองใหม่ได้
+    #[instrument(skip_all, err)]
+    pub async fn delete(&self, id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!("DELETE FROM cart_recoveries WHERE id = $1", id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    // บันทึกเวลาที่เปิดลิงก์ครั้งแรก (เปิดซ้ำไม่ทับ)
+    #[instrument(skip_all, err)]
+    pub async fn mark_clicked(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<CartRecoveryEntity>, sqlx::Error> {
+        sqlx::query_as!(
+            CartRecoveryEntity,
+            r#"
+            UPDATE cart_recoveries
+            SET clicked_at = COALESCE(clicked_at, NOW())
+            WHERE id = $1 AND user_id = $2
+            RETURNING *
+            "#,
+            id,
+            user_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+}
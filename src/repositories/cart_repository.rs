@@ -1,5 +1,6 @@
-use crate::models::entity::{CartItemDetail, CartsEntity};
+use crate::models::entity::{CartItemDetail, CartsEntity, ShipmentLine};
 use sqlx::{Pool, Postgres};
+use tracing::instrument;
 use uuid::Uuid;
 
 #[derive(Clone)]
@@ -12,6 +13,7 @@ impl CartRepository {
         Self { pool }
     }
 
+    #[instrument(skip_all, err)]
     pub async fn get_or_create_cart_id(&self, user_id: Uuid) -> Result<Uuid, sqlx::Error> {
         let cart = sqlx::query_as!(
             CartsEntity,
@@ -30,10 +32,12 @@ impl CartRepository {
             )
             .fetch_one(&self.pool)
             .await?;
+            metrics::counter!("shop_carts_created_total").increment(1);
             Ok(new_cart.id)
         }
     }
 
+    #[instrument(skip_all, err)]
     pub async fn find_cart_items(&self, cart_id: Uuid) -> Result<Vec<CartItemDetail>, sqlx::Error> {
         sqlx::query_as!(
             CartItemDetail,
@@ -43,7 +47,8 @@ impl CartRepository {
                 ci.product_id,
                 p.name as product_name,
                 p.price as "price: rust_decimal::Decimal",
-                ci.quantity
+                ci.quantity,
+                p.tax_class
             FROM cart_items ci
             JOIN products p ON ci.product_id = p.id
             WHERE ci.cart_id = $1
@@ -55,6 +60,35 @@ impl CartRepository {
         .await
     }
 
+    // เรียงตาม product_id ให้ตรงกับลำดับที่ Checkout ล็อกสินค้า
+    #[instrument(skip_all, err)]
+    pub async fn find_shipment_lines(&self, user_id: Uuid) -> Result<Vec<ShipmentLine>, sqlx::Error> {
+        sqlx::query_as!(
+            ShipmentLine,
+            r#"
+            SELECT
+                p.id as product_id,
+                p.price as "price: rust_decimal::Decimal",
+                ci.quantity,
+                p.weight_grams,
+                p.length_mm,
+                p.width_mm,
+                p.height_mm
+            FROM cart_items ci
+            JOIN carts c ON c.id = ci.cart_id
+            JOIN products p ON p.id = ci.product_id
+            WHERE c.user_id = $1
+            ORDER BY p.id
+            "#,
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    // เก็บประวัติใน cart_additions ไปด้วยใน statement เดียว (ใช้ทำสถิติสินค้าที่ถูกหยิบบ่อย)
+    // ทุกการแก้ตะกร้าแตะ carts.updated_at ด้วย (ใช้หาตะกร้าที่ถูกทิ้งไว้)
+    #[instrument(skip_all, err)]
     pub async fn upsert_item(
         &self,
         cart_id: Uuid,
@@ -63,12 +97,20 @@ impl CartRepository {
     ) -> Result<(), sqlx::Error> {
         sqlx::query!(
             r#"
-            INSERT INTO cart_items (cart_id, product_id, quantity)
-            VALUES ($1, $2, $3)
-            ON CONFLICT (cart_id, product_id) 
-            DO UPDATE SET 
-                quantity = cart_items.quantity + $3,
-                updated_at = NOW()
+            WITH item AS (
+                INSERT INTO cart_items (cart_id, product_id, quantity)
+                VALUES ($1, $2, $3)
+                ON CONFLICT (cart_id, product_id) 
+                DO UPDATE SET 
+                    quantity = cart_items.quantity + $3,
+                    updated_at = NOW()
+                RETURNING cart_id, product_id
+            ),
+            touched AS (
+                UPDATE carts SET updated_at = NOW() WHERE id IN (SELECT cart_id FROM item)
+            )
+            INSERT INTO cart_additions (cart_id, product_id, quantity)
+            SELECT cart_id, product_id, $3 FROM item
             "#,
             cart_id,
             product_id,
@@ -79,15 +121,25 @@ impl CartRepository {
         Ok(())
     }
 
+    #[instrument(skip_all, err)]
     pub async fn update_item_quantity(
         &self,
+        cart_id: Uuid,
         item_id: Uuid,
         quantity: i32,
     ) -> Result<bool, sqlx::Error> {
         let result = sqlx::query!(
-            "UPDATE cart_items SET quantity = $1, updated_at = NOW() WHERE id = $2",
+            r#"
+            WITH item AS (
+                UPDATE cart_items SET quantity = $1, updated_at = NOW()
+                WHERE id = $2 AND cart_id = $3
+                RETURNING cart_id
+            )
+            UPDATE carts SET updated_at = NOW() WHERE id IN (SELECT cart_id FROM item)
+            "#,
             quantity,
-            item_id
+            item_id,
+            cart_id
         )
         .execute(&self.pool)
         .await?;
@@ -95,10 +147,21 @@ impl CartRepository {
         Ok(result.rows_affected() > 0)
     }
 
-    pub async fn delete_item(&self, item_id: Uuid) -> Result<bool, sqlx::Error> {
-        let result = sqlx::query!("DELETE FROM cart_items WHERE id = $1", item_id)
-            .execute(&self.pool)
-            .await?;
+    #[instrument(skip_all, err)]
+    pub async fn delete_item(&self, cart_id: Uuid, item_id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"
+            WITH item AS (
+                DELETE FROM cart_items WHERE id = $1 AND cart_id = $2
+                RETURNING cart_id
+            )
+            UPDATE carts SET updated_at = NOW() WHERE id IN (SELECT cart_id FROM item)
+            "#,
+            item_id,
+            cart_id
+        )
+        .execute(&self.pool)
+        .await?;
 
         Ok(result.rows_affected() > 0)
     }
@@ -1,11 +1,20 @@
 use crate::models::{
     dto::{CategoryRequest, FilterOptions, UpdateCategoryRequest},
-    entity::CategoryEntity,
+    entity::{CategoryEntity, ProductEntity},
 };
+use crate::repositories::update_builder::UpdateBuilder;
 use axum::extract::Query;
 use sqlx::{Pool, Postgres, QueryBuilder};
+use tracing::instrument;
 use uuid::Uuid;
 
+// ผลของการปิดหมวดหมู่แบบ restrict
+pub enum RestrictedDelete {
+    Deleted,
+    NotUpdated, // ไม่เจอ หรือ version ไม่ตรง
+    HasActiveProducts(i64),
+}
+
 #[derive(Clone)]
 pub struct CategoriesRepository {
     pool: Pool<Postgres>,
@@ -16,6 +25,7 @@ impl CategoriesRepository {
         Self { pool }
     }
 
+    #[instrument(skip_all, err)]
     pub async fn create_category(
         &self,
         req: CategoryRequest,
@@ -35,6 +45,7 @@ impl CategoriesRepository {
         .await
     }
 
+    #[instrument(skip_all, err)]
     pub async fn list_all(
         &self,
         opts: FilterOptions,
@@ -103,6 +114,7 @@ impl CategoriesRepository {
         Ok((categories, total))
     }
 
+    #[instrument(skip_all, err)]
     pub async fn find_by_id(
         &self,
         categories_id: Uuid,
@@ -116,45 +128,235 @@ impl CategoriesRepository {
         .await
     }
 
+    #[instrument(skip_all, err)]
     pub async fn update_categories(
         &self,
         categories_id: Uuid,
         data: UpdateCategoryRequest,
+        expected_versions: Option<Vec<i32>>,
     ) -> Result<CategoryEntity, sqlx::Error> {
-        sqlx::query_as!(
-            CategoryEntity,
+        let mut update = UpdateBuilder::new("categories");
+        update
+            .set_if("name", data.name)
+            .set_patch("description", data.description)
+            .set_if("is_active", data.is_active)
+            .set_expr("version", "version + 1")
+            .set_expr("updated_at", "NOW()");
+
+        let mut qb = update.into_query();
+        qb.push(" WHERE id = ");
+        qb.push_bind(categories_id);
+        if let Some(versions) = expected_versions {
+            qb.push(" AND version = ANY(");
+            qb.push_bind(versions);
+            qb.push(")");
+        }
+        qb.push(" RETURNING *");
+
+        qb.build_query_as::<CategoryEntity>()
+            .fetch_one(&self.pool)
+            .await
+    }
+
+    // ปิดหมวดหมู่เฉพาะตอนไม่มีสินค้าที่เปิดขายอยู่ (mode=restrict)
+    // ล็อกแถวหมวดหมู่ก่อนนับ: สินค้าที่เพิ่ม/ย้ายเข้ามาต้องล็อก FK แถวเดียวกันนี้ จึงแทรกระหว่างนับกับปิดไม่ได้
+    #[instrument(skip_all, err)]
+    pub async fn soft_delete_if_empty(
+        &self,
+        id: Uuid,
+        expected_versions: Option<Vec<i32>>,
+    ) -> Result<RestrictedDelete, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let locked = sqlx::query_scalar!("SELECT id FROM categories WHERE id = $1 FOR UPDATE", id)
+            .fetch_optional(&mut *tx)
+            .await?;
+        if locked.is_none() {
+            return Ok(RestrictedDelete::NotUpdated);
+        }
+
+        let active_products = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) as "count!" FROM products WHERE category_id = $1 AND is_active = true"#,
+            id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        if active_products > 0 {
+            return Ok(RestrictedDelete::HasActiveProducts(active_products));
+        }
+
+        let result = sqlx::query!(
             r#"
             UPDATE categories 
-            SET
-                name = COALESCE($1, name),
-                description = COALESCE($2, description),
-                is_active = COALESCE($3, is_active),
+            SET is_active = false, 
+                version = version + 1,
+                updated_at = NOW() 
+            WHERE id = $1
+              AND ($2::int4[] IS NULL OR version = ANY($2))
+            "#,
+            id,
+            expected_versions.as_deref()
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Ok(RestrictedDelete::NotUpdated); // tx ถูก rollback ตอน drop
+        }
+
+        tx.commit().await?;
+        Ok(RestrictedDelete::Deleted)
+    }
+
+    // ใช้ตอน Import สินค้า: โหลดหมวดหมู่ทั้งหมดมาทำ lookup ทีเดียว
+    #[instrument(skip_all, err)]
+    pub async fn find_all(&self) -> Result<Vec<CategoryEntity>, sqlx::Error> {
+        sqlx::query_as!(CategoryEntity, "SELECT * FROM categories ORDER BY name ASC")
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    // ปิดหมวดหมู่ + ปิดสินค้าที่เปิดขายอยู่ในหมวดหมู่ (Transaction เดียว)
+    // คืน None ถ้าไม่มีแถวถูกแก้ (ไม่เจอ หรือ version ไม่ตรง), ไม่งั้นคืน id สินค้าที่ถูกปิด
+    #[instrument(skip_all, err)]
+    pub async fn soft_delete_cascade(
+        &self,
+        id: Uuid,
+        expected_versions: Option<Vec<i32>>,
+    ) -> Result<Option<Vec<Uuid>>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let result = sqlx::query!(
+            r#"
+            UPDATE categories 
+            SET is_active = false, 
+                version = version + 1,
+                updated_at = NOW() 
+            WHERE id = $1
+              AND ($2::int4[] IS NULL OR version = ANY($2))
+            "#,
+            id,
+            expected_versions.as_deref()
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Ok(None); // tx ถูก rollback ตอน drop
+        }
+
+        let rows = sqlx::query!(
+            r#"
+            UPDATE products
+            SET is_active = false,
+                deactivated_via_category = true,
+                version = version + 1,
                 updated_at = NOW()
-            WHERE id = $4
-            RETURNING *
+            WHERE category_id = $1 AND is_active = true
+            RETURNING id
             "#,
-            data.name,
-            data.description,
-            data.is_active,
-            categories_id
+            id
         )
-        .fetch_one(&self.pool)
-        .await
+        .fetch_all(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(Some(rows.into_iter().map(|r| r.id).collect()))
     }
 
-    pub async fn soft_delete(&self, id: Uuid) -> Result<bool, sqlx::Error> {
+    // ปิดหมวดหมู่ + ย้ายสินค้าทั้งหมด (รวมที่ปิดขายแล้ว) ไปหมวดหมู่ปลายทาง
+    #[instrument(skip_all, err)]
+    pub async fn soft_delete_reassign(
+        &self,
+        id: Uuid,
+        target_id: Uuid,
+        expected_versions: Option<Vec<i32>>,
+    ) -> Result<Option<Vec<ProductEntity>>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
         let result = sqlx::query!(
             r#"
             UPDATE categories 
             SET is_active = false, 
+                version = version + 1,
                 updated_at = NOW() 
             WHERE id = $1
+              AND ($2::int4[] IS NULL OR version = ANY($2))
+            "#,
+            id,
+            expected_versions.as_deref()
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Ok(None);
+        }
+
+        let products = sqlx::query_as!(
+            ProductEntity,
+            r#"
+            UPDATE products
+            SET category_id = $2,
+                version = version + 1,
+                updated_at = NOW()
+            WHERE category_id = $1
+            RETURNING *
+            "#,
+            id,
+            target_id
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(Some(products))
+    }
+
+    // เปิดหมวดหมู่คืน + เปิดสินค้าที่เคยถูกปิดตามหมวดหมู่ (สินค้าที่ถูกลบเองจะไม่ถูกเปิด)
+    #[instrument(skip_all, err)]
+    pub async fn restore(
+        &self,
+        id: Uuid,
+    ) -> Result<Option<(CategoryEntity, Vec<ProductEntity>)>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let category = sqlx::query_as!(
+            CategoryEntity,
+            r#"
+            UPDATE categories
+            SET is_active = true,
+                version = version + 1,
+                updated_at = NOW()
+            WHERE id = $1
+            RETURNING *
+            "#,
+            id
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(category) = category else {
+            return Ok(None);
+        };
+
+        let products = sqlx::query_as!(
+            ProductEntity,
+            r#"
+            UPDATE products
+            SET is_active = true,
+                deactivated_via_category = false,
+                version = version + 1,
+                updated_at = NOW()
+            WHERE category_id = $1 AND deactivated_via_category = true
+            RETURNING *
             "#,
             id
         )
-        .execute(&self.pool)
+        .fetch_all(&mut *tx)
         .await?;
 
-        Ok(result.rows_affected() > 0)
+        tx.commit().await?;
+        Ok(Some((category, products)))
     }
 }
@@ -0,0 +1,69 @@
+use crate::models::entity::ExchangeRateEntity;
+use rust_decimal::Decimal;
+use sqlx::{Pool, Postgres};
+use tracing::instrument;
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct ExchangeRateRepository {
+    pool: Pool<Postgres>,
+}
+
+impl ExchangeRateRepository {
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+
+    #[instrument(skip_all, err)]
+    pub async fn list(&self) -> Result<Vec<ExchangeRateEntity>, sqlx::Error> {
+        sqlx::query_as!(
+            ExchangeRateEntity,
+            "SELECT * FROM exchange_rates ORDER BY currency"
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    #[instrument(skip_all, err)]
+    pub async fn find(&self, currency: &str) -> Result<Option<ExchangeRateEntity>, sqlx::Error> {
+        sqlx::query_as!(
+            ExchangeRateEntity,
+            "SELECT * FROM exchange_rates WHERE currency = $1",
+            currency
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    #[instrument(skip_all, err)]
+    pub async fn upsert(
+        &self,
+        currency: &str,
+        rate: Decimal,
+        updated_by: Uuid,
+    ) -> Result<ExchangeRateEntity, sqlx::Error> {
+        sqlx::query_as!(
+            ExchangeRateEntity,
+            r#"
+            INSERT INTO exchange_rates (currency, rate, updated_by)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (currency) DO UPDATE
+            SET rate = EXCLUDED.rate, updated_by = EXCLUDED.updated_by, updated_at = NOW()
+            RETURNING *
+            "#,
+            currency,
+            rate,
+            updated_by
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    #[instrument(skip_all, err)]
+    pub async fn delete(&self, currency: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM exchange_rates WHERE currency = $1", currency)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
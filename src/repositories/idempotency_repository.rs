@@ -0,0 +1,144 @@
+use crate::models::entity::IdempotencyKeyEntity;
+use sqlx::{Pool, Postgres};
+use tracing::instrument;
+use uuid::Uuid;
+
+// request ที่ขอจอง Idempotency-Key (path รวม query string)
+pub struct KeyClaim<'a> {
+    pub scope: &'a str, // เจ้าของ key (user id)
+    pub key: &'a str,
+    pub method: &'a str,
+    pub path: &'a str,
+    pub request_hash: &'a str,
+}
+
+#[derive(Clone)]
+pub struct IdempotencyRepository {
+    pool: Pool<Postgres>,
+}
+
+impl IdempotencyRepository {
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+
+    // จอง key: คืน claim token ถ้าได้เป็นเจ้าของ (key ใหม่, หมดอายุแล้ว หรือ request เดิมที่ค้างเกิน processing_timeout)
+    // request อื่นที่ใช้ key เดิม (hash ไม่ตรง) แย่ง key ที่ค้างไม่ได้ คืน None ให้ไปอ่านแถวเดิมด้วย find
+    // token ใช้ยืนยันความเป็นเจ้าของตอน complete / release (request ที่ถูกแย่ง key ไปแล้วจะเขียนทับไม่ได้)
+    #[instrument(skip_all, err)]
+    pub async fn try_claim(
+        &self,
+        claim: &KeyClaim<'_>,
+        ttl_hours: i64,
+        processing_timeout_secs: i64,
+    ) -> Result<Option<Uuid>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"
+            INSERT INTO idempotency_keys (scope, idempotency_key, method, path, request_hash, claim_token, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $8, NOW() + make_interval(hours => $6::int4))
+            ON CONFLICT (scope, idempotency_key) DO UPDATE
+            SET method = EXCLUDED.method,
+                path = EXCLUDED.path,
+                request_hash = EXCLUDED.request_hash,
+                claim_token = EXCLUDED.claim_token,
+                status_code = NULL,
+                content_type = NULL,
+                response_body = NULL,
+                created_at = NOW(),
+                expires_at = EXCLUDED.expires_at
+            WHERE idempotency_keys.expires_at < NOW()
+               OR (idempotency_keys.status_code IS NULL
+                   AND idempotency_keys.request_hash = EXCLUDED.request_hash
+                   AND idempotency_keys.created_at < NOW() - make_interval(secs => $7::float8))
+            RETURNING claim_token as "claim_token!"
+            "#,
+            claim.scope,
+            claim.key,
+            claim.method,
+            claim.path,
+            claim.request_hash,
+            ttl_hours as i32,
+            processing_timeout_secs as f64,
+            Uuid::new_v4()
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    #[instrument(skip_all, err)]
+    pub async fn find(
+        &self,
+        scope: &str,
+        key: &str,
+    ) -> Result<Option<IdempotencyKeyEntity>, sqlx::Error> {
+        sqlx::query_as!(
+            IdempotencyKeyEntity,
+            r#"
+            SELECT request_hash, status_code, content_type, response_body
+            FROM idempotency_keys
+            WHERE scope = $1 AND idempotency_key = $2
+            "#,
+            scope,
+            key
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    // คืน false ถ้า key ไม่ใช่ของ token นี้แล้ว (ถูก request อื่นแย่งไปหลังค้างเกิน processing_timeout)
+    #[instrument(skip_all, err)]
+    pub async fn complete(
+        &self,
+        scope: &str,
+        key: &str,
+        claim_token: Uuid,
+        status_code: i16,
+        content_type: Option<&str>,
+        response_body: &[u8],
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE idempotency_keys
+            SET status_code = $4, content_type = $5, response_body = $6
+            WHERE scope = $1 AND idempotency_key = $2 AND claim_token = $3
+            "#,
+            scope,
+            key,
+            claim_token,
+            status_code,
+            content_type,
+            response_body
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    // ปล่อย key ที่ยังไม่เสร็จ (เช่น request แรกตอบ 5xx) ให้ retry ด้วย key เดิมได้ (คืน false ถ้าไม่ใช่เจ้าของแล้ว)
+    #[instrument(skip_all, err)]
+    pub async fn release(&self, scope: &str, key: &str, claim_token: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM idempotency_keys
+            WHERE scope = $1 AND idempotency_key = $2 AND claim_token = $3 AND status_code IS NULL
+            "#,
+            scope,
+            key,
+            claim_token
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    #[instrument(skip_all, err)]
+    pub async fn delete_expired(&self) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM idempotency_keys WHERE expires_at < NOW()")
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
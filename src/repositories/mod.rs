@@ -1,4 +1,14 @@
 pub mod user_repository;
 pub mod categories_repository;
 pub mod products_repository;
-pub mod cart_repository;
\ No newline at end of file
+pub mod cart_repository;
+pub mod update_builder;
+pub mod address_repository;
+pub mod order_repository;
+pub mod payment_repository;
+pub mod idempotency_repository;
+pub mod exchange_rate_repository;
+pub mod tax_rate_repository;
+pub mod shipping_repository;
+pub mod stats_repository;
+pub mod cart_recovery_repository;
\ No newline at end of file
@@ -0,0 +1,302 @@
+use crate::constants::{ORDER_PAYMENT_FAILED, ORDER_PENDING_PAYMENT};
+use crate::models::entity::{CheckoutLine, OrderEntity, OrderItemEntity};
+use crate::utils::tax::{TaxCalculator, TaxSummary};
+use rust_decimal::Decimal;
+use sqlx::{Pool, Postgres, Transaction};
+use tracing::instrument;
+use uuid::Uuid;
+
+// ผลของการสร้าง Order จากตะกร้า
+pub enum CheckoutOutcome {
+    Created(Box<OrderEntity>, Vec<OrderItemEntity>),
+    EmptyCart,
+    Unavailable(Vec<String>), // ชื่อสินค้าที่ปิดขายหรือสต็อกไม่พอ
+    CartChanged,              // ตะกร้า/ราคาเปลี่ยนหลัง quote ค่าส่ง
+}
+
+// วิธีจัดส่งที่ quote ไว้ก่อนเปิด Transaction (quoted = สินค้า/จำนวน/ราคาที่ใช้ quote เรียงตาม product_id)
+pub struct ShippingSelection {
+    pub method: String,
+    pub method_name: String,
+    pub cost: Decimal,
+    pub quoted: Vec<(Uuid, i32, Decimal)>,
+}
+
+#[derive(Clone)]
+pub struct OrderRepository {
+    pool: Pool<Postgres>,
+}
+
+impl OrderRepository {
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+
+    // ตะกร้า -> Order ใน Transaction เดียว: ล็อกสินค้า, ตัดสต็อก, copy ราคา/ชื่อ/ภาษี, ล้างตะกร้า
+    #[instrument(skip_all, err)]
+    pub async fn create_from_cart(
+        &self,
+        user_id: Uuid,
+        currency: &str,
+        tax: &TaxCalculator,
+        shipping: &ShippingSelection,
+        shipping_address: serde_json::Value,
+        billing_address: serde_json::Value,
+    ) -> Result<CheckoutOutcome, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        // ล็อกเรียงตาม id เสมอ กัน deadlock กับ Checkout อื่นที่มีสินค้าซ้ำกัน
+        let lines = sqlx::query_as!(
+            CheckoutLine,
+            r#"
+            SELECT
+                p.id as product_id,
+                p.name as product_name,
+                p.price as "price: rust_decimal::Decimal",
+                p.stock,
+                p.is_active,
+                ci.quantity,
+                p.tax_class
+            FROM cart_items ci
+            JOIN carts c ON c.id = ci.cart_id
+            JOIN products p ON p.id = ci.product_id
+            WHERE c.user_id = $1
+            ORDER BY p.id
+            FOR UPDATE OF p
+            "#,
+            user_id
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        if lines.is_empty() {
+            return Ok(CheckoutOutcome::EmptyCart);
+        }
+
+        let unavailable: Vec<String> = lines
+            .iter()
+            .filter(|line| !line.is_active || line.stock < line.quantity)
+            .map(|line| line.product_name.clone())
+            .collect();
+        if !unavailable.is_empty() {
+            return Ok(CheckoutOutcome::Unavailable(unavailable));
+        }
+
+        let current: Vec<_> = lines
+            .iter()
+            .map(|line| (line.product_id, line.quantity, line.price))
+            .collect();
+        if current != shipping.quoted {
+            return Ok(CheckoutOutcome::CartChanged);
+        }
+
+        let subtotal: Decimal = lines
+            .iter()
+            .map(|line| line.price * Decimal::from(line.quantity))
+            .sum();
+        let line_taxes: Vec<_> = lines
+            .iter()
+            .map(|line| tax.line(&line.tax_class, line.price, line.quantity))
+            .collect();
+        let summary = TaxSummary::from_lines(&line_taxes);
+
+        let order = sqlx::query_as!(
+            OrderEntity,
+            r#"
+            INSERT INTO orders
+            (user_id, currency, subtotal, total, shipping_address, billing_address, tax_total,
+             shipping_method, shipping_method_name, shipping_cost)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            RETURNING *
+            "#,
+            user_id,
+            currency,
+            subtotal,
+            summary.gross_total + shipping.cost,
+            shipping_address,
+            billing_address,
+            summary.tax_total,
+            shipping.method,
+            shipping.method_name,
+            shipping.cost
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let mut items = Vec::with_capacity(lines.len());
+        for (line, line_tax) in lines.iter().zip(&line_taxes) {
+            let item = sqlx::query_as!(
+                OrderItemEntity,
+                r#"
+                INSERT INTO order_items
+                (order_id, product_id, product_name, unit_price, quantity, line_total, tax_rate, tax_inclusive, tax_amount)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                RETURNING *
+                "#,
+                order.id,
+                line.product_id,
+                line.product_name,
+                line.price,
+                line.quantity,
+                line.price * Decimal::from(line.quantity),
+                line_tax.rate,
+                line_tax.inclusive,
+                line_tax.tax
+            )
+            .fetch_one(&mut *tx)
+            .await?;
+            items.push(item);
+
+            sqlx::query!(
+                r#"
+                UPDATE products
+                SET stock = stock - $2,
+                    version = version + 1,
+                    updated_at = NOW()
+                WHERE id = $1
+                "#,
+                line.product_id,
+                line.quantity
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        sqlx::query!(
+            "DELETE FROM cart_items WHERE cart_id = (SELECT id FROM carts WHERE user_id = $1)",
+            user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        // Order แรกหลังแจ้งเตือนตะกร้าที่ถูกทิ้ง (ภายในอายุลิงก์) นับเป็นผลของการแจ้งเตือนนั้น
+        sqlx::query!(
+            r#"
+            UPDATE cart_recoveries
+            SET converted_at = NOW(), order_id = $2
+            WHERE id = (
+                SELECT id FROM cart_recoveries
+                WHERE user_id = $1 AND converted_at IS NULL AND expires_at > NOW()
+                ORDER BY created_at DESC
+                LIMIT 1
+            )
+            "#,
+            user_id,
+            order.id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(CheckoutOutcome::Created(Box::new(order), items))
+    }
+
+    // จ่ายไม่สำเร็จ: ปิด Order แล้วคืนสต็อก (ทำเฉพาะ Order ที่ยังรอจ่าย จึงเรียกซ้ำได้)
+    // คืน id สินค้าที่สต็อกกลับมาจาก 0 (ต้องแจ้งคนที่รอของอยู่หลัง commit)
+    #[instrument(skip_all, err)]
+    pub async fn fail_unpaid(
+        tx: &mut Transaction<'_, Postgres>,
+        order_id: Uuid,
+    ) -> Result<Vec<Uuid>, sqlx::Error> {
+        let result = sqlx::query!(
+            "UPDATE orders SET status = $2, updated_at = NOW() WHERE id = $1 AND status = $3",
+            order_id,
+            ORDER_PAYMENT_FAILED,
+            ORDER_PENDING_PAYMENT
+        )
+        .execute(&mut **tx)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Ok(Vec::new());
+        }
+
+        let restocked = sqlx::query!(
+            r#"
+            UPDATE products p
+            SET stock = p.stock + oi.quantity,
+                version = p.version + 1,
+                updated_at = NOW()
+            FROM order_items oi
+            WHERE oi.order_id = $1 AND p.id = oi.product_id
+            RETURNING p.id, p.stock, oi.quantity
+            "#,
+            order_id
+        )
+        .fetch_all(&mut **tx)
+        .await?;
+
+        Ok(restocked
+            .into_iter()
+            .filter(|row| row.stock - row.quantity <= 0 && row.stock > 0)
+            .map(|row| row.id)
+            .collect())
+    }
+
+    #[instrument(skip_all, err)]
+    pub async fn find(
+        &self,
+        user_id: Uuid,
+        order_id: Uuid,
+    ) -> Result<Option<OrderEntity>, sqlx::Error> {
+        sqlx::query_as!(
+            OrderEntity,
+            "SELECT * FROM orders WHERE id = $1 AND user_id = $2",
+            order_id,
+            user_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    #[instrument(skip_all, err)]
+    pub async fn find_by_id(&self, order_id: Uuid) -> Result<Option<OrderEntity>, sqlx::Error> {
+        sqlx::query_as!(OrderEntity, "SELECT * FROM orders WHERE id = $1", order_id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    #[instrument(skip_all, err)]
+    pub async fn list_by_user(
+        &self,
+        user_id: Uuid,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<OrderEntity>, i64), sqlx::Error> {
+        let orders = sqlx::query_as!(
+            OrderEntity,
+            r#"
+            SELECT * FROM orders
+            WHERE user_id = $1
+            ORDER BY created_at DESC
+            LIMIT $2 OFFSET $3
+            "#,
+            user_id,
+            limit,
+            offset
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let total = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) as "count!" FROM orders WHERE user_id = $1"#,
+            user_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok((orders, total))
+    }
+
+    // ดึงรายการสินค้าของหลาย Order ในครั้งเดียว (หน้า list)
+    #[instrument(skip_all, err)]
+    pub async fn find_items(&self, order_ids: &[Uuid]) -> Result<Vec<OrderItemEntity>, sqlx::Error> {
+        sqlx::query_as!(
+            OrderItemEntity,
+            "SELECT * FROM order_items WHERE order_id = ANY($1) ORDER BY product_name",
+            order_ids
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+}
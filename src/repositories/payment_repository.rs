@@ -0,0 +1,300 @@
+use crate::constants::{
+    ORDER_PAID, ORDER_PENDING_PAYMENT, ORDER_REFUNDED, PAYMENT_AUTHORIZED, PAYMENT_CANCELED,
+    PAYMENT_CAPTURED, PAYMENT_FAILED, PAYMENT_PENDING, PAYMENT_REFUND_PENDING, PAYMENT_REFUNDED,
+};
+use crate::models::entity::PaymentEntity;
+use crate::repositories::order_repository::OrderRepository;
+use rust_decimal::Decimal;
+use sqlx::{Pool, Postgres, Transaction};
+use tracing::instrument;
+use uuid::Uuid;
+
+// ผลของการประมวลผล Webhook หนึ่งครั้ง
+pub enum WebhookOutcome {
+    Duplicate,              // event นี้เคยประมวลผลแล้ว
+    UnknownPayment,         // ไม่มี payment ตาม id ที่ provider ส่งมา
+    Ignored(PaymentEntity), // สถานะปัจจุบันเปลี่ยนไปเป็นสถานะนี้ไม่ได้ (เช่น failed มาหลัง captured)
+    Applied(Transition),
+}
+
+// ผลการเปลี่ยนสถานะ (restocked = สินค้าที่สต็อกกลับมาจาก 0 ตอนปิด Order ที่จ่ายไม่สำเร็จ)
+pub struct Transition {
+    pub payment: PaymentEntity,
+    pub restocked: Vec<Uuid>,
+}
+
+// Capture ที่มาถึงตอน Order ไม่ได้รอจ่ายแล้ว (จ่ายซ้ำ / Order ถูกปิด) ต้องคืนเงินทันที
+const ORPHAN_CAPTURE_REASON: &str = "Captured after the order was no longer awaiting payment";
+
+// สถานะที่ payment ต้องอยู่ก่อน ถึงจะเปลี่ยนไปสถานะปลายทางได้
+// (canceled ยังรับผลจาก provider ได้ เพราะลูกค้าอาจจ่ายด้วยรายการเก่าไปแล้ว)
+fn allowed_from(to: &str) -> &'static [&'static str] {
+    match to {
+        PAYMENT_AUTHORIZED => &[PAYMENT_PENDING, PAYMENT_CANCELED],
+        PAYMENT_CAPTURED => &[PAYMENT_PENDING, PAYMENT_AUTHORIZED, PAYMENT_CANCELED],
+        PAYMENT_FAILED => &[PAYMENT_PENDING, PAYMENT_AUTHORIZED],
+        PAYMENT_CANCELED => &[PAYMENT_PENDING],
+        PAYMENT_REFUND_PENDING => &[PAYMENT_CAPTURED],
+        PAYMENT_REFUNDED => &[PAYMENT_CAPTURED, PAYMENT_REFUND_PENDING],
+        _ => &[],
+    }
+}
+
+#[derive(Clone)]
+pub struct PaymentRepository {
+    pool: Pool<Postgres>,
+}
+
+impl PaymentRepository {
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+
+    #[instrument(skip_all, err)]
+    pub async fn create(
+        &self,
+        order_id: Uuid,
+        provider: &str,
+        provider_payment_id: &str,
+        amount: Decimal,
+        currency: &str,
+    ) -> Result<PaymentEntity, sqlx::Error> {
+        sqlx::query_as!(
+            PaymentEntity,
+            r#"
+            INSERT INTO payments (order_id, provider, provider_payment_id, amount, currency)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING *
+            "#,
+            order_id,
+            provider,
+            provider_payment_id,
+            amount,
+            currency
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    #[instrument(skip_all, err)]
+    pub async fn find_by_orders(&self, order_ids: &[Uuid]) -> Result<Vec<PaymentEntity>, sqlx::Error> {
+        sqlx::query_as!(
+            PaymentEntity,
+            "SELECT * FROM payments WHERE order_id = ANY($1) ORDER BY created_at",
+            order_ids
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    // เปลี่ยนสถานะ payment + ผลต่อ Order (เรียกภายใน Transaction ที่ล็อกแถว payment ไว้แล้ว)
+    async fn apply_transition(
+        tx: &mut Transaction<'_, Postgres>,
+        payment: &PaymentEntity,
+        to: &str,
+        failure_reason: Option<&str>,
+    ) -> Result<Option<Transition>, sqlx::Error> {
+        if !allowed_from(to).contains(&payment.status.as_str()) {
+            return Ok(None);
+        }
+
+        let updated = sqlx::query_as!(
+            PaymentEntity,
+            r#"
+            UPDATE payments
+            SET status = $2,
+                failure_reason = COALESCE($3, failure_reason),
+                updated_at = NOW()
+            WHERE id = $1
+            RETURNING *
+            "#,
+            payment.id,
+            to,
+            failure_reason
+        )
+        .fetch_one(&mut **tx)
+        .await?;
+
+        let mut restocked = Vec::new();
+        match to {
+            PAYMENT_CAPTURED => {
+                let paid = sqlx::query!(
+                    r#"
+                    UPDATE orders
+                    SET status = $2, paid_at = NOW(), updated_at = NOW()
+                    WHERE id = $1 AND status = $3
+                    "#,
+                    payment.order_id,
+                    ORDER_PAID,
+                    ORDER_PENDING_PAYMENT
+                )
+                .execute(&mut **tx)
+                .await?;
+
+                // Order จ่ายด้วยรายการอื่นไปแล้วหรือถูกปิดไปแล้ว: ตั้งเป็น refund_pending ให้ service สั่งคืนเงิน
+                if paid.rows_affected() == 0 {
+                    let flagged = sqlx::query_as!(
+                        PaymentEntity,
+                        r#"
+                        UPDATE payments
+                        SET status = $2, failure_reason = $3, updated_at = NOW()
+                        WHERE id = $1
+                        RETURNING *
+                        "#,
+                        payment.id,
+                        PAYMENT_REFUND_PENDING,
+                        ORPHAN_CAPTURE_REASON
+                    )
+                    .fetch_one(&mut **tx)
+                    .await?;
+                    return Ok(Some(Transition {
+                        payment: flagged,
+                        restocked: Vec::new(),
+                    }));
+                }
+            }
+            PAYMENT_FAILED => {
+                // ยังมีรายการอื่นที่รอผลอยู่ (เช่นลองจ่ายใหม่แล้ว) ให้ Order รอต่อไป
+                let in_flight = sqlx::query_scalar!(
+                    r#"
+                    SELECT EXISTS (
+                        SELECT 1 FROM payments
+                        WHERE order_id = $1 AND id <> $2 AND status IN ($3, $4)
+                    ) as "exists!"
+                    "#,
+                    payment.order_id,
+                    payment.id,
+                    PAYMENT_PENDING,
+                    PAYMENT_AUTHORIZED
+                )
+                .fetch_one(&mut **tx)
+                .await?;
+
+                if !in_flight {
+                    restocked = OrderRepository::fail_unpaid(tx, payment.order_id).await?;
+                }
+            }
+            // คืนเงินรายการที่จ่ายซ้ำไม่ทำให้ Order ที่จ่ายด้วยรายการอื่นกลายเป็น refunded
+            PAYMENT_REFUNDED => {
+                sqlx::query!(
+                    r#"
+                    UPDATE orders SET status = $2, updated_at = NOW()
+                    WHERE id = $1 AND status = $3
+                      AND NOT EXISTS (
+                          SELECT 1 FROM payments
+                          WHERE order_id = $1 AND id <> $4 AND status = $5
+                      )
+                    "#,
+                    payment.order_id,
+                    ORDER_REFUNDED,
+                    ORDER_PAID,
+                    payment.id,
+                    PAYMENT_CAPTURED
+                )
+                .execute(&mut **tx)
+                .await?;
+            }
+            _ => {}
+        }
+
+        Ok(Some(Transition {
+            payment: updated,
+            restocked,
+        }))
+    }
+
+    // บันทึก event id กับเปลี่ยนสถานะใน Transaction เดียว: provider ส่งซ้ำกี่ครั้งก็มีผลครั้งเดียว
+    #[instrument(skip_all, err)]
+    pub async fn process_webhook_event(
+        &self,
+        provider: &str,
+        event_id: &str,
+        provider_payment_id: &str,
+        to: &str,
+        failure_reason: Option<&str>,
+    ) -> Result<WebhookOutcome, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let inserted = sqlx::query!(
+            r#"
+            INSERT INTO payment_webhook_events (provider, event_id)
+            VALUES ($1, $2)
+            ON CONFLICT DO NOTHING
+            "#,
+            provider,
+            event_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        if inserted.rows_affected() == 0 {
+            return Ok(WebhookOutcome::Duplicate);
+        }
+
+        let payment = sqlx::query_as!(
+            PaymentEntity,
+            "SELECT * FROM payments WHERE provider = $1 AND provider_payment_id = $2 FOR UPDATE",
+            provider,
+            provider_payment_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let outcome = match payment {
+            None => WebhookOutcome::UnknownPayment,
+            Some(payment) => match Self::apply_transition(&mut tx, &payment, to, failure_reason).await? {
+                Some(updated) => WebhookOutcome::Applied(updated),
+                None => WebhookOutcome::Ignored(payment),
+            },
+        };
+
+        tx.commit().await?;
+        Ok(outcome)
+    }
+
+    // เปลี่ยนสถานะจากฝั่งเรา (เช่น capture สำเร็จ, สั่ง refund แล้ว) คืน None ถ้าสถานะปัจจุบันไม่อนุญาต
+    #[instrument(skip_all, err)]
+    pub async fn transition(
+        &self,
+        payment_id: Uuid,
+        to: &str,
+        failure_reason: Option<&str>,
+    ) -> Result<Option<Transition>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let payment = sqlx::query_as!(
+            PaymentEntity,
+            "SELECT * FROM payments WHERE id = $1 FOR UPDATE",
+            payment_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(payment) = payment else {
+            return Ok(None);
+        };
+
+        let updated = Self::apply_transition(&mut tx, &payment, to, failure_reason).await?;
+        tx.commit().await?;
+        Ok(updated)
+    }
+
+    // สั่งคืนเงินกับ provider ไม่สำเร็จ: กลับเป็น captured ให้ admin สั่งใหม่ได้
+    #[instrument(skip_all, err)]
+    pub async fn revert_refund(&self, payment_id: Uuid) -> Result<Option<PaymentEntity>, sqlx::Error> {
+        sqlx::query_as!(
+            PaymentEntity,
+            r#"
+            UPDATE payments
+            SET status = $2, updated_at = NOW()
+            WHERE id = $1 AND status = $3
+            RETURNING *
+            "#,
+            payment_id,
+            PAYMENT_CAPTURED,
+            PAYMENT_REFUND_PENDING
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+}
@@ -1,10 +1,22 @@
+use crate::constants::{DEFAULT_LOW_STOCK_THRESHOLD, DEFAULT_TAX_CLASS};
+use crate::repositories::update_builder::UpdateBuilder;
 use crate::models::{
-    dto::{FilterOptions, ProductRequest, UpdateProductRequest},
-    entity::{ProductEntity, ProductWithCategory},
+    dto::{FilterOptions, ProductImportRecord, ProductRequest, UpdateProductRequest},
+    entity::{ProductEntity, ProductWithCategory, RestockSubscriptionEntity, UpsertedProduct},
 };
-use sqlx::{Pool, Postgres, QueryBuilder};
+use sqlx::{Acquire, Pool, Postgres, QueryBuilder, Transaction};
+use tracing::instrument;
 use uuid::Uuid;
 
+// จำนวนแถวต่อ Transaction ตอน Import
+const IMPORT_BATCH_SIZE: usize = 500;
+
+// ผล Upsert รายแถวตอน Import
+pub enum UpsertOutcome {
+    Upserted(UpsertedProduct),
+    KeyConflict, // sku / external_id ไปชนกับสินค้าตัวอื่น แถวนี้ถูกข้าม
+}
+
 #[derive(Clone)]
 pub struct ProductsRepository {
     pool: Pool<Postgres>,
@@ -15,13 +27,15 @@ impl ProductsRepository {
         Self { pool }
     }
 
+    #[instrument(skip_all, err)]
     pub async fn create_product(&self, req: ProductRequest) -> Result<ProductEntity, sqlx::Error> {
         sqlx::query_as!(
             ProductEntity,
             r#"
             INSERT INTO products 
-            (category_id, name, description, price, stock, is_active, average_rating, review_count) 
-            VALUES ($1, $2, $3, $4, $5, $6, 0.0, 0) -- default rating=0
+            (category_id, name, description, price, stock, is_active, average_rating, review_count, low_stock_threshold, tax_class,
+             weight_grams, length_mm, width_mm, height_mm) 
+            VALUES ($1, $2, $3, $4, $5, $6, 0.0, 0, $7, $8, $9, $10, $11, $12) -- default rating=0
             RETURNING *
             "#,
             req.category_id,
@@ -29,12 +43,19 @@ impl ProductsRepository {
             req.description,
             req.price,
             req.stock,
-            req.is_active.unwrap_or(true)
+            req.is_active.unwrap_or(true),
+            req.low_stock_threshold.unwrap_or(DEFAULT_LOW_STOCK_THRESHOLD),
+            req.tax_class.as_deref().unwrap_or(DEFAULT_TAX_CLASS),
+            req.weight_grams,
+            req.length_mm,
+            req.width_mm,
+            req.height_mm
         )
         .fetch_one(&self.pool)
         .await
     }
 
+    #[instrument(skip_all, err)]
     pub async fn list_all(
         &self,
         opts: FilterOptions,
@@ -60,9 +81,12 @@ impl ProductsRepository {
             qb.push_bind(format!("%{}%", search));
         }
 
-        // Filter: Is Active
+        // Filter: Is Active (สินค้าที่เปิดขายต้องอยู่ในหมวดหมู่ที่เปิดอยู่ด้วย)
         qb.push(" AND p.is_active = ");
         qb.push_bind(active_filter);
+        if active_filter {
+            qb.push(" AND c.is_active = true");
+        }
 
         // Sort
         if let Some(sort_by) = &opts.sort_by {
@@ -95,19 +119,25 @@ impl ProductsRepository {
             .await?;
 
         // Count Total
-        let mut count_qb = QueryBuilder::new("SELECT COUNT(*) FROM products p WHERE 1 = 1");
+        let mut count_qb = QueryBuilder::new(
+            "SELECT COUNT(*) FROM products p JOIN categories c ON p.category_id = c.id WHERE 1 = 1",
+        );
         if let Some(search) = &opts.search {
             count_qb.push(" AND p.name ILIKE ");
             count_qb.push_bind(format!("%{}%", search));
         }
         count_qb.push(" AND p.is_active = ");
         count_qb.push_bind(active_filter);
+        if active_filter {
+            count_qb.push(" AND c.is_active = true");
+        }
 
         let count_row: (i64,) = count_qb.build_query_as().fetch_one(&self.pool).await?;
 
         Ok((products, count_row.0))
     }
 
+    #[instrument(skip_all, err)]
     pub async fn find_by_id(&self, id: Uuid) -> Result<Option<ProductWithCategory>, sqlx::Error> {
         sqlx::query_as::<_, ProductWithCategory>(
             r#"
@@ -122,46 +152,293 @@ impl ProductsRepository {
         .await
     }
 
+    #[instrument(skip_all, err)]
     pub async fn update_product(
         &self,
         id: Uuid,
         req: UpdateProductRequest,
-    ) -> Result<ProductEntity, sqlx::Error> {
-        // Update ใช้ COALESCE (Patch)
-        sqlx::query_as!(
-            ProductEntity,
+        expected_versions: Option<Vec<i32>>,
+    ) -> Result<(ProductEntity, i32), sqlx::Error> {
+        // Update เฉพาะ field ที่ส่งมา (description ส่ง null เพื่อล้างค่าได้)
+        // ถ้า version ไม่ตรงจะไม่มีแถวถูกแก้ -> RowNotFound (Service แยกเองว่าไม่เจอหรือ version ชน)
+        // คืนสต็อกก่อนแก้ด้วย (ล็อกแถวไว้ใน Transaction เดียวกัน กันคนอื่นแก้แทรกระหว่างอ่านกับเขียน)
+        let mut tx = self.pool.begin().await?;
+        let previous_stock = sqlx::query_scalar!(
+            "SELECT stock FROM products WHERE id = $1 FOR UPDATE",
+            id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let mut update = UpdateBuilder::new("products");
+        update
+            .set_if("category_id", req.category_id)
+            .set_if("name", req.name)
+            .set_patch("description", req.description)
+            .set_if("price", req.price)
+            .set_if("stock", req.stock)
+            .set_if("is_active", req.is_active)
+            .set_if("low_stock_threshold", req.low_stock_threshold)
+            .set_if("tax_class", req.tax_class)
+            .set_patch("weight_grams", req.weight_grams)
+            .set_patch("length_mm", req.length_mm)
+            .set_patch("width_mm", req.width_mm)
+            .set_patch("height_mm", req.height_mm)
+            .set_expr("version", "version + 1")
+            .set_expr("updated_at", "NOW()");
+
+        let mut qb = update.into_query();
+        qb.push(" WHERE id = ");
+        qb.push_bind(id);
+        if let Some(versions) = expected_versions {
+            qb.push(" AND version = ANY(");
+            qb.push_bind(versions);
+            qb.push(")");
+        }
+        qb.push(" RETURNING *");
+
+        let updated = qb
+            .build_query_as::<ProductEntity>()
+            .fetch_one(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        Ok((updated, previous_stock))
+    }
+
+    #[instrument(skip_all, err)]
+    pub async fn soft_delete(
+        &self,
+        id: Uuid,
+        expected_versions: Option<Vec<i32>>,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
             r#"
             UPDATE products
-            SET 
-                category_id = COALESCE($1, category_id),
-                name = COALESCE($2, name),
-                description = COALESCE($3, description),
-                price = COALESCE($4, price),
-                stock = COALESCE($5, stock),
-                is_active = COALESCE($6, is_active),
+            SET is_active = false,
+                deactivated_via_category = false,
+                version = version + 1,
                 updated_at = NOW()
-            WHERE id = $7
+            WHERE id = $1
+              AND ($2::int4[] IS NULL OR version = ANY($2))
+            "#,
+            id,
+            expected_versions.as_deref()
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    // สินค้าที่ยังขายอยู่และสต็อกต่ำกว่าหรือเท่ากับเกณฑ์ของตัวเอง
+    #[instrument(skip_all, err)]
+    pub async fn find_low_stock(&self) -> Result<Vec<ProductWithCategory>, sqlx::Error> {
+        sqlx::query_as::<_, ProductWithCategory>(
+            r#"
+            SELECT p.*, c.name as category_name 
+            FROM products p
+            JOIN categories c ON p.category_id = c.id
+            WHERE p.is_active = true
+              AND p.stock <= p.low_stock_threshold
+            ORDER BY p.stock ASC, p.name ASC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    #[instrument(skip_all, err)]
+    pub async fn create_restock_subscription(
+        &self,
+        user_id: Uuid,
+        product_id: Uuid,
+    ) -> Result<RestockSubscriptionEntity, sqlx::Error> {
+        // กดซ้ำได้ ไม่ error (คืนค่าแถวเดิม)
+        sqlx::query_as!(
+            RestockSubscriptionEntity,
+            r#"
+            INSERT INTO restock_subscriptions (user_id, product_id)
+            VALUES ($1, $2)
+            ON CONFLICT (user_id, product_id)
+            DO UPDATE SET created_at = restock_subscriptions.created_at
             RETURNING *
             "#,
-            req.category_id,
-            req.name,
-            req.description,
-            req.price,
-            req.stock,
-            req.is_active,
-            id
+            user_id,
+            product_id
         )
         .fetch_one(&self.pool)
         .await
     }
 
-    pub async fn soft_delete(&self, id: Uuid) -> Result<bool, sqlx::Error> {
+    #[instrument(skip_all, err)]
+    pub async fn delete_restock_subscription(
+        &self,
+        user_id: Uuid,
+        product_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
         let result = sqlx::query!(
-            "UPDATE products SET is_active = false, updated_at = NOW() WHERE id = $1",
-            id
+            "DELETE FROM restock_subscriptions WHERE user_id = $1 AND product_id = $2",
+            user_id,
+            product_id
         )
         .execute(&self.pool)
         .await?;
         Ok(result.rows_affected() > 0)
     }
+
+    // ดึงรายชื่อผู้รอแจ้งเตือน (พร้อมภาษาที่ตั้งไว้) แล้วลบทิ้งในคำสั่งเดียว (แจ้งครั้งเดียวต่อการสมัคร)
+    #[instrument(skip_all, err)]
+    pub async fn take_restock_subscribers(
+        &self,
+        product_id: Uuid,
+    ) -> Result<Vec<(Uuid, Option<String>)>, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"
+            DELETE FROM restock_subscriptions s
+            WHERE s.product_id = $1
+            RETURNING s.user_id, (SELECT u.locale FROM users u WHERE u.id = s.user_id) AS locale
+            "#,
+            product_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(|r| (r.user_id, r.locale)).collect())
+    }
+
+    // Upsert ทีละ batch (commit ทุก IMPORT_BATCH_SIZE แถว) ใช้ sku เป็น key ก่อน ถ้าไม่มีค่อยใช้ external_id
+    // แต่ละแถวอยู่ใน SAVEPOINT ของตัวเอง ชน unique ของสินค้าอื่นจะข้ามแค่แถวนั้น (ผลลัพธ์เรียงตาม records)
+    #[instrument(skip_all, err)]
+    pub async fn upsert_all(
+        &self,
+        records: &[ProductImportRecord],
+    ) -> Result<Vec<UpsertOutcome>, sqlx::Error> {
+        let mut results = Vec::with_capacity(records.len());
+
+        for batch in records.chunks(IMPORT_BATCH_SIZE) {
+            let mut tx = self.pool.begin().await?;
+            for record in batch {
+                results.push(Self::upsert_one(&mut tx, record).await?);
+            }
+            tx.commit().await?;
+        }
+
+        Ok(results)
+    }
+
+    async fn upsert_one(
+        tx: &mut Transaction<'_, Postgres>,
+        record: &ProductImportRecord,
+    ) -> Result<UpsertOutcome, sqlx::Error> {
+        // เช่น sku ตรงสินค้าหนึ่ง แต่ external_id ไปตรงอีกตัว -> ชน constraint อีกตัว
+        let mut savepoint = tx.begin().await?;
+        match Self::upsert_query(&mut savepoint, record).await {
+            Ok(row) => {
+                savepoint.commit().await?;
+                Ok(UpsertOutcome::Upserted(row))
+            }
+            Err(e) if e.as_database_error().is_some_and(|db| db.is_unique_violation()) => {
+                savepoint.rollback().await?;
+                Ok(UpsertOutcome::KeyConflict)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn upsert_query(
+        tx: &mut Transaction<'_, Postgres>,
+        record: &ProductImportRecord,
+    ) -> Result<UpsertedProduct, sqlx::Error> {
+        let conflict_key = if record.sku.is_some() { "sku" } else { "external_id" };
+
+        let sql = format!(
+            r#"
+            WITH prev AS (
+                SELECT stock FROM products WHERE {key} = $1
+            )
+            INSERT INTO products
+            (sku, external_id, category_id, name, description, price, stock, is_active, low_stock_threshold, average_rating, review_count)
+            VALUES ($2, $3, $4, $5, $6, $7, $8, $9, $10, 0.0, 0)
+            ON CONFLICT ({key}) DO UPDATE SET
+                sku = COALESCE(EXCLUDED.sku, products.sku),
+                external_id = COALESCE(EXCLUDED.external_id, products.external_id),
+                category_id = EXCLUDED.category_id,
+                name = EXCLUDED.name,
+                description = EXCLUDED.description,
+                price = EXCLUDED.price,
+                stock = EXCLUDED.stock,
+                is_active = EXCLUDED.is_active,
+                low_stock_threshold = EXCLUDED.low_stock_threshold,
+                version = products.version + 1,
+                updated_at = NOW()
+            RETURNING products.*, (xmax = 0) AS inserted, (SELECT stock FROM prev) AS previous_stock
+            "#,
+            key = conflict_key
+        );
+
+        let key_value = if record.sku.is_some() {
+            &record.sku
+        } else {
+            &record.external_id
+        };
+
+        sqlx::query_as::<_, UpsertedProduct>(&sql)
+            .bind(key_value)
+            .bind(&record.sku)
+            .bind(&record.external_id)
+            .bind(record.category_id)
+            .bind(&record.name)
+            .bind(&record.description)
+            .bind(record.price)
+            .bind(record.stock)
+            .bind(record.is_active)
+            .bind(record.low_stock_threshold)
+            .fetch_one(&mut **tx)
+            .await
+    }
+
+    // Export แบบ Keyset Pagination (เรียงตาม id) รวมสินค้าที่ปิดขายด้วย
+    #[instrument(skip_all, err)]
+    pub async fn find_export_batch(
+        &self,
+        after_id: Option<Uuid>,
+        limit: i64,
+    ) -> Result<Vec<ProductWithCategory>, sqlx::Error> {
+        sqlx::query_as::<_, ProductWithCategory>(
+            r#"
+            SELECT p.*, c.name as category_name 
+            FROM products p
+            JOIN categories c ON p.category_id = c.id
+            WHERE ($1::uuid IS NULL OR p.id > $1)
+            ORDER BY p.id ASC
+            LIMIT $2
+            "#,
+        )
+        .bind(after_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    // เปิดขายสินค้าคืน ได้เฉพาะเมื่อหมวดหมู่ยังเปิดอยู่ (คืน None ถ้าไม่เจอหรือหมวดหมู่ถูกปิด)
+    #[instrument(skip_all, err)]
+    pub async fn restore(&self, id: Uuid) -> Result<Option<ProductEntity>, sqlx::Error> {
+        sqlx::query_as!(
+            ProductEntity,
+            r#"
+            UPDATE products p
+            SET is_active = true,
+                deactivated_via_category = false,
+                version = p.version + 1,
+                updated_at = NOW()
+            FROM categories c
+            WHERE p.id = $1
+              AND c.id = p.category_id
+              AND c.is_active = true
+            RETURNING p.*
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
 }
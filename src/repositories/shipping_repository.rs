@@ -0,0 +1,54 @@
+use crate::models::entity::{ShippingMethodEntity, ShippingWeightTierEntity};
+use sqlx::{Pool, Postgres};
+use tracing::instrument;
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct ShippingRepository {
+    pool: Pool<Postgres>,
+}
+
+impl ShippingRepository {
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+
+    // วิธีจัดส่งที่เปิดใช้และส่งไปประเทศนี้ได้
+    #[instrument(skip_all, err)]
+    pub async fn list_active_for_country(
+        &self,
+        country: &str,
+    ) -> Result<Vec<ShippingMethodEntity>, sqlx::Error> {
+        sqlx::query_as!(
+            ShippingMethodEntity,
+            r#"
+            SELECT id, code, name, carrier, rate_type, base_cost, free_threshold, min_days, max_days
+            FROM shipping_methods
+            WHERE is_active
+              AND (countries IS NULL OR $1 = ANY(countries))
+            ORDER BY sort_order, name
+            "#,
+            country
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    #[instrument(skip_all, err)]
+    pub async fn find_tiers(
+        &self,
+        method_ids: &[Uuid],
+    ) -> Result<Vec<ShippingWeightTierEntity>, sqlx::Error> {
+        sqlx::query_as!(
+            ShippingWeightTierEntity,
+            r#"
+            SELECT * FROM shipping_weight_tiers
+            WHERE method_id = ANY($1)
+            ORDER BY method_id, up_to_grams
+            "#,
+            method_ids
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+}
@@ -0,0 +1,243 @@
+use crate::models::dto::{
+    CategoryProductCount, CategoryRevenue, DailyCount, DailyRevenue, TopProductResponse,
+};
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use sqlx::{Pool, Postgres};
+use tracing::instrument;
+
+// ช่วงวันที่แบบ UTC: [start, end) สำหรับกรอง, from/to สำหรับเติมวันที่ไม่มีข้อมูลเป็น 0
+pub struct StatsWindow {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+// ผลของการแจ้งเตือนตะกร้าที่ถูกทิ้งที่ส่งในช่วงวันที่
+pub struct RecoveryFunnel {
+    pub notified: i64,
+    pub notified_value: Decimal,
+    pub clicked: i64,
+    pub converted: i64,
+    pub recovered_value: Decimal,
+}
+
+#[derive(Clone)]
+pub struct StatsRepository {
+    pool: Pool<Postgres>,
+}
+
+impl StatsRepository {
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+
+    #[instrument(skip_all, err)]
+    pub async fn signups_by_day(&self, window: &StatsWindow) -> Result<Vec<DailyCount>, sqlx::Error> {
+        sqlx::query_as!(
+            DailyCount,
+            r#"
+            WITH counts AS (
+                SELECT (created_at AT TIME ZONE 'UTC')::date AS day, COUNT(*) AS count
+                FROM users
+                WHERE created_at >= $3 AND created_at < $4
+                GROUP BY 1
+            )
+            SELECT d.day::date as "date!", COALESCE(c.count, 0) as "count!"
+            FROM generate_series($1::date, $2::date, interval '1 day') AS d(day)
+            LEFT JOIN counts c ON c.day = d.day::date
+            ORDER BY d.day
+            "#,
+            window.from,
+            window.to,
+            window.start,
+            window.end
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    // (ตะกร้า, จำนวนชิ้น, มูลค่า) ของตะกร้าที่มีการหยิบ/แก้สินค้าในช่วงนี้
+    #[instrument(skip_all, err)]
+    pub async fn active_carts(
+        &self,
+        window: &StatsWindow,
+    ) -> Result<(i64, i64, Decimal), sqlx::Error> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                COUNT(DISTINCT ci.cart_id) as "carts!",
+                COALESCE(SUM(ci.quantity), 0)::bigint as "items!",
+                COALESCE(SUM(ci.quantity * p.price), 0) as "value!: rust_decimal::Decimal"
+            FROM carts c
+            JOIN cart_items ci ON ci.cart_id = c.id
+            JOIN products p ON p.id = ci.product_id
+            WHERE c.updated_at >= $1 AND c.updated_at < $2
+            "#,
+            window.start,
+            window.end
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok((row.carts, row.items, row.value))
+    }
+
+    #[instrument(skip_all, err)]
+    pub async fn top_products_by_additions(
+        &self,
+        window: &StatsWindow,
+        limit: i64,
+    ) -> Result<Vec<TopProductResponse>, sqlx::Error> {
+        sqlx::query_as!(
+            TopProductResponse,
+            r#"
+            SELECT
+                a.product_id,
+                p.name as product_name,
+                COUNT(*) as "additions!",
+                SUM(a.quantity)::bigint as "quantity!"
+            FROM cart_additions a
+            JOIN products p ON p.id = a.product_id
+            WHERE a.created_at >= $1 AND a.created_at < $2
+            GROUP BY a.product_id, p.name
+            ORDER BY 3 DESC, 4 DESC, p.name
+            LIMIT $3
+            "#,
+            window.start,
+            window.end,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    #[instrument(skip_all, err)]
+    pub async fn category_product_counts(&self) -> Result<Vec<CategoryProductCount>, sqlx::Error> {
+        sqlx::query_as!(
+            CategoryProductCount,
+            r#"
+            SELECT
+                c.id as category_id,
+                c.name as category_name,
+                c.is_active,
+                COUNT(p.id) as "product_count!",
+                COUNT(p.id) FILTER (WHERE p.is_active) as "active_product_count!"
+            FROM categories c
+            LEFT JOIN products p ON p.category_id = c.id
+            GROUP BY c.id
+            ORDER BY c.name
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    #[instrument(skip_all, err)]
+    pub async fn revenue_by_day(
+        &self,
+        window: &StatsWindow,
+        status: &str,
+    ) -> Result<Vec<DailyRevenue>, sqlx::Error> {
+        sqlx::query_as!(
+            DailyRevenue,
+            r#"
+            WITH paid AS (
+                SELECT (paid_at AT TIME ZONE 'UTC')::date AS day, COUNT(*) AS orders, SUM(total) AS revenue
+                FROM orders
+                WHERE status = $5 AND paid_at >= $3 AND paid_at < $4
+                GROUP BY 1
+            )
+            SELECT
+                d.day::date as "date!",
+                COALESCE(p.orders, 0) as "orders!",
+                COALESCE(p.revenue, 0) as "revenue!: rust_decimal::Decimal"
+            FROM generate_series($1::date, $2::date, interval '1 day') AS d(day)
+            LEFT JOIN paid p ON p.day = d.day::date
+            ORDER BY d.day
+            "#,
+            window.from,
+            window.to,
+            window.start,
+            window.end,
+            status
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    // หมวดหมู่ตามสินค้าปัจจุบัน (Order ไม่ได้ copy หมวดหมู่ไว้)
+    #[instrument(skip_all, err)]
+    pub async fn revenue_by_category(
+        &self,
+        window: &StatsWindow,
+        status: &str,
+    ) -> Result<Vec<CategoryRevenue>, sqlx::Error> {
+        sqlx::query_as!(
+            CategoryRevenue,
+            r#"
+            SELECT
+                c.id as "category_id?",
+                c.name as "category_name?",
+                SUM(oi.quantity)::bigint as "quantity!",
+                SUM(oi.line_total) as "revenue!: rust_decimal::Decimal"
+            FROM orders o
+            JOIN order_items oi ON oi.order_id = o.id
+            LEFT JOIN products p ON p.id = oi.product_id
+            LEFT JOIN categories c ON c.id = p.category_id
+            WHERE o.status = $1 AND o.paid_at >= $2 AND o.paid_at < $3
+            GROUP BY c.id, c.name
+            ORDER BY 4 DESC
+            "#,
+            status,
+            window.start,
+            window.end
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    // (ตะกร้า, มูลค่า) ของตะกร้าที่มีของแต่ไม่เคลื่อนไหวเกิน idle_hours ณ ตอนนี้
+    #[instrument(skip_all, err)]
+    pub async fn abandoned_carts(&self, idle_hours: i64) -> Result<(i64, Decimal), sqlx::Error> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                COUNT(DISTINCT c.id) as "carts!",
+                COALESCE(SUM(ci.quantity * p.price), 0) as "value!: rust_decimal::Decimal"
+            FROM carts c
+            JOIN cart_items ci ON ci.cart_id = c.id
+            JOIN products p ON p.id = ci.product_id
+            WHERE c.updated_at < NOW() - make_interval(hours => $1::int4)
+            "#,
+            idle_hours as i32
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok((row.carts, row.value))
+    }
+
+    #[instrument(skip_all, err)]
+    pub async fn recovery_funnel(&self, window: &StatsWindow) -> Result<RecoveryFunnel, sqlx::Error> {
+        sqlx::query_as!(
+            RecoveryFunnel,
+            r#"
+            SELECT
+                COUNT(*) as "notified!",
+                COALESCE(SUM(r.cart_value), 0) as "notified_value!: rust_decimal::Decimal",
+                COUNT(r.clicked_at) as "clicked!",
+                COUNT(r.converted_at) as "converted!",
+                COALESCE(SUM(o.total), 0) as "recovered_value!: rust_decimal::Decimal"
+            FROM cart_recoveries r
+            LEFT JOIN orders o ON o.id = r.order_id
+            WHERE r.created_at >= $1 AND r.created_at < $2
+            "#,
+            window.start,
+            window.end
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+}
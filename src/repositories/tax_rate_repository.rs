@@ -0,0 +1,47 @@
+use crate::models::entity::TaxRateEntity;
+use sqlx::{Pool, Postgres};
+use tracing::instrument;
+
+#[derive(Clone)]
+pub struct TaxRateRepository {
+    pool: Pool<Postgres>,
+}
+
+impl TaxRateRepository {
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+
+    // อัตราของทั้งประเทศ + อัตราของ region ที่ตรงกัน (TaxCalculator เลือกอันที่เฉพาะเจาะจงกว่าเอง)
+    #[instrument(skip_all, err)]
+    pub async fn find_for_location(
+        &self,
+        country: &str,
+        region: Option<&str>,
+    ) -> Result<Vec<TaxRateEntity>, sqlx::Error> {
+        sqlx::query_as!(
+            TaxRateEntity,
+            r#"
+            SELECT tax_class, region, name, rate, inclusive
+            FROM tax_rates
+            WHERE country = $1
+              AND (region IS NULL OR LOWER(region) = LOWER($2))
+            ORDER BY tax_class
+            "#,
+            country,
+            region
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    #[instrument(skip_all, err)]
+    pub async fn class_exists(&self, code: &str) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT EXISTS(SELECT 1 FROM tax_classes WHERE code = $1) as "exists!""#,
+            code
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+}
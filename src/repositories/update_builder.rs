@@ -0,0 +1,76 @@
+use crate::models::dto::Patch;
+use sqlx::{Encode, Postgres, QueryBuilder, Type};
+
+// ประกอบคำสั่ง UPDATE แบบ Dynamic: SET เฉพาะ field ที่ถูกส่งมา
+// (แทน COALESCE ที่ทำให้ตั้งค่าเป็น NULL ไม่ได้)
+pub struct UpdateBuilder<'args> {
+    qb: QueryBuilder<'args, Postgres>,
+    has_columns: bool,
+}
+
+impl<'args> UpdateBuilder<'args> {
+    pub fn new(table: &str) -> Self {
+        Self {
+            qb: QueryBuilder::new(format!("UPDATE {} SET ", table)),
+            has_columns: false,
+        }
+    }
+
+    fn push_column(&mut self, column: &str) {
+        if self.has_columns {
+            self.qb.push(", ");
+        }
+        self.qb.push(column);
+        self.qb.push(" = ");
+        self.has_columns = true;
+    }
+
+    pub fn set<T>(&mut self, column: &str, value: T) -> &mut Self
+    where
+        T: 'args + Encode<'args, Postgres> + Type<Postgres> + Send,
+    {
+        self.push_column(column);
+        self.qb.push_bind(value);
+        self
+    }
+
+    // Option: None = ไม่แก้ (ใช้กับ field ที่ห้ามเป็น NULL)
+    pub fn set_if<T>(&mut self, column: &str, value: Option<T>) -> &mut Self
+    where
+        T: 'args + Encode<'args, Postgres> + Type<Postgres> + Send,
+    {
+        if let Some(value) = value {
+            self.set(column, value);
+        }
+        self
+    }
+
+    pub fn set_patch<T>(&mut self, column: &str, value: Patch<T>) -> &mut Self
+    where
+        T: 'args + Encode<'args, Postgres> + Type<Postgres> + Send,
+    {
+        match value {
+            Patch::Absent => {}
+            Patch::Null => {
+                self.push_column(column);
+                self.qb.push("NULL");
+            }
+            Patch::Value(value) => {
+                self.set(column, value);
+            }
+        }
+        self
+    }
+
+    // SQL expression ตรง ๆ เช่น "version + 1" หรือ "NOW()" (ห้ามใส่ค่าจาก user)
+    pub fn set_expr(&mut self, column: &str, expr: &str) -> &mut Self {
+        self.push_column(column);
+        self.qb.push(expr);
+        self
+    }
+
+    // คืน QueryBuilder ไปต่อ WHERE / RETURNING เอง
+    pub fn into_query(self) -> QueryBuilder<'args, Postgres> {
+        self.qb
+    }
+}
@@ -1,7 +1,21 @@
-use crate::models::{dto::FilterOptions, entity::UserEntity};
+use crate::models::{
+    dto::{FilterOptions, Patch},
+    entity::UserEntity,
+};
+use crate::repositories::update_builder::UpdateBuilder;
 use sqlx::{Pool, Postgres, QueryBuilder};
+use tracing::instrument;
 use uuid::Uuid;
 
+// ค่าที่ผ่าน Validation แล้วของ PUT /users/me (None / Absent = ไม่แก้)
+pub struct UserUpdate {
+    pub username: Option<String>,
+    pub email: Option<String>, // Some(...) จะล้าง email_verified_at ด้วย (ต้องยืนยันอีเมลใหม่)
+    pub display_name: Patch<String>,
+    pub phone: Patch<String>,
+    pub locale: Patch<String>,
+}
+
 #[derive(Clone)]
 pub struct UserRepository {
     pool: Pool<Postgres>,
@@ -12,23 +26,73 @@ impl UserRepository {
         Self { pool }
     }
 
+    #[instrument(skip_all, err)]
     pub async fn create_user(
         &self,
         username: &str,
         password_hash: &str,
+        email: &str,
+        display_name: Option<&str>,
+        phone: Option<&str>,
     ) -> Result<UserEntity, sqlx::Error> {
         sqlx::query_as!(
             UserEntity,
-            "INSERT INTO users (id, username, password_hash, created_at) VALUES ($1, $2, $3, $4) RETURNING *",
+            r#"
+            INSERT INTO users (id, username, password_hash, email, display_name, phone, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING *
+            "#,
             Uuid::new_v4(),
             username,
             password_hash,
+            email,
+            display_name,
+            phone,
             chrono::Utc::now()
         )
         .fetch_one(&self.pool) //ต้องเจอ 1 แถวเท่านั้น (ถ้าไม่เจอจะ Error)
         .await
     }
 
+    #[instrument(skip_all, err)]
+    pub async fn create_user_with_role(
+        &self,
+        username: &str,
+        password_hash: &str,
+        role: &str,
+    ) -> Result<UserEntity, sqlx::Error> {
+        sqlx::query_as!(
+            UserEntity,
+            "INSERT INTO users (id, username, password_hash, role, created_at) VALUES ($1, $2, $3, $4, $5) RETURNING *",
+            Uuid::new_v4(),
+            username,
+            password_hash,
+            role,
+            chrono::Utc::now()
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    // ไม่เพิ่ม version: เป็นการเปลี่ยนภายใน ไม่ควรทำให้ ETag ที่ client ถืออยู่ใช้ไม่ได้
+    #[instrument(skip_all, err)]
+    pub async fn update_password_hash(
+        &self,
+        user_id: Uuid,
+        password_hash: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE users SET password_hash = $1 WHERE id = $2",
+            password_hash,
+            user_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    #[instrument(skip_all, err)]
     pub async fn find_by_username(
         &self,
         username: &str,
@@ -42,18 +106,58 @@ impl UserRepository {
         .await
     }
 
+    // Login ได้ทั้ง username และอีเมล (username มี @ ไม่ได้ จึงแยกกันได้ชัด)
+    #[instrument(skip_all, err)]
+    pub async fn find_by_login(&self, login: &str) -> Result<Option<UserEntity>, sqlx::Error> {
+        if login.contains('@') {
+            sqlx::query_as!(
+                UserEntity,
+                "SELECT * FROM users WHERE LOWER(email) = LOWER($1)",
+                login
+            )
+            .fetch_optional(&self.pool)
+            .await
+        } else {
+            self.find_by_username(login).await
+        }
+    }
+
+    #[instrument(skip_all, err)]
+    pub async fn mark_email_verified(&self, user_id: Uuid, email: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE users
+            SET email_verified_at = NOW(),
+                version = version + 1,
+                updated_at = NOW()
+            WHERE id = $1
+              AND LOWER(email) = LOWER($2)
+              AND email_verified_at IS NULL
+            "#,
+            user_id,
+            email
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    #[instrument(skip_all, err)]
     pub async fn find_by_id(&self, user_id: Uuid) -> Result<Option<UserEntity>, sqlx::Error> {
         sqlx::query_as!(UserEntity, "SELECT * FROM users WHERE id = $1", user_id)
             .fetch_optional(&self.pool)
             .await
     }
 
+    #[instrument(skip_all, err)]
     pub async fn list_users(&self) -> Result<Vec<UserEntity>, sqlx::Error> {
         sqlx::query_as!(UserEntity, "SELECT * FROM users ORDER BY created_at DESC")
             .fetch_all(&self.pool) //ขอมาเป็น List (Vec)
             .await
     }
 
+    #[instrument(skip_all, err)]
     pub async fn find_all(
         &self,
         opts: FilterOptions,
@@ -68,8 +172,12 @@ impl UserRepository {
 
         // ถ้ามี Search ให้เพิ่มเงื่อนไข
         if let Some(search) = &opts.search {
-            qb.push(" AND username ILIKE "); // ILIKE ไม่สนตัวพิมพ์เล็กใหญ่
-            qb.push_bind(format!("%{}%", search)); // bind ค่าเพื่อกัน SQL Injection
+            let pattern = format!("%{}%", search);
+            qb.push(" AND (username ILIKE "); // ILIKE ไม่สนตัวพิมพ์เล็กใหญ่
+            qb.push_bind(pattern.clone()); // bind ค่าเพื่อกัน SQL Injection
+            qb.push(" OR email ILIKE ");
+            qb.push_bind(pattern);
+            qb.push(")");
         }
 
         // การ Sort (ต้องระวัง SQL Injection ตรงชื่อ Column!)
@@ -107,8 +215,12 @@ impl UserRepository {
         let mut count_qb = QueryBuilder::new("SELECT COUNT(*) FROM users WHERE 1 = 1");
 
         if let Some(search) = &opts.search {
-            count_qb.push(" AND username ILIKE ");
-            count_qb.push_bind(format!("%{}%", search));
+            let pattern = format!("%{}%", search);
+            count_qb.push(" AND (username ILIKE ");
+            count_qb.push_bind(pattern.clone());
+            count_qb.push(" OR email ILIKE ");
+            count_qb.push_bind(pattern);
+            count_qb.push(")");
         }
 
         let count_row: (i64,) = count_qb.build_query_as().fetch_one(&self.pool).await?;
@@ -118,34 +230,57 @@ impl UserRepository {
         Ok((users, total))
     }
 
+    #[instrument(skip_all, err)]
     pub async fn update_user(
         &self,
         user_id: Uuid,
-        new_username: Option<&str>,
+        changes: UserUpdate,
+        expected_versions: Option<Vec<i32>>,
     ) -> Result<UserEntity, sqlx::Error> {
-        let now = chrono::Utc::now();
-        sqlx::query_as!(
-            UserEntity,
-            r#"
-        UPDATE users 
-        SET 
-            username = COALESCE($1, username), 
-            updated_at = $2 
-        WHERE id = $3 
-        RETURNING *
-        "#,
-            new_username,
-            now,
-            user_id
-        )
-        .fetch_one(&self.pool)
-        .await
+        let email_changed = changes.email.is_some();
+
+        let mut update = UpdateBuilder::new("users");
+        update
+            .set_if("username", changes.username)
+            .set_if("email", changes.email)
+            .set_patch("display_name", changes.display_name)
+            .set_patch("phone", changes.phone)
+            .set_patch("locale", changes.locale);
+        if email_changed {
+            update.set_expr("email_verified_at", "NULL");
+        }
+        update
+            .set_expr("version", "version + 1")
+            .set_expr("updated_at", "NOW()");
+
+        let mut qb = update.into_query();
+        qb.push(" WHERE id = ");
+        qb.push_bind(user_id);
+        if let Some(versions) = expected_versions {
+            qb.push(" AND version = ANY(");
+            qb.push_bind(versions);
+            qb.push(")");
+        }
+        qb.push(" RETURNING *");
+
+        qb.build_query_as::<UserEntity>()
+            .fetch_one(&self.pool)
+            .await
     }
 
-    pub async fn delete_user(&self, user_id: Uuid) -> Result<(), sqlx::Error> {
-        sqlx::query!("DELETE FROM users WHERE id = $1", user_id)
-            .execute(&self.pool)
-            .await?;
-        Ok(())
+    #[instrument(skip_all, err)]
+    pub async fn delete_user(
+        &self,
+        user_id: Uuid,
+        expected_versions: Option<Vec<i32>>,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM users WHERE id = $1 AND ($2::int4[] IS NULL OR version = ANY($2))",
+            user_id,
+            expected_versions.as_deref()
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
     }
 }
@@ -1,21 +1,64 @@
-use crate::controllers::{auth_controller, products_controller, user_controller};
-use crate::middleware::auth::auth_middleware;
+use crate::controllers::{
+    address_controller, auth_controller, cart_controller, currency_controller, docs_controller,
+    health_controller, meta_controller, metrics_controller, order_controller, payment_controller,
+    products_controller, stats_controller, user_controller,
+};
+use crate::middleware::{
+    admin::admin_middleware, auth::auth_middleware, http_metrics, idempotency, locale,
+    rate_limit, request_trace,
+};
+use crate::openapi::OPENAPI;
 use crate::{config::AppState, controllers::categories_controller};
 use axum::{
-    Router, middleware as axum_middleware,
+    Router,
+    extract::DefaultBodyLimit,
+    middleware as axum_middleware,
     routing::{delete, get, patch, post, put},
 };
+use tower::ServiceBuilder;
+use utoipa_redoc::{Redoc, Servable};
+use tower_http::{
+    request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer},
+    trace::TraceLayer,
+};
+
+// ขนาดไฟล์ Import สูงสุด (ค่า default ของ axum คือ 2MB)
+const IMPORT_BODY_LIMIT: usize = 20 * 1024 * 1024;
 
-pub fn create_routes() -> Router<AppState> {
+// รับ state เข้ามาด้วยเพราะ middleware (เช่น auth) ต้องใช้ Settings
+pub fn create_routes(state: AppState) -> Router {
     Router::new()
-        .nest("/auth", auth_routes())
-        .nest("/users", user_routes())
-        .nest("/categories", categories_routes())
-        .nest("/products", products_routes())
-        .route("/healthz", axum::routing::get(health_check))
+        .nest("/auth", auth_routes(&state))
+        .nest("/users", user_routes(&state))
+        .nest("/categories", categories_routes(&state))
+        .nest("/products", products_routes(&state))
+        .nest("/cart", cart_routes(&state))
+        .nest("/orders", order_routes(&state))
+        .nest("/payments", payment_routes())
+        .nest("/admin", admin_routes(&state))
+        .nest("/healthz", health_routes())
+        .nest("/meta", meta_routes())
+        .route("/metrics", get(metrics_controller::metrics_handler))
+        // เอกสาร API: spec ที่ /openapi.json และหน้า Redoc ที่ /docs
+        .route("/openapi.json", get(docs_controller::openapi_handler))
+        .merge(Redoc::with_url("/docs", OPENAPI.clone()))
+        .layer(axum_middleware::from_fn(locale::locale))
+        .layer(axum_middleware::from_fn(http_metrics::track_metrics))
+        // ตั้ง x-request-id (ถ้า client ไม่ได้ส่งมา) -> เปิด span -> ส่ง id กลับใน response
+        .layer(
+            ServiceBuilder::new()
+                .layer(SetRequestIdLayer::x_request_id(MakeRequestUuid))
+                .layer(
+                    TraceLayer::new_for_http()
+                        .make_span_with(request_trace::make_request_span)
+                        .on_response(request_trace::on_response),
+                )
+                .layer(PropagateRequestIdLayer::x_request_id()),
+        )
+        .with_state(state)
 }
 
-fn auth_routes() -> Router<AppState> {
+fn auth_routes(state: &AppState) -> Router<AppState> {
     Router::new()
         .route(
             "/register",
@@ -25,19 +68,49 @@ fn auth_routes() -> Router<AppState> {
             "/login",
             axum::routing::post(auth_controller::login_handler),
         )
+        .route(
+            "/verify-email",
+            get(auth_controller::verify_email_link_handler)
+                .post(auth_controller::verify_email_handler),
+        )
+        .layer(axum_middleware::from_fn_with_state(
+            state.clone(),
+            rate_limit::auth_rate_limit,
+        ))
 }
 
-fn user_routes() -> Router<AppState> {
+fn user_routes(state: &AppState) -> Router<AppState> {
     Router::new()
         .route("/me", get(user_controller::get_me_handler))
         .route("/me", put(user_controller::update_me_handler))
         .route("/me", delete(user_controller::delete_me_handler))
+        // ส่งอีเมลได้ จึงจำกัดความถี่เหมือน /auth
+        .route(
+            "/me/email/verification",
+            post(user_controller::resend_verification_handler).layer(
+                axum_middleware::from_fn_with_state(state.clone(), rate_limit::auth_rate_limit),
+            ),
+        )
+        .route(
+            "/me/addresses",
+            get(address_controller::list_addresses_handler)
+                .post(address_controller::create_address_handler),
+        )
+        .route(
+            "/me/addresses/:id",
+            get(address_controller::get_address_handler)
+                .patch(address_controller::update_address_handler)
+                .delete(address_controller::delete_address_handler),
+        )
         .route("/all", get(user_controller::list_users_handler))
         .route("/", get(user_controller::get_users_handler))
-        .layer(axum_middleware::from_fn(auth_middleware))
+        .layer(axum_middleware::from_fn_with_state(
+            state.clone(),
+            auth_middleware,
+        ))
 }
 
-fn categories_routes() -> Router<AppState> {
+fn categories_routes(state: &AppState) -> Router<AppState> {
     Router::new()
         .route("/:id", get(categories_controller::get_category_handler))
         .route("/", get(categories_controller::get_categories_handler))
@@ -50,21 +123,155 @@ fn categories_routes() -> Router<AppState> {
             "/:id",
             patch(categories_controller::update_categories_handler),
         )
-        .layer(axum_middleware::from_fn(auth_middleware))
+        .route(
+            "/:id/restore",
+            post(categories_controller::restore_category_handler),
+        )
+        .layer(axum_middleware::from_fn_with_state(
+            state.clone(),
+            auth_middleware,
+        ))
 }
 
-fn products_routes() -> Router<AppState> {
+fn products_routes(state: &AppState) -> Router<AppState> {
     Router::new()
-        .route("/", post(products_controller::create_product_handler))
+        // route ที่สร้างข้อมูลใหม่รับ Idempotency-Key (client retry แล้วไม่ได้ของซ้ำ)
+        .route(
+            "/",
+            post(products_controller::create_product_handler).layer(
+                axum_middleware::from_fn_with_state(state.clone(), idempotency::idempotency),
+            ),
+        )
         .route("/", get(products_controller::list_products_handler))
         .route("/:id", get(products_controller::get_product_handler))
         .route("/:id", patch(products_controller::update_product_handler))
         .route("/:id", delete(products_controller::delete_product_handler))
+        .route("/:id/restore", post(products_controller::restore_product_handler))
+        .route(
+            "/:id/restock-subscription",
+            post(products_controller::subscribe_restock_handler),
+        )
+        .route(
+            "/:id/restock-subscription",
+            delete(products_controller::unsubscribe_restock_handler),
+        )
         .route("/search", get(products_controller::search_products_handler))
         .route("/sync", get(products_controller::sync_products_handler))
-        .layer(axum_middleware::from_fn(auth_middleware))
+        .layer(axum_middleware::from_fn_with_state(
+            state.clone(),
+            auth_middleware,
+        ))
+}
+
+fn cart_routes(state: &AppState) -> Router<AppState> {
+    Router::new()
+        .route("/", get(cart_controller::get_cart_handler))
+        .route(
+            "/",
+            post(cart_controller::add_to_cart_handler).layer(
+                axum_middleware::from_fn_with_state(state.clone(), idempotency::idempotency),
+            ),
+        )
+        .route(
+            "/items/:id",
+            patch(cart_controller::update_cart_item_handler)
+                .delete(cart_controller::remove_cart_item_handler),
+        )
+        .route(
+            "/shipping-options",
+            get(cart_controller::shipping_options_handler),
+        )
+        .layer(axum_middleware::from_fn_with_state(
+            state.clone(),
+            auth_middleware,
+        ))
+        // ลิงก์ในข้อความแจ้งเตือนไม่ต้อง login (token ระบุตัวแล้ว) จึงเพิ่มหลัง auth layer
+        .route(
+            "/recover",
+            get(cart_controller::recover_cart_handler).layer(
+                axum_middleware::from_fn_with_state(state.clone(), rate_limit::auth_rate_limit),
+            ),
+        )
+}
+
+fn order_routes(state: &AppState) -> Router<AppState> {
+    Router::new()
+        .route("/", get(order_controller::list_orders_handler))
+        .route(
+            "/checkout",
+            post(order_controller::checkout_handler).layer(
+                axum_middleware::from_fn_with_state(state.clone(), idempotency::idempotency),
+            ),
+        )
+        .route("/:id", get(order_controller::get_order_handler))
+        .route(
+            "/:id/payments",
+            post(order_controller::retry_payment_handler).layer(
+                axum_middleware::from_fn_with_state(state.clone(), idempotency::idempotency),
+            ),
+        )
+        .layer(axum_middleware::from_fn_with_state(
+            state.clone(),
+            auth_middleware,
+        ))
+}
+
+// Webhook จาก Payment provider ไม่มี JWT (ตรวจลายเซ็นใน service แทน)
+fn payment_routes() -> Router<AppState> {
+    Router::new().route("/webhook/:provider", post(payment_controller::webhook_handler))
+}
+
+// ต้องผ่าน auth ก่อนแล้วค่อยเช็คสิทธิ์ Admin (layer ที่ใส่ทีหลังจะทำงานก่อน)
+fn admin_routes(state: &AppState) -> Router<AppState> {
+    Router::new()
+        .route(
+            "/products/import",
+            post(products_controller::import_products_handler)
+                .layer(DefaultBodyLimit::max(IMPORT_BODY_LIMIT)),
+        )
+        .route(
+            "/products/export",
+            get(products_controller::export_products_handler),
+        )
+        .route(
+            "/orders/:id/refund",
+            post(order_controller::refund_order_handler),
+        )
+        .route(
+            "/exchange-rates",
+            get(currency_controller::list_rates_handler),
+        )
+        .route(
+            "/exchange-rates/:currency",
+            put(currency_controller::set_rate_handler)
+                .delete(currency_controller::delete_rate_handler),
+        )
+        .route("/stats/signups", get(stats_controller::signups_handler))
+        .route("/stats/carts", get(stats_controller::carts_handler))
+        .route(
+            "/stats/top-products",
+            get(stats_controller::top_products_handler),
+        )
+        .route("/stats/categories", get(stats_controller::categories_handler))
+        .route("/stats/revenue", get(stats_controller::revenue_handler))
+        .route(
+            "/stats/abandoned-carts",
+            get(stats_controller::abandoned_carts_handler),
+        )
+        .layer(axum_middleware::from_fn(admin_middleware))
+        .layer(axum_middleware::from_fn_with_state(
+            state.clone(),
+            auth_middleware,
+        ))
+}
+
+fn health_routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(health_controller::live_handler)) // path เดิม เก็บไว้ให้ probe ที่ตั้งไว้แล้ว
+        .route("/live", get(health_controller::live_handler))
+        .route("/ready", get(health_controller::ready_handler))
 }
 
-async fn health_check() -> &'static str {
-    "Service is running healthy!"
+fn meta_routes() -> Router<AppState> {
+    Router::new().route("/error-codes", get(meta_controller::error_codes_handler))
 }
@@ -0,0 +1,165 @@
+use crate::i18n;
+use crate::models::dto::{AddressRequest, AddressResponse, AddressSnapshot, UpdateAddressRequest};
+use crate::models::error::AppError;
+use crate::repositories::address_repository::AddressRepository;
+use crate::utils::address;
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+// ที่อยู่ต่อ user มีได้ไม่เกินนี้
+const MAX_ADDRESSES_PER_USER: usize = 20;
+
+#[derive(Clone)]
+pub struct AddressService {
+    repo: AddressRepository,
+}
+
+impl AddressService {
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        let repo = AddressRepository::new(pool);
+        Self { repo }
+    }
+
+    pub async fn list_addresses(&self, user_id: Uuid) -> Result<Vec<AddressResponse>, AppError> {
+        let addresses = self
+            .repo
+            .list(user_id)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        Ok(addresses.into_iter().map(AddressResponse::from).collect())
+    }
+
+    pub async fn get_address(
+        &self,
+        user_id: Uuid,
+        address_id: Uuid,
+    ) -> Result<AddressResponse, AppError> {
+        let address = self
+            .repo
+            .find(user_id, address_id)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .ok_or(AppError::NotFound("error.address.not_found".into()))?;
+
+        Ok(AddressResponse::from(address))
+    }
+
+    pub async fn create_address(
+        &self,
+        user_id: Uuid,
+        mut req: AddressRequest,
+    ) -> Result<AddressResponse, AppError> {
+        address::normalize(&mut req)?;
+
+        let existing = self
+            .repo
+            .list(user_id)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        if existing.len() >= MAX_ADDRESSES_PER_USER {
+            return Err(AppError::ValidationError(i18n::t_args(
+                "error.address.limit",
+                &[("max", &MAX_ADDRESSES_PER_USER.to_string())],
+            )));
+        }
+
+        let created = self
+            .repo
+            .create(user_id, &req)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        Ok(AddressResponse::from(created))
+    }
+
+    pub async fn update_address(
+        &self,
+        user_id: Uuid,
+        address_id: Uuid,
+        req: UpdateAddressRequest,
+    ) -> Result<AddressResponse, AppError> {
+        let current = self
+            .repo
+            .find(user_id, address_id)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .ok_or(AppError::NotFound("error.address.not_found".into()))?;
+
+        // default ต้องมีอยู่ 1 อันเสมอ: เปลี่ยนได้ด้วยการตั้งอันอื่นเป็น default เท่านั้น
+        if (current.is_default_shipping && req.is_default_shipping == Some(false))
+            || (current.is_default_billing && req.is_default_billing == Some(false))
+        {
+            return Err(AppError::ValidationError(
+                "error.address.default_required".into(),
+            ));
+        }
+
+        // รวมกับค่าเดิมแล้ว validate ทั้งก้อน (เช่น เปลี่ยนประเทศต้องตรวจรหัสไปรษณีย์ใหม่)
+        let mut merged = AddressRequest {
+            label: req.label.apply_to(current.label),
+            recipient_name: req.recipient_name.unwrap_or(current.recipient_name),
+            line1: req.line1.unwrap_or(current.line1),
+            line2: req.line2.apply_to(current.line2),
+            city: req.city.unwrap_or(current.city),
+            region: req.region.apply_to(current.region),
+            postal_code: req.postal_code.unwrap_or(current.postal_code),
+            country: req.country.unwrap_or(current.country),
+            phone: req.phone.apply_to(current.phone),
+            is_default_shipping: req.is_default_shipping.unwrap_or(current.is_default_shipping),
+            is_default_billing: req.is_default_billing.unwrap_or(current.is_default_billing),
+        };
+        address::normalize(&mut merged)?;
+
+        let updated = self
+            .repo
+            .update(user_id, address_id, &merged)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .ok_or(AppError::NotFound("error.address.not_found".into()))?;
+
+        Ok(AddressResponse::from(updated))
+    }
+
+    pub async fn delete_address(&self, user_id: Uuid, address_id: Uuid) -> Result<(), AppError> {
+        let deleted = self
+            .repo
+            .delete(user_id, address_id)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        if !deleted {
+            return Err(AppError::NotFound("error.address.not_found".into()));
+        }
+        Ok(())
+    }
+
+    // สำหรับ Checkout: copy ที่อยู่ ณ ตอนสั่งซื้อ
+    pub async fn snapshot(
+        &self,
+        user_id: Uuid,
+        address_id: Uuid,
+    ) -> Result<AddressSnapshot, AppError> {
+        let address = self
+            .repo
+            .find(user_id, address_id)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .ok_or(AppError::ValidationError("error.address.not_found".into()))?;
+
+        Ok(AddressSnapshot::from(address))
+    }
+
+    pub async fn default_shipping_snapshot(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Option<AddressSnapshot>, AppError> {
+        let address = self
+            .repo
+            .find_default_shipping(user_id)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        Ok(address.map(AddressSnapshot::from))
+    }
+}
@@ -1,53 +1,187 @@
+use crate::config::settings::JwtSettings;
+use crate::constants::{ROLE_ADMIN, WRONG_PASSWORD_MSG};
+use crate::i18n;
 use crate::models::dto::{LoginRequest, LoginResponse, RegisterRequest};
 use crate::models::error::AppError;
 use crate::repositories::user_repository::UserRepository;
-use crate::utils::jwt;
+use crate::services::password_hasher::PasswordHasher;
+use crate::services::rate_limiter::RateLimiter;
+use crate::services::user_service::{user_write_error, validate_username};
+use crate::utils::{contact, jwt};
+use crate::utils::password_policy::PasswordPolicy;
 use sqlx::{Pool, Postgres};
+use std::sync::{Arc, OnceLock};
 use uuid::Uuid;
 
+#[derive(Clone)]
 pub struct AuthService {
     repo: UserRepository,
+    jwt_settings: JwtSettings,
+    rate_limiter: RateLimiter,
+    hasher: Arc<dyn PasswordHasher>,
+    policy: PasswordPolicy,
+    dummy_hash: Arc<OnceLock<String>>,
 }
 
 impl AuthService {
-    pub fn new(pool: Pool<Postgres>) -> Self {
+    pub fn new(
+        pool: Pool<Postgres>,
+        jwt_settings: JwtSettings,
+        rate_limiter: RateLimiter,
+        hasher: Arc<dyn PasswordHasher>,
+        policy: PasswordPolicy,
+    ) -> Self {
         let repo = UserRepository::new(pool);
-        Self { repo }
+        Self {
+            repo,
+            jwt_settings,
+            rate_limiter,
+            hasher,
+            policy,
+            dummy_hash: Arc::new(OnceLock::new()),
+        }
+    }
+
+    // hash/verify กิน CPU หลายสิบ ms ห้ามรันบน async worker ตรง ๆ
+    async fn hash_password(&self, password: String) -> Result<String, AppError> {
+        let hasher = self.hasher.clone();
+        tokio::task::spawn_blocking(move || hasher.hash(&password))
+            .await
+            .map_err(|e| AppError::InternalServerError(e.to_string()))?
+    }
+
+    async fn verify_password(&self, password: String, hash: String) -> Result<bool, AppError> {
+        let hasher = self.hasher.clone();
+        tokio::task::spawn_blocking(move || hasher.verify(&password, &hash))
+            .await
+            .map_err(|e| AppError::InternalServerError(e.to_string()))
+    }
+
+    // ใช้ verify ตอนไม่เจอ user เพื่อให้เวลาตอบพอ ๆ กับกรณีรหัสผิด (เดา username จากเวลาไม่ได้)
+    async fn dummy_hash(&self) -> Result<String, AppError> {
+        if let Some(hash) = self.dummy_hash.get() {
+            return Ok(hash.clone());
+        }
+        let hash = self.hash_password("dummy-password".into()).await?;
+        Ok(self.dummy_hash.get_or_init(|| hash).clone())
     }
 
-    pub async fn register(&self, req: RegisterRequest) -> Result<(), AppError> {
+    // คืน id ของ user ใหม่ ให้ controller ส่งอีเมลยืนยันต่อ
+    pub async fn register(&self, req: RegisterRequest) -> Result<Uuid, AppError> {
+        let username = validate_username(&req.username)?;
+        let email = contact::normalize_email(&req.email)?;
+        let display_name = req
+            .display_name
+            .as_deref()
+            .map(contact::normalize_display_name)
+            .transpose()?;
+        let phone = req.phone.as_deref().map(contact::normalize_phone).transpose()?;
+        self.policy.validate(&username, &req.password)?;
+
         //Hash Password
-        let hash = bcrypt::hash(req.password, 4)
-            .map_err(|e| AppError::InternalServerError(e.to_string()))?;
+        let hash = self.hash_password(req.password).await?;
 
         //Save to DB
-        self.repo
-            .create_user(&req.username, &hash)
+        let user = self
+            .repo
+            .create_user(
+                &username,
+                &hash,
+                &email,
+                display_name.as_deref(),
+                phone.as_deref(),
+            )
+            .await
+            .map_err(user_write_error)?;
+
+        metrics::counter!("shop_user_registrations_total").increment(1);
+        Ok(user.id)
+    }
+
+    // ใช้จาก CLI `create-admin` เท่านั้น (ไม่มี endpoint ให้สมัครเป็น admin)
+    pub async fn create_admin(&self, username: &str, password: &str) -> Result<Uuid, AppError> {
+        let username = &validate_username(username)?;
+        self.policy.validate(username, password)?;
+
+        let existing = self
+            .repo
+            .find_by_username(username)
             .await
             .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        if existing.is_some() {
+            return Err(AppError::Conflict(i18n::t_args(
+                "error.user.username_taken",
+                &[("username", username)],
+            )));
+        }
+
+        let hash = self.hash_password(password.to_string()).await?;
+
+        let user = self
+            .repo
+            .create_user_with_role(username, &hash, ROLE_ADMIN)
+            .await
+            .map_err(user_write_error)?;
 
-        Ok(())
+        Ok(user.id)
     }
 
     pub async fn login(&self, req: LoginRequest) -> Result<LoginResponse, AppError> {
-        //Find User
+        //Find User (username หรืออีเมล)
         let user = self
             .repo
-            .find_by_username(&req.username)
+            .find_by_login(req.username.trim())
             .await
-            .map_err(|e| AppError::DatabaseError(e.to_string()))?
-            .ok_or(AppError::AuthError("User not found".into()))?;
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        // บัญชีถูกล็อก / ยิงถี่เกิน -> 429 ก่อนตรวจรหัสผ่าน
+        // นับต่อ user id: login ด้วย username หรืออีเมลของคนเดียวกันใช้โควตาเดียวกัน
+        let account = match &user {
+            Some(user) => user.id.to_string(),
+            None => req.username.clone(),
+        };
+        self.rate_limiter.check_account(&account).await?;
+
+        let Some(user) = user else {
+            let dummy_hash = self.dummy_hash().await?;
+            let _ = self.verify_password(req.password, dummy_hash).await;
+            metrics::counter!("shop_logins_total", "outcome" => "user_not_found").increment(1);
+            self.rate_limiter.record_login_failure(&account).await?;
+            // ข้อความเดียวกับรหัสผิด ไม่บอกว่า username มีอยู่จริงหรือไม่
+            return Err(AppError::AuthError(WRONG_PASSWORD_MSG.into()));
+        };
 
         //Verify Password
-        let valid = bcrypt::verify(req.password, &user.password_hash).unwrap_or(false);
+        let valid = self
+            .verify_password(req.password.clone(), user.password_hash.clone())
+            .await?;
         if !valid {
-            return Err(AppError::AuthError("Invalid password".into()));
+            metrics::counter!("shop_logins_total", "outcome" => "invalid_password").increment(1);
+            self.rate_limiter.record_login_failure(&account).await?;
+            return Err(AppError::AuthError(WRONG_PASSWORD_MSG.into()));
+        }
+        self.rate_limiter.record_login_success(&account).await?;
+
+        // hash เก่า (bcrypt / cost ต่ำกว่า config) -> hash ใหม่ตอนที่ยังมีรหัสผ่านจริงอยู่ในมือ
+        // ถ้าพลาดก็แค่ log ไว้ ไม่ทำให้ login ล้ม (รอบหน้าจะลองใหม่)
+        if self.hasher.needs_rehash(&user.password_hash) {
+            match self.hash_password(req.password).await {
+                Ok(new_hash) => {
+                    if let Err(e) = self.repo.update_password_hash(user.id, &new_hash).await {
+                        tracing::warn!(error = %e, "failed to store rehashed password");
+                    } else {
+                        metrics::counter!("shop_password_rehashes_total").increment(1);
+                    }
+                }
+                Err(e) => tracing::warn!(error = %e, "failed to rehash password"),
+            }
         }
 
         // Generate JWT
-        let token =
-            jwt::encode_jwt(user.id).map_err(|e| AppError::InternalServerError(e.to_string()))?;
+        let token = jwt::encode_jwt(user.id, &user.role, user.locale.as_deref(), &self.jwt_settings)
+            .map_err(|e| AppError::InternalServerError(e.to_string()))?;
 
+        metrics::counter!("shop_logins_total", "outcome" => "success").increment(1);
         Ok(LoginResponse { token })
     }
 }
@@ -0,0 +1,130 @@
+use crate::config::settings::{CartRecoverySettings, JwtSettings};
+use crate::i18n::{self, Locale};
+use crate::models::dto::CartRecoveryResponse;
+use crate::models::entity::CartRecoveryEntity;
+use crate::models::error::AppError;
+use crate::repositories::cart_recovery_repository::CartRecoveryRepository;
+use crate::services::notifier::{Notification, Notifier, Recipient};
+use crate::utils::{jwt, money};
+use sqlx::{Pool, Postgres};
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct CartRecoveryService {
+    repo: CartRecoveryRepository,
+    notifier: Arc<dyn Notifier>,
+    settings: CartRecoverySettings,
+    jwt_settings: JwtSettings,
+    currency: String,
+}
+
+impl CartRecoveryService {
+    pub fn new(
+        pool: Pool<Postgres>,
+        notifier: Arc<dyn Notifier>,
+        settings: CartRecoverySettings,
+        jwt_settings: JwtSettings,
+        currency: String,
+    ) -> Self {
+        Self {
+            repo: CartRecoveryRepository::new(pool),
+            notifier,
+            settings,
+            jwt_settings,
+            currency,
+        }
+    }
+
+    // แจ้งเตือนเจ้าของตะกร้าที่ถูกทิ้งไว้ (ตะกร้าละครั้งต่อช่วงที่ไม่เคลื่อนไหว) คืนจำนวนที่ส่งสำเร็จ
+    pub async fn notify_abandoned(&self) -> Result<usize, AppError> {
+        if !self.settings.enabled {
+            return Ok(0);
+        }
+
+        let recoveries = self
+            .repo
+            .claim_idle_carts(
+                self.settings.idle_hours,
+                self.settings.max_idle_hours,
+                self.settings.batch_size,
+                &self.currency,
+                self.settings.link_ttl_hours,
+            )
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        let mut sent = 0;
+        for recovery in recoveries {
+            match self.send(&recovery).await {
+                Ok(()) => sent += 1,
+                Err(e) => {
+                    tracing::warn!(cart_id = %recovery.cart_id, error = %e, "could not send cart recovery notification");
+                    if let Err(e) = self.repo.delete(recovery.id).await {
+                        tracing::error!(recovery_id = %recovery.id, error = %e, "could not release cart recovery");
+                    }
+                }
+            }
+        }
+        metrics::counter!("shop_cart_recoveries_sent_total").increment(sent as u64);
+
+        Ok(sent)
+    }
+
+    async fn send(&self, recovery: &CartRecoveryEntity) -> Result<(), AppError> {
+        let token = jwt::encode_cart_recovery(
+            recovery.user_id,
+            recovery.id,
+            recovery.expires_at,
+            &self.jwt_settings,
+        )
+        .map_err(|e| AppError::InternalServerError(e.to_string()))?;
+
+        // Job ไม่มี request: ใช้ภาษาที่ผู้รับตั้งไว้ (รวมรูปแบบราคา)
+        let locale = self
+            .repo
+            .user_locale(recovery.user_id)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .as_deref()
+            .and_then(Locale::parse)
+            .unwrap_or_default();
+        let link = format!("{}?token={}", self.settings.recovery_url, token);
+        let notification = i18n::scope(locale, async {
+            Notification {
+                recipient: Recipient::User(recovery.user_id),
+                subject: i18n::t("email.cart_recovery.subject"),
+                body: i18n::t_args(
+                    "email.cart_recovery.body",
+                    &[
+                        ("count", &recovery.item_count.to_string()),
+                        ("value", &money::format(recovery.cart_value, &recovery.currency)),
+                        ("link", &link),
+                    ],
+                ),
+            }
+        })
+        .await;
+
+        self.notifier.send(notification).await
+    }
+
+    // GET /cart/recover?token=... (ลิงก์ในข้อความแจ้งเตือน) บันทึกว่าเปิดลิงก์แล้ว
+    pub async fn open(&self, token: &str) -> Result<CartRecoveryResponse, AppError> {
+        let invalid = || AppError::ValidationError("error.cart.invalid_recovery_token".into());
+
+        let claims = jwt::decode_cart_recovery(token, &self.jwt_settings)?;
+        let user_id = Uuid::parse_str(&claims.sub).map_err(|_| invalid())?;
+        let recovery_id = Uuid::parse_str(&claims.rid).map_err(|_| invalid())?;
+
+        let recovery = self
+            .repo
+            .mark_clicked(recovery_id, user_id)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .ok_or_else(invalid)?;
+        metrics::counter!("shop_cart_recoveries_opened_total").increment(1);
+
+        Ok(CartRecoveryResponse::from(recovery))
+    }
+}
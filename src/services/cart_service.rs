@@ -3,6 +3,8 @@ use crate::models::dto::{
 };
 use crate::models::error::AppError;
 use crate::repositories::cart_repository::CartRepository;
+use crate::services::tax_service::TaxService;
+use crate::utils::tax::TaxSummary;
 use rust_decimal::Decimal;
 use sqlx::{Pool, Postgres};
 use uuid::Uuid;
@@ -10,12 +12,21 @@ use uuid::Uuid;
 #[derive(Clone)]
 pub struct CartService {
     repo: CartRepository,
+    tax_service: TaxService,
 }
 
 impl CartService {
-    pub fn new(pool: Pool<Postgres>) -> Self {
+    pub fn new(pool: Pool<Postgres>, tax_service: TaxService) -> Self {
         let repo = CartRepository::new(pool);
-        Self { repo }
+        Self { repo, tax_service }
+    }
+
+    // แก้/ลบได้เฉพาะ item ในตะกร้าของตัวเอง
+    async fn cart_id(&self, user_id: Uuid) -> Result<Uuid, AppError> {
+        self.repo
+            .get_or_create_cart_id(user_id)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))
     }
 
     async fn get_cart_response(&self, user_id: Uuid) -> Result<CartResponse, AppError> {
@@ -31,8 +42,11 @@ impl CartService {
             .await
             .map_err(|e| AppError::DatabaseError(e.to_string()))?;
 
+        let tax = self.tax_service.calculator_for_user(user_id).await?;
+
         let mut total_price = Decimal::ZERO;
         let mut total_items = 0;
+        let mut lines = Vec::with_capacity(items.len());
 
         let item_responses: Vec<CartItemResponse> = items
             .into_iter()
@@ -43,24 +57,39 @@ impl CartService {
                 total_price += subtotal;
                 total_items += item.quantity;
 
-                CartItemResponse {
+                let line = tax.line(&item.tax_class, item.price, item.quantity);
+                let response = CartItemResponse {
                     item_id: item.item_id,
                     product_id: item.product_id,
                     product_name: item.product_name,
                     price: item.price,
                     quantity: item.quantity,
                     subtotal,
-                }
+                    tax_class: item.tax_class,
+                    tax_name: line.name.clone(),
+                    tax_rate: line.rate,
+                    tax_inclusive: line.inclusive,
+                    tax_amount: line.tax,
+                };
+                lines.push(line);
+                response
             })
             .collect();
 
-        Ok(CartResponse {
+        let mut response = CartResponse {
             id: cart_id,
             user_id,
             items: item_responses,
             total_price,
             total_items,
-        })
+            tax_total: Decimal::ZERO,
+            grand_total: total_price,
+            taxes: Vec::new(),
+            currency: None,
+            formatted_total: None,
+        };
+        response.apply_tax_summary(TaxSummary::from_lines(&lines));
+        Ok(response)
     }
 
     pub async fn get_cart(&self, user_id: Uuid) -> Result<CartResponse, AppError> {
@@ -83,6 +112,9 @@ impl CartService {
             .await
             .map_err(|e| AppError::DatabaseError(e.to_string()))?;
 
+        metrics::counter!("shop_cart_add_events_total").increment(1);
+        metrics::counter!("shop_cart_items_added_total").increment(req.quantity.max(0) as u64);
+
         self.get_cart_response(user_id).await
     }
 
@@ -92,14 +124,15 @@ impl CartService {
         item_id: Uuid,
         req: UpdateCartItemRequest,
     ) -> Result<CartResponse, AppError> {
+        let cart_id = self.cart_id(user_id).await?;
         let updated = self
             .repo
-            .update_item_quantity(item_id, req.quantity)
+            .update_item_quantity(cart_id, item_id, req.quantity)
             .await
             .map_err(|e| AppError::DatabaseError(e.to_string()))?;
 
         if !updated {
-            return Err(AppError::NotFound("Cart item not found".into()));
+            return Err(AppError::NotFound("error.cart.item_not_found".into()));
         }
 
         self.get_cart_response(user_id).await
@@ -110,14 +143,15 @@ impl CartService {
         user_id: Uuid, 
         item_id: Uuid
     ) -> Result<CartResponse, AppError> {
+        let cart_id = self.cart_id(user_id).await?;
         let deleted = self
             .repo
-            .delete_item(item_id)
+            .delete_item(cart_id, item_id)
             .await
             .map_err(|e| AppError::DatabaseError(e.to_string()))?;
 
         if !deleted {
-            return Err(AppError::NotFound("Cart item not found".into()));
+            return Err(AppError::NotFound("error.cart.item_not_found".into()));
         }
 
         self.get_cart_response(user_id).await
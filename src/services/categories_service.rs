@@ -1,8 +1,10 @@
+use crate::i18n;
 use crate::models::dto::{
-    CategoryRequest, CategoryResponse, FilterOptions, PagedResponse, UpdateCategoryRequest,
+    CategoryDeleteMode, CategoryRequest, CategoryResponse, DeleteCategoryOptions, FilterOptions,
+    PagedResponse, ProductIndexChanges, UpdateCategoryRequest,
 };
 use crate::models::error::AppError;
-use crate::repositories::categories_repository::CategoriesRepository;
+use crate::repositories::categories_repository::{CategoriesRepository, RestrictedDelete};
 use sqlx::{Pool, Postgres};
 use uuid::Uuid;
 
@@ -53,6 +55,7 @@ impl CategoriesService {
                 name: category.name,
                 description: category.description,
                 is_active: category.is_active,
+                version: category.version,
                 created_at: category.created_at,
                 updated_at: category.updated_at,
             })
@@ -78,37 +81,139 @@ impl CategoriesService {
             .find_by_id(categories_id)
             .await
             .map_err(|e| AppError::DatabaseError(e.to_string()))?
-            .ok_or(AppError::NotFound("Categories not found".into()))?;
+            .ok_or(AppError::NotFound("error.category.list_not_found".into()))?;
 
         Ok(categories.into())
     }
 
-    pub async fn delete_categories(&self, categories_id: Uuid) -> Result<(), AppError> {
-        let delete = self
+    // ปิดหมวดหมู่ตาม mode ที่เลือก คืนรายการที่ต้องแก้ใน Search Index
+    pub async fn delete_categories(
+        &self,
+        categories_id: Uuid,
+        expected_versions: Option<Vec<i32>>,
+        opts: DeleteCategoryOptions,
+    ) -> Result<ProductIndexChanges, AppError> {
+        match opts.mode.unwrap_or_default() {
+            CategoryDeleteMode::Restrict => {
+                let deleted = self
+                    .repo
+                    .soft_delete_if_empty(categories_id, expected_versions)
+                    .await
+                    .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+                match deleted {
+                    RestrictedDelete::Deleted => {}
+                    RestrictedDelete::NotUpdated => {
+                        return Err(self.missing_or_modified(categories_id).await);
+                    }
+                    RestrictedDelete::HasActiveProducts(count) => {
+                        return Err(AppError::Conflict(i18n::t_args(
+                            "error.category.has_active_products",
+                            &[("count", &count.to_string())],
+                        )));
+                    }
+                }
+                Ok(ProductIndexChanges::default())
+            }
+            CategoryDeleteMode::Deactivate => {
+                let removed = self
+                    .repo
+                    .soft_delete_cascade(categories_id, expected_versions)
+                    .await
+                    .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+                let Some(removed) = removed else {
+                    return Err(self.missing_or_modified(categories_id).await);
+                };
+                Ok(ProductIndexChanges {
+                    removed,
+                    upserted: Vec::new(),
+                })
+            }
+            CategoryDeleteMode::Reassign => {
+                let target_id = opts.reassign_to.ok_or(AppError::ValidationError(
+                    "error.category.reassign_to_required".into(),
+                ))?;
+                if target_id == categories_id {
+                    return Err(AppError::ValidationError(
+                        "error.category.reassign_to_same".into(),
+                    ));
+                }
+
+                let target = self.get_categories_by_id(target_id).await?;
+                if !target.is_active {
+                    return Err(AppError::ValidationError(
+                        "error.category.reassign_to_inactive".into(),
+                    ));
+                }
+
+                let moved = self
+                    .repo
+                    .soft_delete_reassign(categories_id, target_id, expected_versions)
+                    .await
+                    .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+                let Some(moved) = moved else {
+                    return Err(self.missing_or_modified(categories_id).await);
+                };
+
+                // สินค้าที่ปิดขายอยู่ไม่มีใน Index อยู่แล้ว
+                Ok(ProductIndexChanges {
+                    removed: Vec::new(),
+                    upserted: moved
+                        .into_iter()
+                        .filter(|p| p.is_active)
+                        .map(Into::into)
+                        .collect(),
+                })
+            }
+        }
+    }
+
+    pub async fn restore_category(
+        &self,
+        categories_id: Uuid,
+    ) -> Result<(CategoryResponse, ProductIndexChanges), AppError> {
+        let (category, products) = self
             .repo
-            .soft_delete(categories_id)
+            .restore(categories_id)
             .await
-            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
-        if !delete {
-            return Err(AppError::NotFound("Category not found".into()));
-        }
-        Ok(())
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .ok_or(AppError::NotFound("error.category.not_found".into()))?;
+
+        let changes = ProductIndexChanges {
+            removed: Vec::new(),
+            upserted: products.into_iter().map(Into::into).collect(),
+        };
+        Ok((category.into(), changes))
     }
 
     pub async fn update_categories(
         &self,
         categories_id: Uuid,
         req: UpdateCategoryRequest,
+        expected_versions: Option<Vec<i32>>,
     ) -> Result<CategoryResponse, AppError> {
-        let update = self
+        let update = match self
             .repo
-            .update_categories(categories_id, req)
+            .update_categories(categories_id, req, expected_versions)
             .await
-            .map_err(|e| match e {
-                sqlx::Error::RowNotFound => AppError::NotFound("Category not found".into()),
-                _ => AppError::DatabaseError(e.to_string()),
-            })?;
+        {
+            Ok(update) => update,
+            Err(sqlx::Error::RowNotFound) => {
+                return Err(self.missing_or_modified(categories_id).await);
+            }
+            Err(e) => return Err(AppError::DatabaseError(e.to_string())),
+        };
 
         Ok(update.into())
     }
+
+    // ไม่มีแถวถูกแก้: ถ้าหมวดหมู่ยังอยู่แปลว่า version ไม่ตรง
+    async fn missing_or_modified(&self, categories_id: Uuid) -> AppError {
+        match self.repo.find_by_id(categories_id).await {
+            Ok(Some(_)) => AppError::PreconditionFailed(
+                "error.category.modified".into(),
+            ),
+            Ok(None) => AppError::NotFound("error.category.not_found".into()),
+            Err(e) => AppError::DatabaseError(e.to_string()),
+        }
+    }
 }
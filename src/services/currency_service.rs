@@ -0,0 +1,112 @@
+use crate::models::dto::{CurrencyRatesResponse, ExchangeRateResponse};
+use crate::models::error::AppError;
+use crate::repositories::exchange_rate_repository::ExchangeRateRepository;
+use crate::utils::money::CurrencyConverter;
+use rust_decimal::Decimal;
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct CurrencyService {
+    repo: ExchangeRateRepository,
+    base_currency: String,
+}
+
+// ISO 4217: ตัวพิมพ์ใหญ่ 3 ตัว (รับตัวเล็กมาได้ แปลงให้)
+fn normalize_code(currency: &str) -> Result<String, AppError> {
+    let code = currency.trim().to_ascii_uppercase();
+    if code.len() != 3 || !code.chars().all(|c| c.is_ascii_uppercase()) {
+        return Err(AppError::ValidationError("error.currency.invalid_code".into()));
+    }
+    Ok(code)
+}
+
+impl CurrencyService {
+    pub fn new(pool: Pool<Postgres>, base_currency: String) -> Self {
+        Self {
+            repo: ExchangeRateRepository::new(pool),
+            base_currency,
+        }
+    }
+
+    pub fn base_currency(&self) -> &str {
+        &self.base_currency
+    }
+
+    // None = สกุลหลัก, สกุลอื่นต้องมีอัตราแลกเปลี่ยนที่ Admin ตั้งไว้
+    pub async fn converter(&self, currency: Option<&str>) -> Result<CurrencyConverter, AppError> {
+        let Some(currency) = currency else {
+            return Ok(CurrencyConverter::base(&self.base_currency));
+        };
+        let code = normalize_code(currency)?;
+        if code == self.base_currency {
+            return Ok(CurrencyConverter::base(&self.base_currency));
+        }
+
+        let rate = self
+            .repo
+            .find(&code)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .ok_or(AppError::ValidationError("error.currency.unsupported".into()))?;
+
+        Ok(CurrencyConverter {
+            currency: code,
+            rate: rate.rate,
+        })
+    }
+
+    // GET /admin/exchange-rates
+    pub async fn list_rates(&self) -> Result<CurrencyRatesResponse, AppError> {
+        let rates = self
+            .repo
+            .list()
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        Ok(CurrencyRatesResponse {
+            base_currency: self.base_currency.clone(),
+            rates: rates.into_iter().map(ExchangeRateResponse::from).collect(),
+        })
+    }
+
+    // PUT /admin/exchange-rates/:currency
+    pub async fn set_rate(
+        &self,
+        currency: &str,
+        rate: Decimal,
+        admin_id: Uuid,
+    ) -> Result<ExchangeRateResponse, AppError> {
+        let code = normalize_code(currency)?;
+        if code == self.base_currency {
+            return Err(AppError::ValidationError("error.currency.base_rate".into()));
+        }
+        if rate <= Decimal::ZERO {
+            return Err(AppError::ValidationError("error.currency.invalid_rate".into()));
+        }
+
+        let updated = self
+            .repo
+            .upsert(&code, rate, admin_id)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        tracing::info!(currency = %code, %rate, %admin_id, "exchange rate updated");
+        Ok(ExchangeRateResponse::from(updated))
+    }
+
+    // DELETE /admin/exchange-rates/:currency (เลิกรับสกุลนี้)
+    pub async fn delete_rate(&self, currency: &str) -> Result<(), AppError> {
+        let code = normalize_code(currency)?;
+        let deleted = self
+            .repo
+            .delete(&code)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        if !deleted {
+            return Err(AppError::NotFound("error.currency.not_found".into()));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,120 @@
+use crate::config::MIGRATOR;
+use crate::config::settings::HealthSettings;
+use crate::models::dto::{DependencyCheck, ReadinessReport};
+use meilisearch_sdk::client::Client;
+use sqlx::{Pool, Postgres};
+use std::collections::HashSet;
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+#[derive(Clone)]
+pub struct HealthService {
+    pool: Pool<Postgres>,
+    meilisearch: Client,
+    settings: HealthSettings,
+}
+
+impl HealthService {
+    pub fn new(pool: Pool<Postgres>, meilisearch: Client, settings: HealthSettings) -> Self {
+        Self {
+            pool,
+            meilisearch,
+            settings,
+        }
+    }
+
+    // เช็คทุก dependency พร้อมกัน แต่ละตัวมี timeout ของตัวเอง
+    pub async fn readiness(&self) -> ReadinessReport {
+        let timeout = Duration::from_millis(self.settings.check_timeout_ms);
+
+        let (database, meilisearch, migrations) = tokio::join!(
+            run_check("database", true, timeout, self.ping_database()),
+            run_check(
+                "meilisearch",
+                self.settings.require_meilisearch,
+                timeout,
+                self.ping_meilisearch()
+            ),
+            run_check("migrations", true, timeout, self.pending_migrations()),
+        );
+
+        let checks = vec![database, meilisearch, migrations];
+        let ready = checks.iter().all(|check| check.up || !check.required);
+
+        ReadinessReport { ready, checks }
+    }
+
+    async fn ping_database(&self) -> Result<Option<String>, String> {
+        sqlx::query("SELECT 1")
+            .execute(&self.pool)
+            .await
+            .map(|_| None)
+            .map_err(|e| e.to_string())
+    }
+
+    async fn ping_meilisearch(&self) -> Result<Option<String>, String> {
+        self.meilisearch
+            .health()
+            .await
+            .map(|health| Some(health.status))
+            .map_err(|e| e.to_string())
+    }
+
+    // เทียบ migration ที่ฝังใน binary กับที่ลง DB แล้ว (_sqlx_migrations)
+    async fn pending_migrations(&self) -> Result<Option<String>, String> {
+        let applied: HashSet<i64> =
+            sqlx::query_scalar::<_, i64>("SELECT version FROM _sqlx_migrations WHERE success")
+                .fetch_all(&self.pool)
+                .await
+                .map_err(|e| e.to_string())?
+                .into_iter()
+                .collect();
+
+        let pending: Vec<String> = MIGRATOR
+            .iter()
+            .filter(|migration| !applied.contains(&migration.version))
+            .map(|migration| migration.version.to_string())
+            .collect();
+
+        if pending.is_empty() {
+            Ok(None)
+        } else {
+            Err(format!("pending migrations: {}", pending.join(", ")))
+        }
+    }
+}
+
+async fn run_check(
+    name: &'static str,
+    required: bool,
+    timeout: Duration,
+    check: impl Future<Output = Result<Option<String>, String>>,
+) -> DependencyCheck {
+    let started = Instant::now();
+    let result = match tokio::time::timeout(timeout, check).await {
+        Ok(result) => result,
+        Err(_) => Err(format!("timed out after {}ms", timeout.as_millis())),
+    };
+    let latency_ms = started.elapsed().as_millis() as u64;
+
+    if let Err(e) = &result {
+        tracing::warn!(dependency = name, error = %e, "readiness check failed");
+    }
+
+    match result {
+        Ok(detail) => DependencyCheck {
+            name,
+            up: true,
+            required,
+            latency_ms,
+            detail,
+        },
+        Err(detail) => DependencyCheck {
+            name,
+            up: false,
+            required,
+            latency_ms,
+            detail: Some(detail),
+        },
+    }
+}
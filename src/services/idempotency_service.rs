@@ -0,0 +1,193 @@
+use crate::config::settings::IdempotencySettings;
+use crate::i18n;
+use crate::models::entity::IdempotencyKeyEntity;
+use crate::models::error::AppError;
+use crate::repositories::idempotency_repository::{IdempotencyRepository, KeyClaim};
+use sha2::{Digest, Sha256};
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+// ผลการจอง Idempotency-Key
+#[derive(Debug)]
+pub enum IdempotencyClaim {
+    New(Uuid),                      // request แรก ให้ทำงานจริงแล้วเรียก complete / release ด้วย claim token นี้
+    Replay(IdempotencyKeyEntity),   // เคยทำเสร็จแล้ว ตอบผลเดิม
+}
+
+// SHA-256 ของ method + path (รวม query string) + body (key เดิมต้องมากับ request เดิมเท่านั้น)
+pub fn fingerprint(method: &str, path: &str, body: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(method.as_bytes());
+    hasher.update(b"\n");
+    hasher.update(path.as_bytes());
+    hasher.update(b"\n");
+    hasher.update(body);
+    hex::encode(hasher.finalize())
+}
+
+// จองไม่ได้: ตัดสินจากแถวเดิมของ key
+fn resolve_existing(
+    existing: Option<IdempotencyKeyEntity>,
+    request_hash: &str,
+) -> Result<IdempotencyClaim, AppError> {
+    // ถูกลบไปพอดี (release / หมดอายุ) ให้ client ลองใหม่
+    let existing = existing.ok_or(AppError::Conflict(
+        "error.request.idempotency_in_progress".into(),
+    ))?;
+
+    if existing.request_hash != request_hash {
+        return Err(AppError::UnprocessableEntity(
+            "error.request.idempotency_key_reused".into(),
+        ));
+    }
+    if existing.status_code.is_none() {
+        return Err(AppError::Conflict(
+            "error.request.idempotency_in_progress".into(),
+        ));
+    }
+
+    Ok(IdempotencyClaim::Replay(existing))
+}
+
+#[derive(Clone)]
+pub struct IdempotencyService {
+    repo: IdempotencyRepository,
+    settings: IdempotencySettings,
+}
+
+impl IdempotencyService {
+    pub fn new(pool: Pool<Postgres>, settings: IdempotencySettings) -> Self {
+        let repo = IdempotencyRepository::new(pool);
+        Self { repo, settings }
+    }
+
+    pub fn max_body_bytes(&self) -> usize {
+        self.settings.max_body_bytes
+    }
+
+    pub fn validate_key(&self, key: &str) -> Result<(), AppError> {
+        if key.is_empty() || key.len() > self.settings.max_key_length {
+            return Err(AppError::ValidationError(i18n::t_args(
+                "error.request.idempotency_key_length",
+                &[("max", &self.settings.max_key_length.to_string())],
+            )));
+        }
+        if !key.chars().all(|c| c.is_ascii_graphic()) {
+            return Err(AppError::ValidationError(
+                "error.request.idempotency_key_ascii".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    pub async fn claim(&self, claim: &KeyClaim<'_>) -> Result<IdempotencyClaim, AppError> {
+        let claimed = self
+            .repo
+            .try_claim(
+                claim,
+                self.settings.ttl_hours,
+                self.settings.processing_timeout_secs,
+            )
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        if let Some(claim_token) = claimed {
+            return Ok(IdempotencyClaim::New(claim_token));
+        }
+
+        let existing = self
+            .repo
+            .find(claim.scope, claim.key)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        resolve_existing(existing, claim.request_hash)
+    }
+
+    // key ถูก request อื่นแย่งไปแล้ว (ค้างเกิน processing_timeout) ผลของเจ้าของใหม่ต้องไม่ถูกทับ แค่ log ไว้
+    pub async fn complete(
+        &self,
+        scope: &str,
+        key: &str,
+        claim_token: Uuid,
+        status_code: u16,
+        content_type: Option<&str>,
+        body: &[u8],
+    ) -> Result<(), AppError> {
+        let stored = self
+            .repo
+            .complete(scope, key, claim_token, status_code as i16, content_type, body)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        if !stored {
+            tracing::warn!(scope, "idempotency key taken over by another request, response not stored");
+        }
+        Ok(())
+    }
+
+    pub async fn release(&self, scope: &str, key: &str, claim_token: Uuid) -> Result<(), AppError> {
+        let released = self
+            .repo
+            .release(scope, key, claim_token)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        if !released {
+            tracing::warn!(scope, "idempotency key taken over by another request, not released");
+        }
+        Ok(())
+    }
+
+    pub async fn purge_expired(&self) -> Result<u64, AppError> {
+        self.repo
+            .delete_expired()
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stored(request_hash: &str, status_code: Option<i16>) -> IdempotencyKeyEntity {
+        IdempotencyKeyEntity {
+            request_hash: request_hash.to_string(),
+            status_code,
+            content_type: Some("application/json".into()),
+            response_body: status_code.map(|_| b"{}".to_vec()),
+        }
+    }
+
+    #[test]
+    fn fingerprint_covers_method_path_query_and_body() {
+        let base = fingerprint("POST", "/orders?coupon=A", b"{}");
+
+        assert_eq!(base, fingerprint("POST", "/orders?coupon=A", b"{}"));
+        assert_eq!(base.len(), 64);
+        assert_ne!(base, fingerprint("PUT", "/orders?coupon=A", b"{}"));
+        assert_ne!(base, fingerprint("POST", "/orders?coupon=B", b"{}"));
+        assert_ne!(base, fingerprint("POST", "/orders", b"{}"));
+        assert_ne!(base, fingerprint("POST", "/orders?coupon=A", b"{\"a\":1}"));
+    }
+
+    #[test]
+    fn completed_request_with_same_hash_is_replayed() {
+        let claim = resolve_existing(Some(stored("abc", Some(201))), "abc").unwrap();
+        assert!(matches!(claim, IdempotencyClaim::Replay(row) if row.status_code == Some(201)));
+    }
+
+    #[test]
+    fn different_request_with_same_key_is_rejected() {
+        for status_code in [Some(201), None] {
+            let result = resolve_existing(Some(stored("abc", status_code)), "def");
+            assert!(matches!(result, Err(AppError::UnprocessableEntity(_))));
+        }
+    }
+
+    #[test]
+    fn request_still_in_progress_is_a_conflict() {
+        let result = resolve_existing(Some(stored("abc", None)), "abc");
+        assert!(matches!(result, Err(AppError::Conflict(_))));
+
+        // แถวหายไประหว่างจอง (release / หมดอายุ) ให้ลองใหม่
+        assert!(matches!(resolve_existing(None, "abc"), Err(AppError::Conflict(_))));
+    }
+}
@@ -3,4 +3,18 @@ pub mod user_service;
 pub mod categories_service;
 pub mod products_service;
 pub mod cart_service;
-pub mod search_service;
\ No newline at end of file
+pub mod search_service;
+pub mod notifier;
+pub mod health_service;
+pub mod rate_limiter;
+pub mod password_hasher;
+pub mod address_service;
+pub mod payment_gateway;
+pub mod payment_service;
+pub mod order_service;
+pub mod idempotency_service;
+pub mod currency_service;
+pub mod tax_service;
+pub mod shipping_carrier;
+pub mod shipping_service;pub mod stats_service;
+pub mod cart_recovery_service;
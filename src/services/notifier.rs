@@ -0,0 +1,101 @@
+use crate::models::error::AppError;
+use async_trait::async_trait;
+use std::fmt;
+use uuid::Uuid;
+
+// ผู้รับข้อความ
+#[derive(Debug, Clone)]
+pub enum Recipient {
+    Admins,
+    User(Uuid),
+    Email(String), // ส่งตรงถึงอีเมล (เช่น ลิงก์ยืนยันอีเมล ก่อนอีเมลนั้นจะถูกยืนยัน)
+}
+
+// ใช้ตอน log/ส่งต่อให้ช่องทางจริง ว่าข้อความนี้ไปหาใคร
+impl fmt::Display for Recipient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Recipient::Admins => write!(f, "admins"),
+            Recipient::User(user_id) => write!(f, "user:{}", user_id),
+            Recipient::Email(email) => write!(f, "email:{}", email),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Notification {
+    pub recipient: Recipient,
+    pub subject: String,
+    pub body: String,
+}
+
+// ช่องทางส่งแจ้งเตือน (Email, LINE, Slack ฯลฯ) ให้ implement trait นี้แล้วเสียบเข้า AppState
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    async fn send(&self, notification: Notification) -> Result<(), AppError>;
+}
+
+// Notifier สำหรับ Dev: แค่พิมพ์ลง log
+#[derive(Clone, Default)]
+pub struct LogNotifier;
+
+// ลิงก์ในข้อความมี token ที่ใช้แทนตัวผู้ใช้ได้ ห้ามหลุดลง log
+fn redact_tokens(body: &str) -> String {
+    const MARKER: &str = "token=";
+    let mut redacted = String::with_capacity(body.len());
+    let mut rest = body;
+    while let Some(start) = rest.find(MARKER) {
+        let value_start = start + MARKER.len();
+        redacted.push_str(&rest[..value_start]);
+        redacted.push_str("[REDACTED]");
+        rest = &rest[value_start..];
+        let value_end = rest
+            .find(|c: char| c.is_whitespace() || c == '&' || c == '#')
+            .unwrap_or(rest.len());
+        rest = &rest[value_end..];
+    }
+    redacted.push_str(rest);
+    redacted
+}
+
+#[async_trait]
+impl Notifier for LogNotifier {
+    async fn send(&self, notification: Notification) -> Result<(), AppError> {
+        tracing::info!(
+            recipient = %notification.recipient,
+            subject = %notification.subject,
+            body = %redact_tokens(&notification.body),
+            "notification sent"
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokens_in_links_are_redacted() {
+        let body = "Open:\nhttps://shop.test/verify?token=abc.def.ghi\n\nor https://shop.test/cart?ref=mail&token=xyz&utm=1";
+        assert_eq!(
+            redact_tokens(body),
+            "Open:\nhttps://shop.test/verify?token=[REDACTED]\n\nor https://shop.test/cart?ref=mail&token=[REDACTED]&utm=1"
+        );
+        assert_eq!(redact_tokens("no secrets here"), "no secrets here");
+    }
+
+    #[test]
+    fn recipients_format_with_their_target() {
+        let user_id = Uuid::nil();
+        assert_eq!(Recipient::Admins.to_string(), "admins");
+        assert_eq!(
+            Recipient::User(user_id).to_string(),
+            format!("user:{}", user_id)
+        );
+        assert_eq!(
+            Recipient::Email("a@shop.test".into()).to_string(),
+            "email:a@shop.test"
+        );
+    }
+}
@@ -0,0 +1,217 @@
+use crate::constants::ORDER_PENDING_PAYMENT;
+use crate::i18n;
+use crate::models::dto::{
+    AddressSnapshot, CheckoutRequest, CheckoutResponse, FilterOptions, OrderResponse,
+    PagedResponse, PaymentIntentResponse,
+};
+use crate::models::entity::OrderEntity;
+use crate::models::error::AppError;
+use crate::repositories::order_repository::{CheckoutOutcome, OrderRepository, ShippingSelection};
+use crate::services::address_service::AddressService;
+use crate::services::payment_service::PaymentService;
+use crate::services::shipping_service::ShippingService;
+use crate::services::tax_service::TaxService;
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct OrderService {
+    repo: OrderRepository,
+    address_service: AddressService,
+    payment_service: PaymentService,
+    shipping_service: ShippingService,
+    tax_service: TaxService,
+    currency: String,
+}
+
+impl OrderService {
+    pub fn new(
+        pool: Pool<Postgres>,
+        address_service: AddressService,
+        payment_service: PaymentService,
+        shipping_service: ShippingService,
+        tax_service: TaxService,
+        currency: String,
+    ) -> Self {
+        let repo = OrderRepository::new(pool);
+        Self {
+            repo,
+            address_service,
+            payment_service,
+            shipping_service,
+            tax_service,
+            currency,
+        }
+    }
+
+    // POST /orders/checkout
+    pub async fn checkout(
+        &self,
+        user_id: Uuid,
+        req: CheckoutRequest,
+    ) -> Result<CheckoutResponse, AppError> {
+        let shipping = self
+            .address_service
+            .snapshot(user_id, req.shipping_address_id)
+            .await?;
+        let billing = match req.billing_address_id {
+            Some(id) => self.address_service.snapshot(user_id, id).await?,
+            None => shipping.clone(),
+        };
+
+        let (quote, shipment) = self
+            .shipping_service
+            .quote_for_checkout(user_id, &shipping, req.shipping_method.as_deref())
+            .await?;
+        let selection = ShippingSelection {
+            method: quote.method.method.code,
+            method_name: quote.method.method.name,
+            cost: quote.cost,
+            quoted: shipment
+                .items
+                .iter()
+                .map(|item| (item.product_id, item.quantity, item.price))
+                .collect(),
+        };
+
+        // ภาษีคิดตามที่อยู่จัดส่งของ Order นี้
+        let tax = self
+            .tax_service
+            .calculator(&shipping.country, shipping.region.as_deref())
+            .await?;
+
+        let to_json = |value: AddressSnapshot| {
+            serde_json::to_value(value).map_err(|e| AppError::InternalServerError(e.to_string()))
+        };
+
+        let outcome = self
+            .repo
+            .create_from_cart(
+                user_id,
+                &self.currency,
+                &tax,
+                &selection,
+                to_json(shipping)?,
+                to_json(billing)?,
+            )
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        let (order, items) = match outcome {
+            CheckoutOutcome::Created(order, items) => (*order, items),
+            CheckoutOutcome::EmptyCart => {
+                return Err(AppError::ValidationError("error.cart.empty".into()));
+            }
+            CheckoutOutcome::Unavailable(names) => {
+                return Err(AppError::Conflict(i18n::t_args(
+                    "error.order.items_unavailable",
+                    &[("items", &names.join(", "))],
+                )));
+            }
+            CheckoutOutcome::CartChanged => {
+                return Err(AppError::Conflict("error.order.cart_changed".into()));
+            }
+        };
+        metrics::counter!("shop_orders_created_total").increment(1);
+
+        // Order ถูกสร้างแล้ว ถ้าเปิดรายการกับ provider ไม่ได้ให้ลองใหม่ที่ POST /orders/:id/payments
+        let payment = match self.payment_service.start_payment(&order).await {
+            Ok(payment) => Some(payment),
+            Err(e) => {
+                tracing::warn!(order_id = %order.id, error = %e, "could not start payment");
+                None
+            }
+        };
+
+        let payments = self.payment_service.find_by_orders(&[order.id]).await?;
+        Ok(CheckoutResponse {
+            order: OrderResponse::new(order, items, payments),
+            payment,
+        })
+    }
+
+    // POST /orders/:id/payments (ลองจ่ายใหม่ สำหรับ Order ที่ยังรอจ่าย)
+    pub async fn retry_payment(
+        &self,
+        user_id: Uuid,
+        order_id: Uuid,
+    ) -> Result<PaymentIntentResponse, AppError> {
+        let order = self.find_order(user_id, order_id).await?;
+        if order.status != ORDER_PENDING_PAYMENT {
+            return Err(AppError::Conflict("error.order.not_awaiting_payment".into()));
+        }
+
+        // เปิดรายการใหม่ได้แล้วค่อยยกเลิกรายการเก่า ลูกค้าจะได้ไม่จ่ายซ้ำสองรายการ
+        let intent = self.payment_service.start_payment(&order).await?;
+        self.payment_service
+            .cancel_pending(order.id, intent.payment.id)
+            .await?;
+        Ok(intent)
+    }
+
+    async fn find_order(&self, user_id: Uuid, order_id: Uuid) -> Result<OrderEntity, AppError> {
+        self.repo
+            .find(user_id, order_id)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .ok_or(AppError::NotFound("error.order.not_found".into()))
+    }
+
+    pub async fn get_order(&self, user_id: Uuid, order_id: Uuid) -> Result<OrderResponse, AppError> {
+        let order = self.find_order(user_id, order_id).await?;
+        let mut responses = self.with_details(vec![order]).await?;
+        Ok(responses.remove(0))
+    }
+
+    pub async fn list_orders(
+        &self,
+        user_id: Uuid,
+        opts: FilterOptions,
+    ) -> Result<PagedResponse<OrderResponse>, AppError> {
+        let limit = opts.limit.unwrap_or(10);
+        let page = opts.page.unwrap_or(1).max(1);
+
+        let (orders, total) = self
+            .repo
+            .list_by_user(user_id, limit as i64, ((page - 1) * limit) as i64)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        let data = self.with_details(orders).await?;
+        let total_pages = (total as f64 / limit as f64).ceil() as i64;
+
+        Ok(PagedResponse {
+            data,
+            total,
+            page,
+            limit,
+            total_pages,
+        })
+    }
+
+    // รวม items/payments ของทุก Order ด้วย query เดียวต่อตาราง
+    async fn with_details(&self, orders: Vec<OrderEntity>) -> Result<Vec<OrderResponse>, AppError> {
+        let ids: Vec<Uuid> = orders.iter().map(|order| order.id).collect();
+
+        let mut items = self
+            .repo
+            .find_items(&ids)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        let mut payments = self.payment_service.find_by_orders(&ids).await?;
+
+        Ok(orders
+            .into_iter()
+            .map(|order| {
+                let (order_items, rest): (Vec<_>, Vec<_>) =
+                    items.drain(..).partition(|item| item.order_id == order.id);
+                items = rest;
+                let (order_payments, rest): (Vec<_>, Vec<_>) =
+                    payments.drain(..).partition(|payment| payment.order_id == order.id);
+                payments = rest;
+
+                OrderResponse::new(order, order_items, order_payments)
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,96 @@
+use crate::config::settings::{PasswordAlgorithm, PasswordSettings};
+use crate::models::error::AppError;
+use argon2::{
+    Algorithm, Argon2, Params, PasswordHash, PasswordHasher as _, PasswordVerifier as _, Version,
+    password_hash::{SaltString, rand_core::OsRng},
+};
+
+// งาน hash กิน CPU หนัก ให้เรียกผ่าน spawn_blocking
+pub trait PasswordHasher: Send + Sync {
+    fn hash(&self, password: &str) -> Result<String, AppError>;
+
+    // ต้อง verify ได้ทุก algorithm ที่เคยใช้ (bcrypt เดิม + argon2id)
+    fn verify(&self, password: &str, hash: &str) -> bool;
+
+    // hash นี้ algorithm/cost ไม่ตรงกับ config ปัจจุบัน -> ควร hash ใหม่หลัง login สำเร็จ
+    fn needs_rehash(&self, hash: &str) -> bool;
+}
+
+pub struct ConfiguredPasswordHasher {
+    settings: PasswordSettings,
+}
+
+impl ConfiguredPasswordHasher {
+    pub fn new(settings: PasswordSettings) -> Self {
+        Self { settings }
+    }
+
+    fn argon2(&self) -> Result<Argon2<'static>, AppError> {
+        let params = Params::new(
+            self.settings.argon2_memory_kib,
+            self.settings.argon2_iterations,
+            self.settings.argon2_parallelism,
+            None,
+        )
+        .map_err(|e| AppError::InternalServerError(e.to_string()))?;
+
+        Ok(Argon2::new(Algorithm::Argon2id, Version::V0x13, params))
+    }
+
+    fn argon2_is_current(&self, hash: &str) -> bool {
+        let Ok(parsed) = PasswordHash::new(hash) else {
+            return false;
+        };
+        let Ok(params) = Params::try_from(&parsed) else {
+            return false;
+        };
+
+        parsed.algorithm == Algorithm::Argon2id.ident()
+            && parsed.version == Some(Version::V0x13.into())
+            && params.m_cost() == self.settings.argon2_memory_kib
+            && params.t_cost() == self.settings.argon2_iterations
+            && params.p_cost() == self.settings.argon2_parallelism
+    }
+}
+
+impl PasswordHasher for ConfiguredPasswordHasher {
+    fn hash(&self, password: &str) -> Result<String, AppError> {
+        match self.settings.algorithm {
+            PasswordAlgorithm::Argon2id => {
+                let salt = SaltString::generate(&mut OsRng);
+                self.argon2()?
+                    .hash_password(password.as_bytes(), &salt)
+                    .map(|hash| hash.to_string())
+                    .map_err(|e| AppError::InternalServerError(e.to_string()))
+            }
+            PasswordAlgorithm::Bcrypt => bcrypt::hash(password, self.settings.bcrypt_cost)
+                .map_err(|e| AppError::InternalServerError(e.to_string())),
+        }
+    }
+
+    fn verify(&self, password: &str, hash: &str) -> bool {
+        if hash.starts_with("$argon2") {
+            // parameter อยู่ใน hash เอง จึงใช้ Argon2::default() verify ได้ทุก cost
+            PasswordHash::new(hash)
+                .map(|parsed| {
+                    Argon2::default()
+                        .verify_password(password.as_bytes(), &parsed)
+                        .is_ok()
+                })
+                .unwrap_or(false)
+        } else {
+            bcrypt::verify(password, hash).unwrap_or(false)
+        }
+    }
+
+    fn needs_rehash(&self, hash: &str) -> bool {
+        match self.settings.algorithm {
+            PasswordAlgorithm::Argon2id => !self.argon2_is_current(hash),
+            // รูปแบบ bcrypt: $2b$<cost>$<salt+hash>
+            PasswordAlgorithm::Bcrypt => {
+                let cost = hash.split('$').nth(2).and_then(|cost| cost.parse::<u32>().ok());
+                !hash.starts_with("$2") || cost != Some(self.settings.bcrypt_cost)
+            }
+        }
+    }
+}
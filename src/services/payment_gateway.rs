@@ -0,0 +1,322 @@
+use crate::config::settings::PaymentSettings;
+use crate::i18n;
+use crate::models::error::AppError;
+use async_trait::async_trait;
+use axum::http::HeaderMap;
+use hmac::{Hmac, Mac};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+// ผลของการเปิดรายการชำระเงินกับ provider
+#[derive(Debug, Clone)]
+pub struct PaymentIntent {
+    pub provider_payment_id: String,
+    pub client_secret: String, // ส่งให้ frontend ใช้ยืนยันการจ่ายกับ provider เอง (ไม่เก็บลง DB)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum WebhookEventKind {
+    Authorized, // อนุมัติวงเงินแล้ว ต้องสั่ง capture ต่อ
+    Succeeded,  // ตัดเงินสำเร็จ
+    Failed { reason: String },
+    Refunded,
+}
+
+// Webhook ที่ตรวจลายเซ็นแล้ว (แปลงเป็นรูปแบบกลาง ไม่ขึ้นกับ provider)
+#[derive(Debug, Clone)]
+pub struct WebhookEvent {
+    pub event_id: String, // ใช้กันประมวลผลซ้ำ
+    pub provider_payment_id: String,
+    pub kind: WebhookEventKind,
+}
+
+// Payment provider แต่ละเจ้า (Stripe, Omise ฯลฯ) ให้ implement trait นี้แล้วลงทะเบียนใน PaymentGateways
+#[async_trait]
+pub trait PaymentGateway: Send + Sync {
+    fn provider(&self) -> &'static str;
+
+    async fn create_intent(
+        &self,
+        order_id: Uuid,
+        amount: Decimal,
+        currency: &str,
+    ) -> Result<PaymentIntent, AppError>;
+
+    async fn capture(&self, provider_payment_id: &str) -> Result<(), AppError>;
+
+    // ยกเลิกรายการที่ยังไม่ได้จ่าย (ลูกค้าเปิดรายการใหม่แทนแล้ว)
+    async fn cancel(&self, provider_payment_id: &str) -> Result<(), AppError>;
+
+    async fn refund(&self, provider_payment_id: &str, amount: Decimal) -> Result<(), AppError>;
+
+    // ต้องตรวจลายเซ็นก่อนเชื่อข้อมูลใน body เสมอ
+    fn verify_webhook(&self, headers: &HeaderMap, body: &[u8]) -> Result<WebhookEvent, AppError>;
+}
+
+type HmacSha256 = Hmac<Sha256>;
+
+pub const MOCK_SIGNATURE_HEADER: &str = "x-mock-signature";
+
+// Body ของ Webhook จาก MockGateway
+//   {"id": "evt_1", "type": "payment.succeeded", "payment_id": "mock_pi_...", "failure_reason": null}
+// type: payment.authorized / payment.succeeded / payment.failed / payment.refunded
+// Header: x-mock-signature: t=<unix time>,v1=<hex HMAC-SHA256 ของ "<t>.<body>">
+#[derive(Deserialize)]
+struct MockWebhookBody {
+    id: String,
+    #[serde(rename = "type")]
+    kind: String,
+    payment_id: String,
+    failure_reason: Option<String>,
+}
+
+// Gateway จำลองสำหรับ Dev/Test: ไม่เรียก network เลย ทุกคำสั่งสำเร็จทันที
+// ผลการจ่ายเงินจริงมาทาง Webhook ที่เซ็นด้วย mock_webhook_secret (รูปแบบลายเซ็นดู MockGateway::sign)
+pub struct MockGateway {
+    secret: String,
+    tolerance_secs: i64,
+}
+
+impl MockGateway {
+    pub fn new(settings: &PaymentSettings) -> Self {
+        Self {
+            secret: settings.mock_webhook_secret.expose().to_string(),
+            tolerance_secs: settings.webhook_tolerance_secs,
+        }
+    }
+
+    fn mac(&self, timestamp: i64, body: &[u8]) -> HmacSha256 {
+        let mut mac = HmacSha256::new_from_slice(self.secret.as_bytes())
+            .expect("HMAC accepts keys of any length");
+        mac.update(timestamp.to_string().as_bytes());
+        mac.update(b".");
+        mac.update(body);
+        mac
+    }
+
+    // ค่า header x-mock-signature สำหรับ body นี้
+    #[cfg(test)]
+    fn sign(&self, body: &[u8], timestamp: i64) -> String {
+        let signature = hex::encode(self.mac(timestamp, body).finalize().into_bytes());
+        format!("t={},v1={}", timestamp, signature)
+    }
+}
+
+#[async_trait]
+impl PaymentGateway for MockGateway {
+    fn provider(&self) -> &'static str {
+        "mock"
+    }
+
+    async fn create_intent(
+        &self,
+        order_id: Uuid,
+        amount: Decimal,
+        currency: &str,
+    ) -> Result<PaymentIntent, AppError> {
+        tracing::info!(%order_id, %amount, currency, "mock payment intent created");
+        Ok(PaymentIntent {
+            provider_payment_id: format!("mock_pi_{}", Uuid::new_v4().simple()),
+            client_secret: format!("mock_secret_{}", Uuid::new_v4().simple()),
+        })
+    }
+
+    async fn capture(&self, provider_payment_id: &str) -> Result<(), AppError> {
+        tracing::info!(provider_payment_id, "mock payment captured");
+        Ok(())
+    }
+
+    async fn cancel(&self, provider_payment_id: &str) -> Result<(), AppError> {
+        tracing::info!(provider_payment_id, "mock payment canceled");
+        Ok(())
+    }
+
+    async fn refund(&self, provider_payment_id: &str, amount: Decimal) -> Result<(), AppError> {
+        tracing::info!(provider_payment_id, %amount, "mock refund requested");
+        Ok(())
+    }
+
+    fn verify_webhook(&self, headers: &HeaderMap, body: &[u8]) -> Result<WebhookEvent, AppError> {
+        let invalid = || AppError::AuthError("error.payment.invalid_signature".into());
+
+        let header = headers
+            .get(MOCK_SIGNATURE_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .ok_or_else(invalid)?;
+
+        let mut timestamp = None;
+        let mut signature = None;
+        for part in header.split(',') {
+            match part.trim().split_once('=') {
+                Some(("t", value)) => timestamp = value.parse::<i64>().ok(),
+                Some(("v1", value)) => signature = hex::decode(value).ok(),
+                _ => {}
+            }
+        }
+        let (Some(timestamp), Some(signature)) = (timestamp, signature) else {
+            return Err(invalid());
+        };
+
+        // กัน replay: ลายเซ็นถูกแต่เก่าเกินไปก็ไม่รับ
+        if (chrono::Utc::now().timestamp() - timestamp).abs() > self.tolerance_secs {
+            return Err(invalid());
+        }
+        // verify_slice เทียบแบบ constant-time
+        self.mac(timestamp, body)
+            .verify_slice(&signature)
+            .map_err(|_| invalid())?;
+
+        let payload: MockWebhookBody = serde_json::from_slice(body)
+            .map_err(|e| {
+                AppError::ValidationError(i18n::t_args(
+                    "error.payment.invalid_webhook_payload",
+                    &[("error", &e.to_string())],
+                ))
+            })?;
+
+        let kind = match payload.kind.as_str() {
+            "payment.authorized" => WebhookEventKind::Authorized,
+            "payment.succeeded" => WebhookEventKind::Succeeded,
+            "payment.failed" => WebhookEventKind::Failed {
+                reason: payload
+                    .failure_reason
+                    .unwrap_or_else(|| "Payment declined".to_string()),
+            },
+            "payment.refunded" => WebhookEventKind::Refunded,
+            other => {
+                return Err(AppError::ValidationError(i18n::t_args(
+                    "error.payment.unsupported_event",
+                    &[("kind", other)],
+                )));
+            }
+        };
+
+        Ok(WebhookEvent {
+            event_id: payload.id,
+            provider_payment_id: payload.payment_id,
+            kind,
+        })
+    }
+}
+
+// Gateway ทั้งหมดที่เปิดใช้ เลือกตามชื่อ provider (Webhook) หรือใช้ตัว default (Checkout)
+#[derive(Clone)]
+pub struct PaymentGateways {
+    gateways: HashMap<&'static str, Arc<dyn PaymentGateway>>,
+    default_provider: String,
+}
+
+impl PaymentGateways {
+    pub fn from_settings(settings: &PaymentSettings) -> Self {
+        let mut gateways: HashMap<&'static str, Arc<dyn PaymentGateway>> = HashMap::new();
+        let mock: Arc<dyn PaymentGateway> = Arc::new(MockGateway::new(settings));
+        gateways.insert(mock.provider(), mock);
+
+        Self {
+            gateways,
+            default_provider: settings.default_provider.clone(),
+        }
+    }
+
+    pub fn get(&self, provider: &str) -> Result<Arc<dyn PaymentGateway>, AppError> {
+        self.gateways
+            .get(provider)
+            .cloned()
+            .ok_or_else(|| {
+                AppError::NotFound(i18n::t_args(
+                    "error.payment.unknown_provider",
+                    &[("provider", provider)],
+                ))
+            })
+    }
+
+    pub fn default_gateway(&self) -> Result<Arc<dyn PaymentGateway>, AppError> {
+        self.get(&self.default_provider)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn gateway() -> MockGateway {
+        MockGateway::new(&PaymentSettings::default())
+    }
+
+    fn body(kind: &str) -> Vec<u8> {
+        format!(
+            r#"{{"id": "evt_1", "type": "{}", "payment_id": "mock_pi_1", "failure_reason": null}}"#,
+            kind
+        )
+        .into_bytes()
+    }
+
+    fn verify(gateway: &MockGateway, signature: &str, body: &[u8]) -> Result<WebhookEvent, AppError> {
+        let mut headers = HeaderMap::new();
+        headers.insert(MOCK_SIGNATURE_HEADER, HeaderValue::from_str(signature).unwrap());
+        gateway.verify_webhook(&headers, body)
+    }
+
+    fn now() -> i64 {
+        chrono::Utc::now().timestamp()
+    }
+
+    #[test]
+    fn valid_signature_is_accepted() {
+        let gateway = gateway();
+        let body = body("payment.succeeded");
+        let event = verify(&gateway, &gateway.sign(&body, now()), &body).unwrap();
+
+        assert_eq!(event.event_id, "evt_1");
+        assert_eq!(event.provider_payment_id, "mock_pi_1");
+        assert_eq!(event.kind, WebhookEventKind::Succeeded);
+    }
+
+    #[test]
+    fn tampered_body_is_rejected() {
+        let gateway = gateway();
+        let signature = gateway.sign(&body("payment.failed"), now());
+
+        let result = verify(&gateway, &signature, &body("payment.succeeded"));
+        assert!(matches!(result, Err(AppError::AuthError(_))));
+    }
+
+    #[test]
+    fn stale_timestamp_is_rejected() {
+        let gateway = gateway();
+        let body = body("payment.succeeded");
+        let stale = now() - gateway.tolerance_secs - 1;
+
+        let result = verify(&gateway, &gateway.sign(&body, stale), &body);
+        assert!(matches!(result, Err(AppError::AuthError(_))));
+    }
+
+    #[test]
+    fn malformed_header_is_rejected() {
+        let gateway = gateway();
+        let body = body("payment.succeeded");
+
+        for header in ["", "v1=abcd", &format!("t={}", now()), &format!("t={},v1=not-hex", now())] {
+            let result = verify(&gateway, header, &body);
+            assert!(matches!(result, Err(AppError::AuthError(_))), "{}", header);
+        }
+        assert!(matches!(
+            gateway.verify_webhook(&HeaderMap::new(), &body),
+            Err(AppError::AuthError(_))
+        ));
+    }
+
+    #[test]
+    fn unknown_event_type_is_rejected() {
+        let gateway = gateway();
+        let body = body("payment.disputed");
+
+        let result = verify(&gateway, &gateway.sign(&body, now()), &body);
+        assert!(matches!(result, Err(AppError::ValidationError(_))));
+    }
+}
@@ -0,0 +1,249 @@
+use crate::constants::{
+    ORDER_PAID, PAYMENT_AUTHORIZED, PAYMENT_CANCELED, PAYMENT_CAPTURED, PAYMENT_FAILED,
+    PAYMENT_PENDING, PAYMENT_REFUND_PENDING, PAYMENT_REFUNDED,
+};
+use crate::models::dto::{PaymentIntentResponse, PaymentResponse};
+use crate::models::entity::{OrderEntity, PaymentEntity};
+use crate::models::error::AppError;
+use crate::repositories::order_repository::OrderRepository;
+use crate::repositories::payment_repository::{PaymentRepository, Transition, WebhookOutcome};
+use crate::services::payment_gateway::{PaymentGateways, WebhookEventKind};
+use crate::services::products_service::ProductsService;
+use axum::http::HeaderMap;
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct PaymentService {
+    repo: PaymentRepository,
+    orders: OrderRepository,
+    gateways: PaymentGateways,
+    products: ProductsService, // แจ้งคนที่รอของ เมื่อ Order ที่จ่ายไม่สำเร็จคืนสต็อก
+}
+
+impl PaymentService {
+    pub fn new(pool: Pool<Postgres>, gateways: PaymentGateways, products: ProductsService) -> Self {
+        Self {
+            repo: PaymentRepository::new(pool.clone()),
+            orders: OrderRepository::new(pool),
+            gateways,
+            products,
+        }
+    }
+
+    // หลัง Transaction commit แล้ว: แจ้งสินค้าที่กลับมามีของ แล้วคืน payment ล่าสุด
+    async fn finish(&self, transition: Transition) -> PaymentEntity {
+        if !transition.restocked.is_empty() {
+            self.products.notify_restocked(&transition.restocked).await;
+        }
+        transition.payment
+    }
+
+    // เปิดรายการชำระเงินใหม่กับ provider default (1 Order ลองจ่ายได้หลายครั้ง)
+    pub async fn start_payment(&self, order: &OrderEntity) -> Result<PaymentIntentResponse, AppError> {
+        let gateway = self.gateways.default_gateway()?;
+        let intent = gateway
+            .create_intent(order.id, order.total, &order.currency)
+            .await?;
+
+        let payment = self
+            .repo
+            .create(
+                order.id,
+                gateway.provider(),
+                &intent.provider_payment_id,
+                order.total,
+                &order.currency,
+            )
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        Ok(PaymentIntentResponse {
+            payment: PaymentResponse::from(payment),
+            client_secret: intent.client_secret,
+        })
+    }
+
+    // ลองจ่ายใหม่แล้ว: ยกเลิกรายการเก่าที่ยังไม่จ่าย (ยกเลิกกับ provider ไม่ได้ก็ปล่อยเป็น pending ไว้)
+    pub async fn cancel_pending(&self, order_id: Uuid, keep: Uuid) -> Result<(), AppError> {
+        let stale = self
+            .find_by_orders(&[order_id])
+            .await?
+            .into_iter()
+            .filter(|payment| payment.id != keep && payment.status == PAYMENT_PENDING);
+
+        for payment in stale {
+            let gateway = self.gateways.get(&payment.provider)?;
+            if let Err(e) = gateway.cancel(&payment.provider_payment_id).await {
+                tracing::warn!(payment_id = %payment.id, error = %e, "could not cancel payment");
+                continue;
+            }
+            let canceled = self
+                .repo
+                .transition(payment.id, PAYMENT_CANCELED, None)
+                .await
+                .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+            if let Some(canceled) = canceled {
+                self.finish(canceled).await;
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn find_by_orders(&self, order_ids: &[Uuid]) -> Result<Vec<PaymentEntity>, AppError> {
+        self.repo
+            .find_by_orders(order_ids)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))
+    }
+
+    // POST /payments/webhook/:provider
+    pub async fn handle_webhook(
+        &self,
+        provider: &str,
+        headers: &HeaderMap,
+        body: &[u8],
+    ) -> Result<(), AppError> {
+        let gateway = self.gateways.get(provider)?;
+        let event = gateway.verify_webhook(headers, body)?;
+
+        let (to, failure_reason) = match &event.kind {
+            WebhookEventKind::Authorized => (PAYMENT_AUTHORIZED, None),
+            WebhookEventKind::Succeeded => (PAYMENT_CAPTURED, None),
+            WebhookEventKind::Failed { reason } => (PAYMENT_FAILED, Some(reason.as_str())),
+            WebhookEventKind::Refunded => (PAYMENT_REFUNDED, None),
+        };
+
+        let outcome = self
+            .repo
+            .process_webhook_event(
+                provider,
+                &event.event_id,
+                &event.provider_payment_id,
+                to,
+                failure_reason,
+            )
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        // ตอบ 200 ทุกกรณีที่ลายเซ็นถูก ไม่งั้น provider จะส่งซ้ำไปเรื่อย ๆ
+        let payment = match outcome {
+            WebhookOutcome::Duplicate => {
+                tracing::info!(provider, event_id = %event.event_id, "duplicate webhook ignored");
+                return Ok(());
+            }
+            WebhookOutcome::UnknownPayment => {
+                tracing::warn!(
+                    provider,
+                    provider_payment_id = %event.provider_payment_id,
+                    "webhook for unknown payment"
+                );
+                return Ok(());
+            }
+            WebhookOutcome::Ignored(payment) => {
+                tracing::warn!(
+                    payment_id = %payment.id,
+                    status = %payment.status,
+                    to,
+                    "webhook transition not allowed, ignored"
+                );
+                return Ok(());
+            }
+            WebhookOutcome::Applied(transition) => self.finish(transition).await,
+        };
+
+        metrics::counter!("shop_payments_total", "provider" => provider.to_string(), "status" => to)
+            .increment(1);
+
+        // อนุมัติวงเงินแล้ว สั่งตัดเงินต่อทันที (ถ้าล้มเหลว payment ค้างที่ authorized ให้ตามเก็บทีหลัง)
+        if event.kind == WebhookEventKind::Authorized
+            && let Err(e) = self.capture(&payment).await
+        {
+            tracing::error!(payment_id = %payment.id, error = %e, "payment capture failed");
+        }
+        if to == PAYMENT_CAPTURED && payment.status == PAYMENT_REFUND_PENDING {
+            self.refund_orphan(&payment).await;
+        }
+
+        Ok(())
+    }
+
+    // Capture ที่ Order ไม่รับแล้ว (repository ตั้งเป็น refund_pending ไว้) ต้องคืนเงินทันที กันเก็บเงินซ้ำ
+    // คืนไม่สำเร็จ payment จะค้างที่ refund_pending ให้ตามแก้เอง
+    async fn refund_orphan(&self, payment: &PaymentEntity) {
+        tracing::warn!(payment_id = %payment.id, order_id = %payment.order_id, "capture on closed order, refunding");
+
+        let refunded = match self.gateways.get(&payment.provider) {
+            Ok(gateway) => gateway.refund(&payment.provider_payment_id, payment.amount).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = refunded {
+            tracing::error!(payment_id = %payment.id, error = %e, "orphan capture refund failed");
+        }
+    }
+
+    async fn capture(&self, payment: &PaymentEntity) -> Result<(), AppError> {
+        let gateway = self.gateways.get(&payment.provider)?;
+        gateway.capture(&payment.provider_payment_id).await?;
+
+        let updated = self
+            .repo
+            .transition(payment.id, PAYMENT_CAPTURED, None)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        metrics::counter!("shop_payments_total", "provider" => payment.provider.clone(), "status" => PAYMENT_CAPTURED)
+            .increment(1);
+        if let Some(updated) = updated {
+            let updated = self.finish(updated).await;
+            if updated.status == PAYMENT_REFUND_PENDING {
+                self.refund_orphan(&updated).await;
+            }
+        }
+        Ok(())
+    }
+
+    // Admin: คืนเงินเต็มจำนวน (Order จะเป็น refunded เมื่อ provider ยืนยันผ่าน Webhook)
+    pub async fn refund_order(&self, order_id: Uuid) -> Result<PaymentResponse, AppError> {
+        let order = self
+            .orders
+            .find_by_id(order_id)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .ok_or(AppError::NotFound("error.order.not_found".into()))?;
+
+        if order.status != ORDER_PAID {
+            return Err(AppError::Conflict("error.order.refund_not_paid".into()));
+        }
+
+        let payment = self
+            .find_by_orders(&[order_id])
+            .await?
+            .into_iter()
+            .rev()
+            .find(|payment| payment.status == PAYMENT_CAPTURED)
+            .ok_or(AppError::Conflict("error.order.no_captured_payment".into()))?;
+
+        // จองสถานะ refund_pending (ล็อกแถว payment) ก่อนสั่ง provider: คำขอซ้ำพร้อมกันจะได้ 409 ไม่คืนเงินสองรอบ
+        let gateway = self.gateways.get(&payment.provider)?;
+        let updated = self
+            .repo
+            .transition(payment.id, PAYMENT_REFUND_PENDING, None)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .ok_or(AppError::Conflict("error.payment.already_refunded".into()))?;
+        let updated = self.finish(updated).await;
+
+        if let Err(e) = gateway
+            .refund(&payment.provider_payment_id, payment.amount)
+            .await
+        {
+            if let Err(revert) = self.repo.revert_refund(payment.id).await {
+                tracing::error!(payment_id = %payment.id, error = %revert, "could not revert refund_pending");
+            }
+            return Err(e);
+        }
+
+        Ok(PaymentResponse::from(updated))
+    }
+}
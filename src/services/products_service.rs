@@ -1,26 +1,116 @@
 use crate::models::{
     dto::{
-        FilterOptions, PagedResponse, ProductRequest, ProductResponse, ProductSearchDocument,
-        UpdateProductRequest,
+        FilterOptions, ImportReport, ImportRowError, PagedResponse, ProductRequest,
+        ProductResponse, ProductSearchDocument, ProductTransferRow, RestockSubscriptionResponse,
+        TransferFormat, UpdateProductRequest,
     },
     error::AppError,
 };
-use crate::repositories::products_repository::ProductsRepository;
+use crate::i18n::{self, Locale};
+use crate::repositories::categories_repository::CategoriesRepository;
+use crate::repositories::products_repository::{ProductsRepository, UpsertOutcome};
+use crate::repositories::tax_rate_repository::TaxRateRepository;
+use crate::services::notifier::{Notification, Notifier, Recipient};
+use crate::services::search_service::SearchService;
+use crate::utils::product_transfer::{self, CategoryLookup};
+use futures::Stream;
 use sqlx::{Pool, Postgres};
+use std::sync::Arc;
 use uuid::Uuid;
 
+const EXPORT_BATCH_SIZE: i64 = 1000;
+
+// น้ำหนัก/ขนาดติดลบจะชน CHECK ของ DB ตอบ 400 ไปก่อน
+fn validate_dimensions(values: &[Option<i32>]) -> Result<(), AppError> {
+    if values.iter().flatten().any(|value| *value < 0) {
+        return Err(AppError::ValidationError("error.product.invalid_dimensions".into()));
+    }
+    Ok(())
+}
+
+// เกณฑ์แจ้งเตือนสต็อกติดลบจะชน CHECK ของ DB เช่นกัน
+fn validate_low_stock_threshold(threshold: Option<i32>) -> Result<(), AppError> {
+    if threshold.is_some_and(|threshold| threshold < 0) {
+        return Err(AppError::ValidationError(
+            "error.product.invalid_low_stock_threshold".into(),
+        ));
+    }
+    Ok(())
+}
+
 #[derive(Clone)]
 pub struct ProductsService {
     repo: ProductsRepository,
+    categories_repo: CategoriesRepository,
+    tax_repo: TaxRateRepository,
+    notifier: Arc<dyn Notifier>,
 }
 
 impl ProductsService {
-    pub fn new(pool: Pool<Postgres>) -> Self {
-        let repo = ProductsRepository::new(pool);
-        Self { repo }
+    pub fn new(pool: Pool<Postgres>, notifier: Arc<dyn Notifier>) -> Self {
+        let repo = ProductsRepository::new(pool.clone());
+        let categories_repo = CategoriesRepository::new(pool.clone());
+        let tax_repo = TaxRateRepository::new(pool);
+        Self {
+            repo,
+            categories_repo,
+            tax_repo,
+            notifier,
+        }
+    }
+
+    // ล้าง Index แล้วเติมใหม่ทั้งหมดทีละ Batch (ใช้ทั้งจาก API และ CLI `reindex`)
+    pub async fn reindex_search(&self, search: &SearchService) -> Result<usize, AppError> {
+        let batch_size = 1000;
+        let mut page = 1;
+        let mut indexed = 0;
+
+        search.delete_all_documents().await?;
+
+        loop {
+            let filter = FilterOptions {
+                page: Some(page),
+                limit: Some(batch_size),
+                search: None,
+                sort_by: Some("created_at".to_string()),
+                sort_dir: Some("asc".to_string()),
+                is_active: None,
+            };
+
+            let products = self.list_products(filter).await?.data;
+            if products.is_empty() {
+                break;
+            }
+
+            let docs: Vec<ProductSearchDocument> = products
+                .into_iter()
+                .map(|p| ProductSearchDocument {
+                    id: p.id,
+                    name: p.name,
+                    description: p.description.unwrap_or_default(),
+                    price: p.price,
+                    category_id: p.category_id,
+                    image_url: None,
+                    currency: None,
+                })
+                .collect();
+
+            // ไม่ต้องรอ task เสร็จ ปล่อยให้ Meilisearch จัดคิวเอง
+            search.add_documents(&docs).await?;
+            indexed += docs.len();
+            page += 1;
+        }
+
+        Ok(indexed)
     }
 
     pub async fn create_product(&self, req: ProductRequest) -> Result<ProductResponse, AppError> {
+        validate_dimensions(&[req.weight_grams, req.length_mm, req.width_mm, req.height_mm])?;
+        validate_low_stock_threshold(req.low_stock_threshold)?;
+        if let Some(tax_class) = &req.tax_class {
+            self.ensure_tax_class(tax_class).await?;
+        }
+
         let created = self
             .repo
             .create_product(req)
@@ -62,7 +152,7 @@ impl ProductsService {
             .find_by_id(id)
             .await
             .map_err(|e| AppError::DatabaseError(e.to_string()))?
-            .ok_or(AppError::NotFound("Product not found".into()))?;
+            .ok_or(AppError::NotFound("error.product.not_found".into()))?;
 
         Ok(product.into())
     }
@@ -71,29 +161,344 @@ impl ProductsService {
         &self,
         id: Uuid,
         req: UpdateProductRequest,
+        expected_versions: Option<Vec<i32>>,
     ) -> Result<ProductResponse, AppError> {
-        let _updated = self
+        validate_dimensions(&[
+            req.weight_grams.clone().apply_to(None),
+            req.length_mm.clone().apply_to(None),
+            req.width_mm.clone().apply_to(None),
+            req.height_mm.clone().apply_to(None),
+        ])?;
+        validate_low_stock_threshold(req.low_stock_threshold)?;
+        if let Some(tax_class) = &req.tax_class {
+            self.ensure_tax_class(tax_class).await?;
+        }
+
+        let (updated, previous_stock) =
+            match self.repo.update_product(id, req, expected_versions).await {
+                Ok(result) => result,
+                Err(sqlx::Error::RowNotFound) => return Err(self.missing_or_modified(id).await),
+                Err(e) => return Err(AppError::DatabaseError(e.to_string())),
+            };
+
+        // สต็อกเปลี่ยนจาก 0 เป็นมีของ แจ้งคนที่รออยู่ (บันทึกไปแล้ว แจ้งไม่สำเร็จแค่ log ไว้)
+        if previous_stock <= 0
+            && updated.stock > 0
+            && let Err(e) = self
+                .notify_restock_subscribers(updated.id, &updated.name)
+                .await
+        {
+            tracing::warn!(error = %e, product_id = %updated.id, "restock notification failed");
+        }
+
+        self.get_product_by_id(id).await
+    }
+
+    pub async fn delete_product(
+        &self,
+        id: Uuid,
+        expected_versions: Option<Vec<i32>>,
+    ) -> Result<(), AppError> {
+        let deleted = self
             .repo
-            .update_product(id, req)
+            .soft_delete(id, expected_versions)
             .await
-            .map_err(|e| match e {
-                sqlx::Error::RowNotFound => AppError::NotFound("Product not found".into()),
-                _ => AppError::DatabaseError(e.to_string()),
-            })?;
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        if !deleted {
+            return Err(self.missing_or_modified(id).await);
+        }
+        Ok(())
+    }
+
+    pub async fn restore_product(&self, id: Uuid) -> Result<ProductResponse, AppError> {
+        let restored = self
+            .repo
+            .restore(id)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        if restored.is_none() {
+            // ไม่เจอสินค้า หรือหมวดหมู่ของสินค้ายังถูกปิดอยู่
+            self.get_product_by_id(id).await?;
+            return Err(AppError::Conflict(
+                "error.product.category_inactive".into(),
+            ));
+        }
 
         self.get_product_by_id(id).await
     }
 
-    pub async fn delete_product(&self, id: Uuid) -> Result<(), AppError> {
+    async fn ensure_tax_class(&self, tax_class: &str) -> Result<(), AppError> {
+        let exists = self
+            .tax_repo
+            .class_exists(tax_class)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        if !exists {
+            return Err(AppError::ValidationError("error.product.unknown_tax_class".into()));
+        }
+        Ok(())
+    }
+
+    // ไม่มีแถวถูกแก้: ถ้าสินค้ายังอยู่แปลว่า version ไม่ตรง (มีคนแก้ไปก่อน)
+    async fn missing_or_modified(&self, id: Uuid) -> AppError {
+        match self.repo.find_by_id(id).await {
+            Ok(Some(_)) => AppError::PreconditionFailed(
+                "error.product.modified".into(),
+            ),
+            Ok(None) => AppError::NotFound("error.product.not_found".into()),
+            Err(e) => AppError::DatabaseError(e.to_string()),
+        }
+    }
+
+    pub async fn subscribe_restock(
+        &self,
+        user_id: Uuid,
+        product_id: Uuid,
+    ) -> Result<RestockSubscriptionResponse, AppError> {
+        let product = self.get_product_by_id(product_id).await?;
+
+        if !product.is_active {
+            return Err(AppError::NotFound("error.product.not_found".into()));
+        }
+        if product.stock > 0 {
+            return Err(AppError::ValidationError("error.product.still_in_stock".into()));
+        }
+
+        let subscription = self
+            .repo
+            .create_restock_subscription(user_id, product_id)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        Ok(RestockSubscriptionResponse {
+            product_id: subscription.product_id,
+            subscribed_at: subscription.created_at,
+        })
+    }
+
+    pub async fn unsubscribe_restock(&self, user_id: Uuid, product_id: Uuid) -> Result<(), AppError> {
         let deleted = self
             .repo
-            .soft_delete(id)
+            .delete_restock_subscription(user_id, product_id)
             .await
             .map_err(|e| AppError::DatabaseError(e.to_string()))?;
 
         if !deleted {
-            return Err(AppError::NotFound("Product not found".into()));
+            return Err(AppError::NotFound("error.product.restock_subscription_not_found".into()));
         }
         Ok(())
     }
+
+    // สต็อกกลับมาจากที่อื่น (เช่น คืนสต็อกจาก Order ที่จ่ายไม่สำเร็จ) หลัง commit แล้ว แจ้งไม่สำเร็จแค่ log ไว้
+    pub async fn notify_restocked(&self, product_ids: &[Uuid]) {
+        for &product_id in product_ids {
+            let notified = match self.get_product_by_id(product_id).await {
+                Ok(product) => self.notify_restock_subscribers(product.id, &product.name).await,
+                Err(e) => Err(e),
+            };
+            if let Err(e) = notified {
+                tracing::warn!(error = %e, %product_id, "restock notification failed");
+            }
+        }
+    }
+
+    async fn notify_restock_subscribers(
+        &self,
+        product_id: Uuid,
+        product_name: &str,
+    ) -> Result<(), AppError> {
+        let subscribers = self
+            .repo
+            .take_restock_subscribers(product_id)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        for (user_id, locale) in subscribers {
+            // ส่งเป็นภาษาที่ผู้รับตั้งไว้ ไม่ใช่ภาษาของคนที่แก้สต็อก
+            let locale = locale.as_deref().and_then(Locale::parse).unwrap_or_default();
+            let notification = i18n::scope(locale, async {
+                Notification {
+                    recipient: Recipient::User(user_id),
+                    subject: i18n::t_args("email.restock.subject", &[("name", product_name)]),
+                    body: i18n::t_args(
+                        "email.restock.body",
+                        &[("name", product_name), ("id", &product_id.to_string())],
+                    ),
+                }
+            })
+            .await;
+
+            // ส่งไม่สำเร็จรายคนไม่ควรทำให้การอัปเดตสต็อกล้ม
+            if let Err(e) = self.notifier.send(notification).await {
+                tracing::warn!(error = %e, %user_id, "restock notification failed");
+            }
+        }
+        Ok(())
+    }
+
+    // ใช้โดย Background Job: สรุปสินค้าใกล้หมดส่งให้ Admin (คืนจำนวนสินค้าที่รายงาน)
+    pub async fn report_low_stock(&self) -> Result<usize, AppError> {
+        let products = self
+            .repo
+            .find_low_stock()
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        if products.is_empty() {
+            return Ok(0);
+        }
+
+        let lines: Vec<String> = products
+            .iter()
+            .map(|p| {
+                i18n::t_args(
+                    "email.low_stock.line",
+                    &[
+                        ("name", &p.product.name),
+                        ("category", &p.category_name),
+                        ("id", &p.product.id.to_string()),
+                        ("stock", &p.product.stock.to_string()),
+                        ("threshold", &p.product.low_stock_threshold.to_string()),
+                    ],
+                )
+            })
+            .collect();
+
+        let notification = Notification {
+            recipient: Recipient::Admins,
+            subject: i18n::t_args(
+                "email.low_stock.subject",
+                &[("count", &products.len().to_string())],
+            ),
+            body: lines.join("\n"),
+        };
+        self.notifier.send(notification).await?;
+
+        Ok(products.len())
+    }
+
+    // Import สินค้าจาก CSV / NDJSON
+    // ถ้ามีแถวไหนไม่ผ่าน Validation จะไม่เขียนอะไรลง DB เลย (คืน report ให้แก้ไฟล์แล้วส่งใหม่)
+    // คืน report พร้อมเอกสารสำหรับ Meilisearch ของสินค้าที่ถูก Upsert
+    pub async fn import_products(
+        &self,
+        format: TransferFormat,
+        body: &[u8],
+        dry_run: bool,
+    ) -> Result<(ImportReport, Vec<ProductSearchDocument>), AppError> {
+        let categories = self
+            .categories_repo
+            .find_all()
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        let lookup = CategoryLookup::new(categories);
+
+        let rows = product_transfer::parse_rows(format, body);
+        let total_rows = rows.len();
+
+        let mut records = Vec::with_capacity(total_rows);
+        let mut record_rows = Vec::with_capacity(total_rows);
+        let mut errors = Vec::new();
+        for (row, parsed) in rows {
+            match parsed.map_err(|e| vec![e]).and_then(|r| product_transfer::validate_row(r, &lookup)) {
+                Ok(record) => {
+                    records.push(record);
+                    record_rows.push(row);
+                }
+                Err(row_errors) => errors.push(ImportRowError {
+                    row,
+                    errors: row_errors,
+                }),
+            }
+        }
+
+        let mut report = ImportReport {
+            dry_run,
+            total_rows,
+            valid_rows: records.len(),
+            created: 0,
+            updated: 0,
+            errors,
+        };
+
+        if dry_run || !report.errors.is_empty() {
+            return Ok((report, Vec::new()));
+        }
+
+        // แถวที่ key ไปชนสินค้าตัวอื่นจะถูกข้าม และรายงานกลับเป็น error ของแถวนั้น
+        let outcomes = self
+            .repo
+            .upsert_all(&records)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        let mut search_docs = Vec::with_capacity(outcomes.len());
+        for (row_number, outcome) in record_rows.into_iter().zip(outcomes) {
+            let row = match outcome {
+                UpsertOutcome::Upserted(row) => row,
+                UpsertOutcome::KeyConflict => {
+                    report.errors.push(ImportRowError {
+                        row: row_number,
+                        errors: vec![i18n::t("error.import.key_conflict")],
+                    });
+                    continue;
+                }
+            };
+
+            if row.inserted {
+                report.created += 1;
+            } else {
+                report.updated += 1;
+            }
+
+            let product = row.product;
+            if row.previous_stock.is_some_and(|stock| stock <= 0)
+                && product.stock > 0
+                && let Err(e) = self
+                    .notify_restock_subscribers(product.id, &product.name)
+                    .await
+            {
+                tracing::warn!(error = %e, product_id = %product.id, "restock notification failed");
+            }
+
+            search_docs.push(product.into());
+        }
+
+        Ok((report, search_docs))
+    }
+
+    // Export ทั้งแคตตาล็อกเป็น Stream ทีละ batch (ไม่โหลดทั้งหมดเข้า memory)
+    pub fn export_products(
+        &self,
+        format: TransferFormat,
+    ) -> impl Stream<Item = Result<Vec<u8>, AppError>> + Send + 'static {
+        let repo = self.repo.clone();
+
+        // state = (cursor ล่าสุด, เป็น chunk แรกไหม, หมดแล้วหรือยัง)
+        futures::stream::try_unfold(
+            (repo, None::<Uuid>, true, false),
+            move |(repo, after_id, first, finished)| async move {
+                if finished {
+                    return Ok(None);
+                }
+
+                let batch = repo
+                    .find_export_batch(after_id, EXPORT_BATCH_SIZE)
+                    .await
+                    .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+                let is_last = (batch.len() as i64) < EXPORT_BATCH_SIZE;
+                let next_cursor = batch.last().map(|p| p.product.id).or(after_id);
+
+                let rows: Vec<ProductTransferRow> =
+                    batch.into_iter().map(ProductTransferRow::from).collect();
+                let chunk = product_transfer::write_rows(format, &rows, first)?;
+
+                Ok(Some((chunk, (repo, next_cursor, false, is_last))))
+            },
+        )
+    }
 }
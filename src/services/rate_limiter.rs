@@ -0,0 +1,251 @@
+use crate::config::settings::RateLimitSettings;
+use crate::models::error::AppError;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+// เก็บ key ได้มากสุดเท่านี้ก่อนเริ่มล้าง entry ที่หมดอายุแล้ว
+const MAX_TRACKED_KEYS: usize = 10_000;
+
+pub enum RateDecision {
+    Allowed,
+    Limited(Duration), // ต้องรออีกเท่าไหร่ถึงจะมี token
+}
+
+// ที่เก็บสถานะ rate limit / lockout
+// ตอนนี้มีแค่ In-memory (ใช้ได้กับ instance เดียว) ถ้ารันหลาย replica ให้ implement ด้วย Redis ฯลฯ
+#[async_trait]
+pub trait RateLimitStore: Send + Sync {
+    // หยิบ token 1 อันจาก bucket ของ key (bucket เต็ม = `burst`, เติม `refill_per_sec` ต่อวินาที)
+    async fn take(&self, key: &str, burst: u32, refill_per_sec: f64) -> Result<RateDecision, AppError>;
+
+    // บันทึกการ login ผิด แล้วคืนจำนวนครั้งที่ผิดติดกัน (เริ่มนับใหม่ถ้าเว้นนานเกิน `window`)
+    async fn record_failure(&self, key: &str, window: Duration) -> Result<u32, AppError>;
+
+    async fn lock(&self, key: &str, duration: Duration) -> Result<(), AppError>;
+
+    // เหลือเวลาล็อกอีกเท่าไหร่ (None = ไม่ได้ถูกล็อก)
+    async fn locked_for(&self, key: &str) -> Result<Option<Duration>, AppError>;
+
+    async fn clear_failures(&self, key: &str) -> Result<(), AppError>;
+}
+
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+    full_at: Instant, // หลังเวลานี้ bucket เต็มแล้ว ลบทิ้งได้
+}
+
+struct FailureState {
+    count: u32,
+    last_failure: Instant,
+    locked_until: Option<Instant>,
+    expires_at: Instant,
+}
+
+#[derive(Default)]
+pub struct InMemoryRateLimitStore {
+    buckets: Mutex<HashMap<String, Bucket>>,
+    failures: Mutex<HashMap<String, FailureState>>,
+}
+
+#[async_trait]
+impl RateLimitStore for InMemoryRateLimitStore {
+    async fn take(&self, key: &str, burst: u32, refill_per_sec: f64) -> Result<RateDecision, AppError> {
+        let now = Instant::now();
+        let burst = burst as f64;
+        let mut buckets = self.buckets.lock().unwrap();
+
+        if buckets.len() >= MAX_TRACKED_KEYS {
+            buckets.retain(|_, bucket| bucket.full_at > now);
+        }
+
+        let bucket = buckets.entry(key.to_string()).or_insert(Bucket {
+            tokens: burst,
+            updated_at: now,
+            full_at: now,
+        });
+
+        let elapsed = now.duration_since(bucket.updated_at).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * refill_per_sec).min(burst);
+        bucket.updated_at = now;
+
+        let decision = if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            RateDecision::Allowed
+        } else {
+            RateDecision::Limited(Duration::from_secs_f64((1.0 - bucket.tokens) / refill_per_sec))
+        };
+        bucket.full_at = now + Duration::from_secs_f64((burst - bucket.tokens) / refill_per_sec);
+
+        Ok(decision)
+    }
+
+    async fn record_failure(&self, key: &str, window: Duration) -> Result<u32, AppError> {
+        let now = Instant::now();
+        let mut failures = self.failures.lock().unwrap();
+
+        if failures.len() >= MAX_TRACKED_KEYS {
+            failures.retain(|_, state| state.expires_at > now);
+        }
+
+        let state = failures.entry(key.to_string()).or_insert(FailureState {
+            count: 0,
+            last_failure: now,
+            locked_until: None,
+            expires_at: now,
+        });
+
+        if now.duration_since(state.last_failure) > window {
+            state.count = 0;
+        }
+        state.count += 1;
+        state.last_failure = now;
+        state.expires_at = state.expires_at.max(now + window);
+
+        Ok(state.count)
+    }
+
+    async fn lock(&self, key: &str, duration: Duration) -> Result<(), AppError> {
+        let until = Instant::now() + duration;
+        let mut failures = self.failures.lock().unwrap();
+
+        if let Some(state) = failures.get_mut(key) {
+            state.locked_until = Some(until);
+            state.expires_at = state.expires_at.max(until);
+        }
+
+        Ok(())
+    }
+
+    async fn locked_for(&self, key: &str) -> Result<Option<Duration>, AppError> {
+        let now = Instant::now();
+        let failures = self.failures.lock().unwrap();
+
+        Ok(failures
+            .get(key)
+            .and_then(|state| state.locked_until)
+            .filter(|until| *until > now)
+            .map(|until| until - now))
+    }
+
+    async fn clear_failures(&self, key: &str) -> Result<(), AppError> {
+        self.failures.lock().unwrap().remove(key);
+        Ok(())
+    }
+}
+
+// นโยบาย rate limit ของ Auth (IP + บัญชี + ล็อกบัญชีแบบ backoff)
+#[derive(Clone)]
+pub struct RateLimiter {
+    store: Arc<dyn RateLimitStore>,
+    settings: RateLimitSettings,
+}
+
+impl RateLimiter {
+    pub fn new(store: Arc<dyn RateLimitStore>, settings: RateLimitSettings) -> Self {
+        Self { store, settings }
+    }
+
+    pub fn trust_forwarded_for(&self) -> bool {
+        self.settings.trust_forwarded_for
+    }
+
+    pub async fn check_ip(&self, ip: IpAddr) -> Result<(), AppError> {
+        if !self.settings.enabled {
+            return Ok(());
+        }
+
+        let key = format!("ip:{}", ip);
+        let decision = self
+            .store
+            .take(&key, self.settings.ip_burst, per_second(self.settings.ip_per_minute))
+            .await?;
+
+        limited("ip", decision)
+    }
+
+    // เรียกก่อนตรวจรหัสผ่าน: บัญชีที่ถูกล็อกหรือยิงถี่เกินจะไม่ถูกตรวจเลย
+    pub async fn check_account(&self, account: &str) -> Result<(), AppError> {
+        if !self.settings.enabled {
+            return Ok(());
+        }
+
+        let key = account_key(account);
+        if let Some(remaining) = self.store.locked_for(&key).await? {
+            return limited("lockout", RateDecision::Limited(remaining));
+        }
+
+        let decision = self
+            .store
+            .take(
+                &key,
+                self.settings.account_burst,
+                per_second(self.settings.account_per_minute),
+            )
+            .await?;
+
+        limited("account", decision)
+    }
+
+    // ผิดครบ threshold แล้วล็อก base, 2*base, 4*base, ... ไม่เกิน max
+    pub async fn record_login_failure(&self, account: &str) -> Result<(), AppError> {
+        if !self.settings.enabled {
+            return Ok(());
+        }
+
+        let key = account_key(account);
+        let window = Duration::from_secs(self.settings.failure_window_secs);
+        let failures = self.store.record_failure(&key, window).await?;
+
+        if failures >= self.settings.lockout_threshold {
+            let exponent = (failures - self.settings.lockout_threshold).min(16);
+            let secs = self
+                .settings
+                .lockout_base_secs
+                .saturating_mul(1 << exponent)
+                .min(self.settings.lockout_max_secs);
+
+            self.store.lock(&key, Duration::from_secs(secs)).await?;
+            tracing::warn!(failures, lock_secs = secs, "account locked after failed logins");
+        }
+
+        Ok(())
+    }
+
+    pub async fn record_login_success(&self, account: &str) -> Result<(), AppError> {
+        if !self.settings.enabled {
+            return Ok(());
+        }
+
+        self.store.clear_failures(&account_key(account)).await
+    }
+}
+
+// account = user id ของบัญชีที่ login (หรือ identifier ที่พิมพ์มา ถ้าไม่มีบัญชีนั้น)
+// ไม่สนตัวพิมพ์ใหญ่เล็ก/ช่องว่าง กันหลบ lockout ด้วยการเปลี่ยนรูปแบบ username
+fn account_key(account: &str) -> String {
+    format!("account:{}", account.trim().to_lowercase())
+}
+
+fn per_second(per_minute: u32) -> f64 {
+    per_minute as f64 / 60.0
+}
+
+fn limited(scope: &'static str, decision: RateDecision) -> Result<(), AppError> {
+    match decision {
+        RateDecision::Allowed => Ok(()),
+        RateDecision::Limited(wait) => {
+            metrics::counter!("rate_limited_total", "scope" => scope).increment(1);
+
+            // ปัดขึ้นเสมอ จะได้ไม่บอก client ให้รอ 0 วินาที
+            let retry_after = wait.as_secs() + u64::from(wait.subsec_nanos() > 0);
+            Err(AppError::TooManyRequests(
+                "error.request.too_many_attempts".into(),
+                retry_after.max(1),
+            ))
+        }
+    }
+}
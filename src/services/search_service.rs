@@ -1,6 +1,10 @@
 use crate::{
     controllers::products_controller::SearchQuery,
-    models::{dto::ProductSearchDocument, error::AppError},
+    models::{
+        dto::{ProductIndexChanges, ProductSearchDocument},
+        error::AppError,
+    },
+    utils::money::CurrencyConverter,
 };
 use meilisearch_sdk::{
     client::Client,
@@ -8,8 +12,48 @@ use meilisearch_sdk::{
     settings::{self, Settings},
     tasks::Task,
 };
+use std::future::Future;
+use std::time::Instant;
+use tracing::instrument;
 use uuid::Uuid;
 
+// รวม filter ที่ client ส่งมากับช่วงราคา (min/max เป็นสกุลที่ client ขอ แปลงกลับเป็นสกุลหลักของ index)
+fn price_filter(
+    params: &SearchQuery,
+    currency: &CurrencyConverter,
+) -> Result<Option<String>, AppError> {
+    let mut conditions: Vec<String> = Vec::new();
+    if let Some(filter) = params.filter.as_deref().filter(|f| !f.trim().is_empty()) {
+        conditions.push(format!("({})", filter));
+    }
+    if let Some(min) = params.min_price {
+        conditions.push(format!("price >= {}", currency.to_base(min)?));
+    }
+    if let Some(max) = params.max_price {
+        conditions.push(format!("price <= {}", currency.to_base(max)?));
+    }
+
+    Ok((!conditions.is_empty()).then(|| conditions.join(" AND ")))
+}
+
+// จับเวลา + นับ error ของทุก call ที่ยิงไป Meilisearch
+async fn observe<T, E>(
+    operation: &'static str,
+    call: impl Future<Output = Result<T, E>>,
+) -> Result<T, E> {
+    let started = Instant::now();
+    let result = call.await;
+
+    metrics::histogram!("meilisearch_request_duration_seconds", "operation" => operation)
+        .record(started.elapsed().as_secs_f64());
+    if result.is_err() {
+        metrics::counter!("meilisearch_request_failures_total", "operation" => operation)
+            .increment(1);
+    }
+
+    result
+}
+
 #[derive(Clone)]
 pub struct SearchService {
     client: Client,
@@ -20,15 +64,15 @@ impl SearchService {
         Self { client }
     }
 
+    #[instrument(skip_all, err)]
     pub async fn add_documents(&self, products: &[ProductSearchDocument]) -> Result<(), AppError> {
         let index = self.client.index(ProductSearchDocument::INDEX_NAME);
 
         // ส่งข้อมูลเป็น Batch
-        index
-            .add_documents(products, Some("id"))
+        observe("add_documents", index.add_documents(products, Some("id")))
             .await
             .map_err(|e| {
-                println!("Meilisearch Error: {:?}", e); // log error เผื่อไว้ debug
+                tracing::error!(error = ?e, "meilisearch batch index failed"); // log error เผื่อไว้ debug
                 AppError::InternalServerError("Failed to batch index products".into())
             })?;
 
@@ -36,41 +80,69 @@ impl SearchService {
     }
 
     // ฟังก์ชันเพิ่มสินค้าลง Index (ใช้ตอน Create/Update Product)
+    #[instrument(skip_all, err)]
     pub async fn add_product(&self, product: ProductSearchDocument) -> Result<(), AppError> {
         let index = self.client.index(ProductSearchDocument::INDEX_NAME);
 
         // Meilisearch รับเป็น Array ของ documents
-        index
-            .add_documents(&[product], Some("id"))
+        observe("add_documents", index.add_documents(&[product], Some("id")))
             .await
             .map_err(|_| AppError::InternalServerError("Failed to index product".into()))?;
 
         Ok(())
     }
 
+    #[instrument(skip_all, err)]
     pub async fn delete_product(&self, id: Uuid) -> Result<(), AppError> {
         let index = self.client.index(ProductSearchDocument::INDEX_NAME);
 
-        index.delete_document(id).await.map_err(|e| {
-            println!("Meilisearch Delete Error: {:?}", e);
-            AppError::InternalServerError("Failed to delete product from index".into())
-        })?;
+        observe("delete_document", index.delete_document(id))
+            .await
+            .map_err(|e| {
+                tracing::error!(error = ?e, "meilisearch delete failed");
+                AppError::InternalServerError("Failed to delete product from index".into())
+            })?;
+
+        Ok(())
+    }
+
+    // ใช้หลังเปลี่ยนสินค้าหลายตัวพร้อมกัน (เช่น ปิด/ย้าย/เปิดคืน หมวดหมู่)
+    #[instrument(skip_all, err)]
+    pub async fn apply_changes(&self, changes: ProductIndexChanges) -> Result<(), AppError> {
+        let index = self.client.index(ProductSearchDocument::INDEX_NAME);
+
+        if !changes.removed.is_empty() {
+            observe("delete_documents", index.delete_documents(&changes.removed))
+                .await
+                .map_err(|e| {
+                    tracing::error!(error = ?e, "meilisearch delete failed");
+                    AppError::InternalServerError("Failed to delete products from index".into())
+                })?;
+        }
+
+        if !changes.upserted.is_empty() {
+            self.add_documents(&changes.upserted).await?;
+        }
 
         Ok(())
     }
 
+    #[instrument(skip_all, err)]
     pub async fn search_products(
         &self,
         params: SearchQuery,
+        currency: &CurrencyConverter,
     ) -> Result<Vec<ProductSearchDocument>, AppError> {
         let index = self.client.index(ProductSearchDocument::INDEX_NAME);
+        // ราคาใน index เป็นสกุลหลัก อัตราแลกเปลี่ยนเดียวกันทั้งชุดจึง sort ด้วย price ได้ตรงลำดับเดิม
         let sort_criteria = params.sort.as_deref().map(|s| [s]);
+        let filter = price_filter(&params, currency)?;
         let mut search_builder = index.search();
         
         if let Some(query_str) = &params.q {
             search_builder.with_query(query_str);
         }
-        if let Some(filter_str) = &params.filter {
+        if let Some(filter_str) = &filter {
             search_builder.with_filter(filter_str);
         }
         if let Some(sort) = &sort_criteria {
@@ -82,24 +154,27 @@ impl SearchService {
         if let Some(offset) = params.offset {
             search_builder.with_offset(offset);
         }
-        let search_results = search_builder
-            .execute::<ProductSearchDocument>()
+        let search_results = observe("search", search_builder.execute::<ProductSearchDocument>())
             .await
             .map_err(|e| {
-                println!("Search error: {:?}", e);
-                AppError::InternalServerError("Search failed".into())
+                tracing::error!(error = ?e, "meilisearch search failed");
+                AppError::InternalServerError("error.search.failed".into())
             })?;
 
         // ดึงเฉพาะผลลัพธ์ออกมา
-        let products: Vec<ProductSearchDocument> = search_results
+        search_results
             .hits
             .into_iter()
-            .map(|result| result.result)
-            .collect();
-
-        Ok(products)
+            .map(|result| {
+                let mut product = result.result;
+                product.price = currency.convert(product.price)?;
+                product.currency = Some(currency.currency.clone());
+                Ok(product)
+            })
+            .collect()
     }
 
+    #[instrument(skip_all, err)]
     pub async fn setup_settings(&self) -> Result<(), AppError> {
         let index = self.client.index(ProductSearchDocument::INDEX_NAME);
 
@@ -108,28 +183,33 @@ impl SearchService {
             .with_filterable_attributes(&["category_id", "price", "id"])
             .with_sortable_attributes(&["price"]);
 
-        index.set_settings(&settings).await.map_err(|e| {
-            println!("Failed to update settings: {:?}", e);
-            AppError::InternalServerError("Failed to setup Meilisearch settings".into())
-        })?;
+        observe("set_settings", index.set_settings(&settings))
+            .await
+            .map_err(|e| {
+                tracing::error!(error = ?e, "meilisearch settings update failed");
+                AppError::InternalServerError("Failed to setup Meilisearch settings".into())
+            })?;
 
-        println!("Meilisearch settings updated!");
+        tracing::info!("meilisearch settings updated");
         Ok(())
     }
 
+    #[instrument(skip_all, err)]
     pub async fn delete_all_documents(&self) -> Result<(), AppError> {
         let index = self.client.index(ProductSearchDocument::INDEX_NAME);
 
-        let task = index
-            .delete_all_documents()
+        let task = observe("delete_all_documents", index.delete_all_documents())
             .await
             .map_err(|e| AppError::InternalServerError(e.to_string()))?;
 
         // ตรงนี้สำคัญ! ถ้าจะล้างบาง ควร "รอ" ให้มันลบเสร็จจริง ๆ ก่อนค่อยเติมของใหม่
         // ไม่งั้นเดี๋ยวของใหม่เข้าไปปนกับของเก่าที่กำลังทยอยลบ
-        task.wait_for_completion(&self.client, None, None)
-            .await
-            .map_err(|e| AppError::InternalServerError(e.to_string()))?;
+        observe(
+            "wait_for_task",
+            task.wait_for_completion(&self.client, None, None),
+        )
+        .await
+        .map_err(|e| AppError::InternalServerError(e.to_string()))?;
 
         Ok(())
     }
@@ -0,0 +1,177 @@
+use crate::models::entity::{ShipmentLine, ShippingMethodEntity, ShippingWeightTierEntity};
+use crate::models::error::AppError;
+use async_trait::async_trait;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+// น้ำหนักตามปริมาตร (กรัม) = กว้าง x ยาว x สูง (mm) / 5000 (เท่ากับ 5000 cm³/kg ที่ขนส่งส่วนใหญ่ใช้)
+const VOLUMETRIC_DIVISOR: i64 = 5000;
+
+// ของที่ต้องส่ง 1 ครั้ง: ทั้งตะกร้าไปประเทศเดียว (ราคาเป็นสกุลหลักของร้าน)
+#[derive(Debug)]
+pub struct Shipment {
+    pub country: String,
+    pub items: Vec<ShipmentLine>, // เรียงตาม product_id
+}
+
+impl Shipment {
+    pub fn subtotal(&self) -> Decimal {
+        self.items
+            .iter()
+            .map(|item| item.price * Decimal::from(item.quantity))
+            .sum()
+    }
+
+    // น้ำหนักที่ใช้คิดค่าส่ง: ต่อชิ้นใช้ค่าที่มากกว่าระหว่างน้ำหนักจริงกับน้ำหนักตามปริมาตร
+    // สินค้าที่ไม่ได้ระบุน้ำหนัก / ขนาดไม่ครบคิดส่วนนั้นเป็น 0
+    pub fn chargeable_weight_grams(&self) -> i64 {
+        self.items
+            .iter()
+            .map(|item| chargeable_grams(item) * i64::from(item.quantity))
+            .sum()
+    }
+}
+
+fn chargeable_grams(item: &ShipmentLine) -> i64 {
+    let actual = i64::from(item.weight_grams.unwrap_or(0));
+    let volumetric = match (item.length_mm, item.width_mm, item.height_mm) {
+        (Some(length), Some(width), Some(height)) => {
+            // ปัดขึ้น ของเล็กแต่มีขนาดต้องไม่คิดเป็น 0 (ขนาดไม่ติดลบตาม CHECK ของตาราง)
+            let volume = i64::from(length) * i64::from(width) * i64::from(height);
+            (volume + VOLUMETRIC_DIVISOR - 1) / VOLUMETRIC_DIVISOR
+        }
+        _ => 0,
+    };
+    actual.max(volumetric)
+}
+
+// วิธีจัดส่งพร้อมขั้นน้ำหนักของมัน (เรียง up_to_grams จากน้อยไปมาก)
+#[derive(Debug, Clone)]
+pub struct ShippingMethod {
+    pub method: ShippingMethodEntity,
+    pub tiers: Vec<ShippingWeightTierEntity>,
+}
+
+// ขนส่งแต่ละเจ้า (Kerry, Flash ฯลฯ) ให้ implement trait นี้แล้วลงทะเบียนใน ShippingCarriers
+// ตั้ง shipping_methods.carrier เป็นชื่อเดียวกับ carrier()
+#[async_trait]
+pub trait ShippingCarrier: Send + Sync {
+    fn carrier(&self) -> &'static str;
+
+    // None = วิธีนี้ส่ง shipment นี้ไม่ได้ (เช่น หนักเกินขั้นสุดท้าย)
+    async fn quote(
+        &self,
+        method: &ShippingMethod,
+        shipment: &Shipment,
+    ) -> Result<Option<Decimal>, AppError>;
+}
+
+// คิดราคาจากตาราง shipping_methods / shipping_weight_tiers อย่างเดียว ไม่เรียก network
+pub struct LocalTableCarrier;
+
+#[async_trait]
+impl ShippingCarrier for LocalTableCarrier {
+    fn carrier(&self) -> &'static str {
+        "local"
+    }
+
+    async fn quote(
+        &self,
+        method: &ShippingMethod,
+        shipment: &Shipment,
+    ) -> Result<Option<Decimal>, AppError> {
+        let base_cost = method.method.base_cost;
+        let cost = match method.method.rate_type.as_str() {
+            "flat" => Some(base_cost),
+            "free_over" => match method.method.free_threshold {
+                Some(threshold) if shipment.subtotal() >= threshold => Some(Decimal::ZERO),
+                _ => Some(base_cost),
+            },
+            "weight" => {
+                let weight = shipment.chargeable_weight_grams();
+                method
+                    .tiers
+                    .iter()
+                    .find(|tier| i64::from(tier.up_to_grams) >= weight)
+                    .map(|tier| base_cost + tier.cost)
+            }
+            other => {
+                tracing::warn!(method = %method.method.code, rate_type = other, "unknown shipping rate type");
+                None
+            }
+        };
+        Ok(cost)
+    }
+}
+
+pub struct ShippingCarriers {
+    carriers: HashMap<&'static str, Arc<dyn ShippingCarrier>>,
+}
+
+impl Default for ShippingCarriers {
+    fn default() -> Self {
+        let mut carriers: HashMap<&'static str, Arc<dyn ShippingCarrier>> = HashMap::new();
+        let local: Arc<dyn ShippingCarrier> = Arc::new(LocalTableCarrier);
+        carriers.insert(local.carrier(), local);
+
+        Self { carriers }
+    }
+}
+
+impl ShippingCarriers {
+    pub fn get(&self, carrier: &str) -> Option<Arc<dyn ShippingCarrier>> {
+        self.carriers.get(carrier).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn line(weight: Option<i32>, size: Option<(i32, i32, i32)>, quantity: i32) -> ShipmentLine {
+        ShipmentLine {
+            product_id: Uuid::nil(),
+            price: Decimal::ONE,
+            quantity,
+            weight_grams: weight,
+            length_mm: size.map(|s| s.0),
+            width_mm: size.map(|s| s.1),
+            height_mm: size.map(|s| s.2),
+        }
+    }
+
+    fn shipment(items: Vec<ShipmentLine>) -> Shipment {
+        Shipment {
+            country: "TH".into(),
+            items,
+        }
+    }
+
+    #[test]
+    fn heavy_small_items_use_actual_weight() {
+        // 100 x 100 x 100 mm = 200 g ตามปริมาตร
+        let shipment = shipment(vec![line(Some(500), Some((100, 100, 100)), 2)]);
+        assert_eq!(shipment.chargeable_weight_grams(), 1000);
+    }
+
+    #[test]
+    fn bulky_light_items_use_volumetric_weight() {
+        // 400 x 300 x 250 mm = 6000 g ตามปริมาตร
+        let shipment = shipment(vec![
+            line(Some(800), Some((400, 300, 250)), 1),
+            line(Some(100), None, 3),
+        ]);
+        assert_eq!(shipment.chargeable_weight_grams(), 6300);
+    }
+
+    #[test]
+    fn missing_weight_or_dimensions_count_as_zero() {
+        let shipment = shipment(vec![
+            line(None, None, 2),
+            line(None, Some((10, 10, 1)), 1), // 100 mm³ ปัดขึ้นเป็น 1 g
+        ]);
+        assert_eq!(shipment.chargeable_weight_grams(), 1);
+    }
+}
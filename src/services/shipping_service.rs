@@ -0,0 +1,176 @@
+use crate::i18n;
+use crate::models::dto::{AddressSnapshot, ShippingOptionResponse, ShippingOptionsResponse};
+use crate::models::error::AppError;
+use crate::repositories::cart_repository::CartRepository;
+use crate::repositories::shipping_repository::ShippingRepository;
+use crate::services::address_service::AddressService;
+use crate::services::shipping_carrier::{Shipment, ShippingCarriers, ShippingMethod};
+use rust_decimal::Decimal;
+use sqlx::{Pool, Postgres};
+use std::sync::Arc;
+use uuid::Uuid;
+
+// ราคาค่าส่งของวิธีหนึ่ง (สกุลหลักของร้าน)
+#[derive(Debug, Clone)]
+pub struct ShippingQuote {
+    pub method: ShippingMethod,
+    pub cost: Decimal,
+}
+
+impl From<ShippingQuote> for ShippingOptionResponse {
+    fn from(quote: ShippingQuote) -> Self {
+        let method = quote.method.method;
+        Self {
+            code: method.code,
+            name: method.name,
+            carrier: method.carrier,
+            cost: quote.cost,
+            formatted_cost: None,
+            min_days: method.min_days,
+            max_days: method.max_days,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ShippingService {
+    repo: ShippingRepository,
+    cart_repo: CartRepository,
+    address_service: AddressService,
+    carriers: Arc<ShippingCarriers>,
+}
+
+impl ShippingService {
+    pub fn new(
+        pool: Pool<Postgres>,
+        address_service: AddressService,
+        carriers: ShippingCarriers,
+    ) -> Self {
+        Self {
+            repo: ShippingRepository::new(pool.clone()),
+            cart_repo: CartRepository::new(pool),
+            address_service,
+            carriers: Arc::new(carriers),
+        }
+    }
+
+    // GET /cart/shipping-options
+    pub async fn options_for_cart(
+        &self,
+        user_id: Uuid,
+        address_id: Option<Uuid>,
+    ) -> Result<ShippingOptionsResponse, AppError> {
+        let address = match address_id {
+            Some(id) => self.address_service.snapshot(user_id, id).await?,
+            None => self
+                .address_service
+                .default_shipping_snapshot(user_id)
+                .await?
+                .ok_or(AppError::ValidationError(
+                    "error.shipping.address_required".into(),
+                ))?,
+        };
+
+        let shipment = self.cart_shipment(user_id, &address).await?;
+        let quotes = self.quote_all(&shipment).await?;
+
+        Ok(ShippingOptionsResponse {
+            country: shipment.country.clone(),
+            weight_grams: shipment.chargeable_weight_grams(),
+            options: quotes
+                .into_iter()
+                .map(ShippingOptionResponse::from)
+                .collect(),
+            currency: None,
+        })
+    }
+
+    // Checkout: method None = เลือกวิธีที่ถูกที่สุด
+    // คืน Shipment ไปด้วย ให้ Checkout ตรวจว่าตะกร้าไม่เปลี่ยนหลัง quote
+    pub async fn quote_for_checkout(
+        &self,
+        user_id: Uuid,
+        address: &AddressSnapshot,
+        method: Option<&str>,
+    ) -> Result<(ShippingQuote, Shipment), AppError> {
+        let shipment = self.cart_shipment(user_id, address).await?;
+        let quotes = self.quote_all(&shipment).await?;
+
+        let quote = match method {
+            Some(code) => quotes
+                .into_iter()
+                .find(|quote| quote.method.method.code == code)
+                .ok_or_else(|| {
+                    AppError::ValidationError(i18n::t_args(
+                        "error.shipping.unknown_method",
+                        &[("method", code)],
+                    ))
+                })?,
+            None => quotes.into_iter().min_by_key(|quote| quote.cost).ok_or(
+                AppError::ValidationError("error.shipping.unavailable".into()),
+            )?,
+        };
+        Ok((quote, shipment))
+    }
+
+    async fn cart_shipment(
+        &self,
+        user_id: Uuid,
+        address: &AddressSnapshot,
+    ) -> Result<Shipment, AppError> {
+        let items = self
+            .cart_repo
+            .find_shipment_lines(user_id)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        if items.is_empty() {
+            return Err(AppError::ValidationError("error.cart.empty".into()));
+        }
+
+        Ok(Shipment {
+            country: address.country.clone(),
+            items,
+        })
+    }
+
+    // วิธีที่ส่งไม่ได้หรือ carrier ตอบไม่ได้จะถูกข้ามไป ไม่ทำให้วิธีอื่นใช้ไม่ได้ตาม
+    async fn quote_all(&self, shipment: &Shipment) -> Result<Vec<ShippingQuote>, AppError> {
+        let methods = self
+            .repo
+            .list_active_for_country(&shipment.country)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        let ids: Vec<Uuid> = methods.iter().map(|method| method.id).collect();
+        let mut tiers = self
+            .repo
+            .find_tiers(&ids)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        let mut quotes = Vec::with_capacity(methods.len());
+        for method in methods {
+            let (method_tiers, rest): (Vec<_>, Vec<_>) = tiers
+                .drain(..)
+                .partition(|tier| tier.method_id == method.id);
+            tiers = rest;
+            let method = ShippingMethod {
+                method,
+                tiers: method_tiers,
+            };
+
+            let Some(carrier) = self.carriers.get(&method.method.carrier) else {
+                tracing::warn!(method = %method.method.code, carrier = %method.method.carrier, "unknown shipping carrier");
+                continue;
+            };
+            match carrier.quote(&method, shipment).await {
+                Ok(Some(cost)) => quotes.push(ShippingQuote { method, cost }),
+                Ok(None) => {}
+                Err(e) => {
+                    tracing::warn!(method = %method.method.code, error = %e, "could not quote shipping");
+                }
+            }
+        }
+        Ok(quotes)
+    }
+}
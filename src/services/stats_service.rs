@@ -0,0 +1,261 @@
+use crate::config::settings::StatsSettings;
+use crate::constants::ORDER_PAID;
+use crate::i18n;
+use crate::models::dto::{
+    AbandonedCartStatsResponse, CartStatsResponse, CategoryProductCount, RevenueStatsResponse,
+    SignupStatsResponse, StatsRange, StatsRangeQuery, TopProductResponse,
+};
+use crate::models::error::AppError;
+use crate::repositories::stats_repository::{StatsRepository, StatsWindow};
+use chrono::{Days, Utc};
+use rust_decimal::Decimal;
+use sqlx::{Pool, Postgres};
+use std::any::Any;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+const DEFAULT_TOP_PRODUCTS: i64 = 10;
+const MAX_TOP_PRODUCTS: i64 = 100;
+
+type CacheEntry = (Instant, Arc<dyn Any + Send + Sync>);
+
+// เก็บผล aggregate ไว้ช่วงสั้น ๆ กัน Dashboard ที่ refresh บ่อยยิง query หนักซ้ำ (ต่อ process)
+#[derive(Default)]
+struct StatsCache {
+    entries: Mutex<HashMap<String, CacheEntry>>,
+}
+
+impl StatsCache {
+    fn get<T: Clone + 'static>(&self, key: &str, ttl: Duration) -> Option<T> {
+        let entries = self.entries.lock().unwrap();
+        let (stored_at, value) = entries.get(key)?;
+        if stored_at.elapsed() >= ttl {
+            return None;
+        }
+        value.downcast_ref::<T>().cloned()
+    }
+
+    fn put<T: Send + Sync + 'static>(&self, key: String, value: T, ttl: Duration) {
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|_, (stored_at, _)| stored_at.elapsed() < ttl);
+        entries.insert(key, (Instant::now(), Arc::new(value)));
+    }
+}
+
+#[derive(Clone)]
+pub struct StatsService {
+    repo: StatsRepository,
+    settings: StatsSettings,
+    abandoned_idle_hours: i64, // = cart_recovery.idle_hours
+    currency: String,
+    cache: Arc<StatsCache>,
+}
+
+impl StatsService {
+    pub fn new(
+        pool: Pool<Postgres>,
+        settings: StatsSettings,
+        abandoned_idle_hours: i64,
+        currency: String,
+    ) -> Self {
+        Self {
+            repo: StatsRepository::new(pool),
+            settings,
+            abandoned_idle_hours,
+            currency,
+            cache: Arc::new(StatsCache::default()),
+        }
+    }
+
+    // GET /admin/stats/signups
+    pub async fn signups(&self, query: &StatsRangeQuery) -> Result<SignupStatsResponse, AppError> {
+        let range = self.range(query)?;
+        self.cached(format!("signups:{}:{}", range.from, range.to), || async move {
+            let days = self
+                .repo
+                .signups_by_day(&window(range))
+                .await
+                .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+            Ok(SignupStatsResponse {
+                range,
+                total: days.iter().map(|day| day.count).sum(),
+                days,
+            })
+        })
+        .await
+    }
+
+    // GET /admin/stats/carts
+    pub async fn carts(&self, query: &StatsRangeQuery) -> Result<CartStatsResponse, AppError> {
+        let range = self.range(query)?;
+        self.cached(format!("carts:{}:{}", range.from, range.to), || async move {
+            let (active_carts, total_items, total_value) = self
+                .repo
+                .active_carts(&window(range))
+                .await
+                .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+            Ok(CartStatsResponse {
+                range,
+                currency: self.currency.clone(),
+                active_carts,
+                total_items,
+                total_value,
+            })
+        })
+        .await
+    }
+
+    // GET /admin/stats/top-products
+    pub async fn top_products(
+        &self,
+        query: &StatsRangeQuery,
+    ) -> Result<Vec<TopProductResponse>, AppError> {
+        let range = self.range(query)?;
+        let limit = query
+            .limit
+            .unwrap_or(DEFAULT_TOP_PRODUCTS)
+            .clamp(1, MAX_TOP_PRODUCTS);
+
+        let key = format!("top-products:{}:{}:{}", range.from, range.to, limit);
+        self.cached(key, || async move {
+            self.repo
+                .top_products_by_additions(&window(range), limit)
+                .await
+                .map_err(|e| AppError::DatabaseError(e.to_string()))
+        })
+        .await
+    }
+
+    // GET /admin/stats/categories (สถานะปัจจุบัน ไม่ขึ้นกับช่วงวันที่)
+    pub async fn categories(&self) -> Result<Vec<CategoryProductCount>, AppError> {
+        self.cached("categories".to_string(), || async move {
+            self.repo
+                .category_product_counts()
+                .await
+                .map_err(|e| AppError::DatabaseError(e.to_string()))
+        })
+        .await
+    }
+
+    // GET /admin/stats/revenue
+    pub async fn revenue(&self, query: &StatsRangeQuery) -> Result<RevenueStatsResponse, AppError> {
+        let range = self.range(query)?;
+        self.cached(format!("revenue:{}:{}", range.from, range.to), || async move {
+            let window = window(range);
+            let days = self
+                .repo
+                .revenue_by_day(&window, ORDER_PAID)
+                .await
+                .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+            let categories = self
+                .repo
+                .revenue_by_category(&window, ORDER_PAID)
+                .await
+                .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+            Ok(RevenueStatsResponse {
+                range,
+                currency: self.currency.clone(),
+                order_count: days.iter().map(|day| day.orders).sum(),
+                total_revenue: days.iter().map(|day| day.revenue).sum(),
+                days,
+                categories,
+            })
+        })
+        .await
+    }
+
+    // GET /admin/stats/abandoned-carts
+    pub async fn abandoned_carts(
+        &self,
+        query: &StatsRangeQuery,
+    ) -> Result<AbandonedCartStatsResponse, AppError> {
+        let range = self.range(query)?;
+        let key = format!("abandoned-carts:{}:{}", range.from, range.to);
+        self.cached(key, || async move {
+            let (abandoned_carts, abandoned_value) = self
+                .repo
+                .abandoned_carts(self.abandoned_idle_hours)
+                .await
+                .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+            let funnel = self
+                .repo
+                .recovery_funnel(&window(range))
+                .await
+                .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+            let conversion_rate = if funnel.notified > 0 {
+                (Decimal::from(funnel.converted) / Decimal::from(funnel.notified)).round_dp(4)
+            } else {
+                Decimal::ZERO
+            };
+
+            Ok(AbandonedCartStatsResponse {
+                range,
+                currency: self.currency.clone(),
+                abandoned_carts,
+                abandoned_value,
+                notified: funnel.notified,
+                notified_value: funnel.notified_value,
+                clicked: funnel.clicked,
+                converted: funnel.converted,
+                recovered_value: funnel.recovered_value,
+                conversion_rate,
+            })
+        })
+        .await
+    }
+
+    // ไม่ส่งมา: to = วันนี้, from = ย้อนหลัง default_range_days วัน (นับรวมวันนี้)
+    fn range(&self, query: &StatsRangeQuery) -> Result<StatsRange, AppError> {
+        let to = query.to.unwrap_or_else(|| Utc::now().date_naive());
+        let from = match query.from {
+            Some(from) => from,
+            None => to
+                .checked_sub_days(Days::new(self.settings.default_range_days as u64 - 1))
+                .ok_or(AppError::ValidationError("error.stats.invalid_range".into()))?,
+        };
+
+        if from > to {
+            return Err(AppError::ValidationError("error.stats.invalid_range".into()));
+        }
+        if (to - from).num_days() + 1 > self.settings.max_range_days {
+            return Err(AppError::ValidationError(i18n::t_args(
+                "error.stats.range_too_long",
+                &[("max", &self.settings.max_range_days.to_string())],
+            )));
+        }
+        Ok(StatsRange { from, to })
+    }
+
+    async fn cached<T, F, Fut>(&self, key: String, load: F) -> Result<T, AppError>
+    where
+        T: Clone + Send + Sync + 'static,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T, AppError>>,
+    {
+        let ttl = Duration::from_secs(self.settings.cache_ttl_secs);
+        if let Some(value) = self.cache.get::<T>(&key, ttl) {
+            return Ok(value);
+        }
+
+        let value = load().await?;
+        self.cache.put(key, value.clone(), ttl);
+        Ok(value)
+    }
+}
+
+fn window(range: StatsRange) -> StatsWindow {
+    let start = range.from.and_time(Default::default()).and_utc();
+    let end = (range.to + Days::new(1)).and_time(Default::default()).and_utc();
+    StatsWindow {
+        from: range.from,
+        to: range.to,
+        start,
+        end,
+    }
+}
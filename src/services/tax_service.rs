@@ -0,0 +1,64 @@
+use crate::models::error::AppError;
+use crate::repositories::address_repository::AddressRepository;
+use crate::repositories::tax_rate_repository::TaxRateRepository;
+use crate::utils::tax::{TaxCalculator, TaxRate};
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct TaxService {
+    repo: TaxRateRepository,
+    address_repo: AddressRepository,
+    currency: String,
+    default_country: String,
+}
+
+impl TaxService {
+    pub fn new(pool: Pool<Postgres>, currency: String, default_country: String) -> Self {
+        Self {
+            repo: TaxRateRepository::new(pool.clone()),
+            address_repo: AddressRepository::new(pool),
+            currency,
+            default_country,
+        }
+    }
+
+    // อัตราภาษีตามที่อยู่จัดส่ง (ใช้ตอน Checkout)
+    pub async fn calculator(
+        &self,
+        country: &str,
+        region: Option<&str>,
+    ) -> Result<TaxCalculator, AppError> {
+        let rates = self
+            .repo
+            .find_for_location(country, region)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        let rates = rates
+            .into_iter()
+            .map(|rate| TaxRate {
+                tax_class: rate.tax_class,
+                region: rate.region,
+                name: rate.name,
+                rate: rate.rate,
+                inclusive: rate.inclusive,
+            })
+            .collect();
+        Ok(TaxCalculator::new(&self.currency, rates))
+    }
+
+    // ตะกร้ายังไม่ได้เลือกที่อยู่: ใช้ default shipping ของ user ถ้าไม่มีใช้ประเทศของร้าน
+    pub async fn calculator_for_user(&self, user_id: Uuid) -> Result<TaxCalculator, AppError> {
+        let address = self
+            .address_repo
+            .find_default_shipping(user_id)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        match address {
+            Some(address) => self.calculator(&address.country, address.region.as_deref()).await,
+            None => self.calculator(&self.default_country, None).await,
+        }
+    }
+}
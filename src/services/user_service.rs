@@ -1,35 +1,78 @@
-use crate::models::dto::{FilterOptions, UpdateUserRequest, PagedResponse, UserResponse};
+use crate::config::settings::{EmailSettings, JwtSettings};
+use crate::constants::{EMAIL_EXISTS_MSG, USER_EXISTS_MSG};
+use crate::i18n::{self, Locale};
+use crate::models::dto::{FilterOptions, Patch, UpdateUserRequest, PagedResponse, UserResponse};
+use crate::models::entity::UserEntity;
 use crate::models::error::AppError;
-use crate::repositories::user_repository::UserRepository;
+use crate::repositories::user_repository::{UserRepository, UserUpdate};
+use crate::services::notifier::{Notification, Notifier, Recipient};
+use crate::utils::{contact, jwt};
 use sqlx::{Pool, Postgres};
+use std::sync::Arc;
 use uuid::Uuid;
 
+// แปลง Unique Violation ของ users เป็น 409 (ที่เหลือเป็น DatabaseError ตามเดิม)
+pub fn user_write_error(e: sqlx::Error) -> AppError {
+    let constraint = e
+        .as_database_error()
+        .and_then(|db_error| db_error.constraint())
+        .map(str::to_string);
+
+    match constraint.as_deref() {
+        Some("users_username_key") => AppError::Conflict(USER_EXISTS_MSG.into()),
+        Some("users_email_lower_key") => AppError::Conflict(EMAIL_EXISTS_MSG.into()),
+        _ => AppError::DatabaseError(e.to_string()),
+    }
+}
+
+// username ห้ามมี @ เพราะช่อง login รับได้ทั้ง username และอีเมล
+pub fn validate_username(username: &str) -> Result<String, AppError> {
+    let username = username.trim();
+    if username.is_empty() {
+        return Err(AppError::ValidationError("error.user.username_required".into()));
+    }
+    if username.contains('@') {
+        return Err(AppError::ValidationError("error.user.username_at_sign".into()));
+    }
+    Ok(username.to_string())
+}
+
 #[derive(Clone)]
 pub struct UserService {
     repo: UserRepository,
+    notifier: Arc<dyn Notifier>,
+    jwt_settings: JwtSettings,
+    email_settings: EmailSettings,
 }
 
 impl UserService {
-    pub fn new(pool: Pool<Postgres>) -> Self {
+    pub fn new(
+        pool: Pool<Postgres>,
+        notifier: Arc<dyn Notifier>,
+        jwt_settings: JwtSettings,
+        email_settings: EmailSettings,
+    ) -> Self {
         let repo = UserRepository::new(pool);
-        Self { repo }
+        Self {
+            repo,
+            notifier,
+            jwt_settings,
+            email_settings,
+        }
     }
 
-    // Get Current User Profile
-    pub async fn get_current_user(&self, user_id: Uuid) -> Result<UserResponse, AppError> {
-        let user = self
-            .repo
+    async fn find_user(&self, user_id: Uuid) -> Result<UserEntity, AppError> {
+        self.repo
             .find_by_id(user_id)
             .await
             .map_err(|e| AppError::DatabaseError(e.to_string()))?
-            .ok_or(AppError::NotFound("User not found".into()))?;
+            .ok_or(AppError::NotFound("error.user.not_found".into()))
+    }
 
-        Ok(UserResponse {
-            id: user.id,
-            username: user.username,
-            created_at: user.created_at,
-            updated_at: user.updated_at,
-        })
+    // Get Current User Profile
+    pub async fn get_current_user(&self, user_id: Uuid) -> Result<UserResponse, AppError> {
+        let user = self.find_user(user_id).await?;
+        Ok(UserResponse::from(user))
     }
 
     // List Users
@@ -40,17 +83,7 @@ impl UserService {
             .await
             .map_err(|e| AppError::DatabaseError(e.to_string()))?;
 
-        let user_responses = users
-            .into_iter()
-            .map(|user| UserResponse {
-                id: user.id,
-                username: user.username,
-                created_at: user.created_at,
-                updated_at: user.updated_at,
-            })
-            .collect();
-
-        Ok(user_responses)
+        Ok(users.into_iter().map(UserResponse::from).collect())
     }
 
     // Update User
@@ -58,34 +91,179 @@ impl UserService {
         &self,
         user_id: Uuid,
         req: UpdateUserRequest,
+        expected_versions: Option<Vec<i32>>,
     ) -> Result<UserResponse, AppError> {
-        let new_username = req
-            .username
-            .ok_or(AppError::ValidationError("Username is required".into()))?;
+        let username = req.username.as_deref().map(validate_username).transpose()?;
+        let display_name = match req.display_name {
+            Patch::Value(name) => Patch::Value(contact::normalize_display_name(&name)?),
+            other => other,
+        };
+        let phone = match req.phone {
+            Patch::Value(phone) => Patch::Value(contact::normalize_phone(&phone)?),
+            other => other,
+        };
+        let locale = match req.locale {
+            Patch::Value(tag) => match Locale::parse(&tag) {
+                Some(locale) => Patch::Value(locale.as_str().to_string()),
+                None => {
+                    return Err(AppError::ValidationError(i18n::t_args(
+                        "error.user.invalid_locale",
+                        &[("locale", &tag)],
+                    )));
+                }
+            },
+            other => other,
+        };
+
+        // อีเมลเดิม (ต่างแค่ตัวพิมพ์) ไม่นับว่าเปลี่ยน ไม่ต้องยืนยันใหม่
+        let email = match req.email.as_deref().map(contact::normalize_email).transpose()? {
+            Some(email) => {
+                let current = self.find_user(user_id).await?;
+                let unchanged = current
+                    .email
+                    .is_some_and(|current| current.eq_ignore_ascii_case(&email));
+                (!unchanged).then_some(email)
+            }
+            None => None,
+        };
+        let email_changed = email.is_some();
 
-        let updated_user = self
+        let updated_user = match self
             .repo
-            .update_user(user_id, Some(&new_username))
+            .update_user(
+                user_id,
+                UserUpdate {
+                    username,
+                    email,
+                    display_name,
+                    phone,
+                    locale,
+                },
+                expected_versions,
+            )
             .await
-            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        {
+            Ok(user) => user,
+            Err(sqlx::Error::RowNotFound) => return Err(self.missing_or_modified(user_id).await),
+            Err(e) => return Err(user_write_error(e)),
+        };
+
+        if email_changed
+            && let Err(e) = self.send_verification(&updated_user).await
+        {
+            tracing::warn!(error = %e, "failed to send verification email");
+        }
+
+        Ok(UserResponse::from(updated_user))
+    }
+
+    // ส่งลิงก์ยืนยันอีเมลใหม่ (ใช้หลังสมัคร และเมื่อ user ขอส่งซ้ำ)
+    pub async fn send_email_verification(&self, user_id: Uuid) -> Result<(), AppError> {
+        let user = self.find_user(user_id).await?;
 
-        Ok(UserResponse {
-            id: updated_user.id,
-            username: updated_user.username,
-            created_at: updated_user.created_at,
-            updated_at: updated_user.updated_at,
+        if user.email.is_none() {
+            return Err(AppError::ValidationError("error.user.no_email".into()));
+        }
+        if user.email_verified_at.is_some() {
+            return Err(AppError::Conflict("error.user.email_already_verified".into()));
+        }
+
+        self.send_verification(&user).await
+    }
+
+    async fn send_verification(&self, user: &UserEntity) -> Result<(), AppError> {
+        let Some(email) = &user.email else {
+            return Ok(());
+        };
+
+        let token = jwt::encode_email_verification(
+            user.id,
+            email,
+            self.email_settings.verification_ttl_hours,
+            &self.jwt_settings,
+        )
+        .map_err(|e| AppError::InternalServerError(e.to_string()))?;
+
+        // ส่งเป็นภาษาที่ผู้ใช้ตั้งไว้ (ยังไม่ตั้ง = ภาษาของ request นี้)
+        let locale = user
+            .locale
+            .as_deref()
+            .and_then(Locale::parse)
+            .unwrap_or_else(i18n::current);
+        let link = format!("{}?token={}", self.email_settings.verification_url, token);
+        let notification = i18n::scope(locale, async {
+            Notification {
+                recipient: Recipient::Email(email.clone()),
+                subject: i18n::t("email.verification.subject"),
+                body: i18n::t_args(
+                    "email.verification.body",
+                    &[
+                        ("name", user.display_name.as_deref().unwrap_or(&user.username)),
+                        ("link", &link),
+                        ("hours", &self.email_settings.verification_ttl_hours.to_string()),
+                    ],
+                ),
+            }
         })
+        .await;
+
+        self.notifier.send(notification).await
     }
 
-    // Delete User
-    pub async fn delete_user(&self, user_id: Uuid) -> Result<(), AppError> {
+    pub async fn verify_email(&self, token: &str) -> Result<UserResponse, AppError> {
+        let claims = jwt::decode_email_verification(token, &self.jwt_settings)?;
+        let user_id = Uuid::parse_str(&claims.sub)
+            .map_err(|_| AppError::ValidationError("error.user.invalid_verification_token".into()))?;
+
         self.repo
-            .delete_user(user_id)
+            .mark_email_verified(user_id, &claims.email)
             .await
             .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        // กดลิงก์ซ้ำถือว่าสำเร็จ แต่ถ้าอีเมลถูกเปลี่ยนไปแล้ว token นี้ใช้ไม่ได้
+        let user = self.find_user(user_id).await?;
+        let matches = user
+            .email
+            .as_deref()
+            .is_some_and(|email| email.eq_ignore_ascii_case(&claims.email));
+        if !matches || user.email_verified_at.is_none() {
+            return Err(AppError::ValidationError(
+                "error.user.invalid_verification_token".into(),
+            ));
+        }
+
+        Ok(UserResponse::from(user))
+    }
+
+    // Delete User
+    pub async fn delete_user(
+        &self,
+        user_id: Uuid,
+        expected_versions: Option<Vec<i32>>,
+    ) -> Result<(), AppError> {
+        let deleted = self
+            .repo
+            .delete_user(user_id, expected_versions)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        if !deleted {
+            return Err(self.missing_or_modified(user_id).await);
+        }
         Ok(())
     }
 
+    // ไม่มีแถวถูกแก้: ถ้าผู้ใช้ยังอยู่แปลว่า version ไม่ตรง
+    async fn missing_or_modified(&self, user_id: Uuid) -> AppError {
+        match self.repo.find_by_id(user_id).await {
+            Ok(Some(_)) => {
+                AppError::PreconditionFailed("error.user.modified".into())
+            }
+            Ok(None) => AppError::NotFound("error.user.not_found".into()),
+            Err(e) => AppError::DatabaseError(e.to_string()),
+        }
+    }
+
     // Get Users with Pagination and Filtering
     pub async fn get_users(
         &self,
@@ -101,15 +279,7 @@ impl UserService {
             .map_err(|e| AppError::DatabaseError(e.to_string()))?;
 
         // แปลง Entity -> Response DTO
-        let user_responses: Vec<UserResponse> = users
-            .into_iter()
-            .map(|u| UserResponse {
-                id: u.id,
-                username: u.username,
-                created_at: u.created_at,
-                updated_at: u.updated_at,
-            })
-            .collect();
+        let user_responses: Vec<UserResponse> = users.into_iter().map(UserResponse::from).collect();
 
         // คำนวณจำนวนหน้าทั้งหมด
         let total_pages = (total as f64 / limit as f64).ceil() as i64;
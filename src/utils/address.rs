@@ -0,0 +1,211 @@
+use crate::i18n;
+use crate::models::dto::AddressRequest;
+use crate::models::error::AppError;
+use crate::utils::contact;
+
+// รูปแบบรหัสไปรษณีย์: '9' = ตัวเลข, 'A' = ตัวอักษร, อื่น ๆ ต้องตรงตัว
+struct CountryRule {
+    country: &'static str,
+    postal_patterns: &'static [&'static str],
+    region_required: bool,
+}
+
+const COUNTRY_RULES: &[CountryRule] = &[
+    CountryRule { country: "TH", postal_patterns: &["99999"], region_required: true },
+    CountryRule { country: "US", postal_patterns: &["99999", "99999-9999"], region_required: true },
+    CountryRule { country: "CA", postal_patterns: &["A9A 9A9"], region_required: true },
+    CountryRule { country: "JP", postal_patterns: &["999-9999"], region_required: true },
+    CountryRule { country: "SG", postal_patterns: &["999999"], region_required: false },
+    CountryRule {
+        country: "GB",
+        postal_patterns: &["A9 9AA", "A99 9AA", "AA9 9AA", "AA99 9AA", "A9A 9AA", "AA9A 9AA"],
+        region_required: false,
+    },
+];
+
+fn matches_pattern(value: &str, pattern: &str) -> bool {
+    value.chars().count() == pattern.chars().count()
+        && value.chars().zip(pattern.chars()).all(|(c, p)| match p {
+            '9' => c.is_ascii_digit(),
+            'A' => c.is_ascii_uppercase(),
+            literal => c == literal,
+        })
+}
+
+// จัดรูปแบบที่ผู้ใช้มักพิมพ์ไม่ครบ เช่น "sw1a1aa" -> "SW1A 1AA", "1000001" -> "100-0001"
+fn normalize_postal_code(country: &str, postal_code: &str) -> String {
+    let postal_code = postal_code.trim().to_uppercase();
+    // รหัสไปรษณีย์ที่รองรับเป็น ASCII ล้วน ตัวอื่นปล่อยให้ไม่ผ่าน pattern (และกัน split ตรงกลางตัวอักษร)
+    if !postal_code.is_ascii() {
+        return postal_code;
+    }
+
+    match country {
+        "GB" | "CA" if !postal_code.contains(' ') && postal_code.len() > 3 => {
+            let (outward, inward) = postal_code.split_at(postal_code.len() - 3);
+            format!("{} {}", outward, inward)
+        }
+        "JP" if postal_code.len() == 7 && postal_code.chars().all(|c| c.is_ascii_digit()) => {
+            format!("{}-{}", &postal_code[..3], &postal_code[3..])
+        }
+        _ => postal_code,
+    }
+}
+
+fn too_long(field: &str, max_len: usize) -> String {
+    i18n::t_args(
+        "error.address.field_too_long",
+        &[("field", field), ("max", &max_len.to_string())],
+    )
+}
+
+fn required(errors: &mut Vec<String>, field: &str, value: &mut String, max_len: usize) {
+    *value = value.trim().to_string();
+    if value.is_empty() {
+        errors.push(i18n::t_args("error.address.required_field", &[("field", field)]));
+    } else if value.chars().count() > max_len {
+        errors.push(too_long(field, max_len));
+    }
+}
+
+// ช่องว่างล้วนถือว่าไม่ได้กรอก
+fn optional(errors: &mut Vec<String>, field: &str, value: &mut Option<String>, max_len: usize) {
+    *value = value
+        .take()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty());
+    if value.as_ref().is_some_and(|v| v.chars().count() > max_len) {
+        errors.push(too_long(field, max_len));
+    }
+}
+
+// ตัดช่องว่าง / จัดรูปแบบ แล้วตรวจตามกฎของประเทศ (รวม error ทุกข้อไว้ในข้อความเดียว)
+pub fn normalize(address: &mut AddressRequest) -> Result<(), AppError> {
+    let mut errors = Vec::new();
+
+    optional(&mut errors, "label", &mut address.label, 50);
+    required(&mut errors, "recipient_name", &mut address.recipient_name, 100);
+    required(&mut errors, "line1", &mut address.line1, 200);
+    optional(&mut errors, "line2", &mut address.line2, 200);
+    required(&mut errors, "city", &mut address.city, 100);
+    optional(&mut errors, "region", &mut address.region, 100);
+
+    address.country = address.country.trim().to_uppercase();
+    let country_valid =
+        address.country.len() == 2 && address.country.chars().all(|c| c.is_ascii_uppercase());
+    if !country_valid {
+        errors.push(i18n::t("error.address.invalid_country"));
+    }
+
+    address.postal_code = normalize_postal_code(&address.country, &address.postal_code);
+    match COUNTRY_RULES.iter().find(|rule| rule.country == address.country) {
+        Some(rule) => {
+            if !rule
+                .postal_patterns
+                .iter()
+                .any(|pattern| matches_pattern(&address.postal_code, pattern))
+            {
+                errors.push(i18n::t_args(
+                    "error.address.invalid_postal_code_for",
+                    &[
+                        ("country", rule.country),
+                        ("patterns", &rule.postal_patterns.join(", ")),
+                    ],
+                ));
+            }
+            if rule.region_required && address.region.is_none() {
+                errors.push(i18n::t_args(
+                    "error.address.region_required",
+                    &[("country", rule.country)],
+                ));
+            }
+        }
+        // ประเทศที่ยังไม่มีกฎเฉพาะ: ตรวจแบบกว้าง ๆ
+        None => {
+            let valid = (2..=10).contains(&address.postal_code.len())
+                && address
+                    .postal_code
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == ' ' || c == '-');
+            if !valid {
+                errors.push(i18n::t("error.address.invalid_postal_code"));
+            }
+        }
+    }
+
+    optional(&mut errors, "phone", &mut address.phone, 32);
+    if let Some(phone) = &address.phone {
+        match contact::normalize_phone(phone) {
+            Ok(phone) => address.phone = Some(phone),
+            Err(e) => errors.push(e.message().to_string()),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(AppError::ValidationError(errors.join("; ")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn address(country: &str, postal_code: &str, region: Option<&str>) -> AddressRequest {
+        AddressRequest {
+            label: None,
+            recipient_name: "Somchai".into(),
+            line1: "1 Main Road".into(),
+            line2: None,
+            city: "Bangkok".into(),
+            region: region.map(str::to_string),
+            postal_code: postal_code.into(),
+            country: country.into(),
+            phone: None,
+            is_default_shipping: false,
+            is_default_billing: false,
+        }
+    }
+
+    fn normalized(country: &str, postal_code: &str, region: Option<&str>) -> Result<String, AppError> {
+        let mut req = address(country, postal_code, region);
+        normalize(&mut req).map(|_| req.postal_code)
+    }
+
+    #[test]
+    fn matches_pattern_by_character() {
+        assert!(matches_pattern("10110", "99999"));
+        assert!(matches_pattern("SW1A 1AA", "AA9A 9AA"));
+        assert!(!matches_pattern("1011", "99999"));
+        assert!(!matches_pattern("sw1a 1aa", "AA9A 9AA"));
+        assert!(!matches_pattern("1ก2", "99999"));
+    }
+
+    #[test]
+    fn postal_codes_are_reformatted_per_country() {
+        assert_eq!(normalized("gb", " sw1a1aa ", None).unwrap(), "SW1A 1AA");
+        assert_eq!(normalized("CA", "k1a0b1", Some("ON")).unwrap(), "K1A 0B1");
+        assert_eq!(normalized("JP", "1000001", Some("Tokyo")).unwrap(), "100-0001");
+        assert_eq!(normalized("US", "94105-1234", Some("CA")).unwrap(), "94105-1234");
+        assert!(normalized("TH", "1011", Some("Bangkok")).is_err());
+    }
+
+    #[test]
+    fn region_is_required_for_some_countries() {
+        for (country, postal_code) in [("TH", "10110"), ("US", "94105"), ("CA", "K1A 0B1"), ("JP", "100-0001")] {
+            assert!(normalized(country, postal_code, None).is_err(), "{}", country);
+            assert!(normalized(country, postal_code, Some("  ")).is_err(), "{}", country);
+        }
+        assert!(normalized("SG", "018956", None).is_ok());
+        assert!(normalized("GB", "SW1A 1AA", None).is_ok());
+    }
+
+    #[test]
+    fn multibyte_postal_codes_are_rejected_without_panicking() {
+        for country in ["GB", "CA", "JP", "TH", "FR"] {
+            assert!(normalized(country, "1ก2", Some("Region")).is_err(), "{}", country);
+            assert!(normalized(country, "１２３４５６７", Some("Region")).is_err(), "{}", country);
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use crate::i18n;
+use crate::models::error::AppError;
+
+const MAX_EMAIL_LENGTH: usize = 254;
+const MAX_DISPLAY_NAME_LENGTH: usize = 100;
+
+// ตรวจแบบพื้นฐานพอ (ของจริงคือส่งอีเมลยืนยันไปแล้วดูว่าเปิดได้ไหม)
+pub fn normalize_email(email: &str) -> Result<String, AppError> {
+    let email = email.trim();
+    let invalid = || {
+        AppError::ValidationError(i18n::t_args("error.user.invalid_email", &[("email", email)]))
+    };
+
+    if email.is_empty() || email.len() > MAX_EMAIL_LENGTH || email.chars().any(char::is_whitespace) {
+        return Err(invalid());
+    }
+    let (local, domain) = email.rsplit_once('@').ok_or_else(invalid)?;
+    if local.is_empty()
+        || local.contains('@')
+        || domain.len() < 3
+        || !domain.contains('.')
+        || domain.starts_with('.')
+        || domain.ends_with('.')
+    {
+        return Err(invalid());
+    }
+
+    // เก็บตัวพิมพ์ตามที่กรอก แต่ทั้งระบบเทียบแบบ LOWER(email)
+    Ok(email.to_string())
+}
+
+// อนุญาตตัวเลข ช่องว่าง + - ( ) เช่น "+66 81-234-5678"
+pub fn normalize_phone(phone: &str) -> Result<String, AppError> {
+    let phone = phone.trim();
+    let digits = phone.chars().filter(char::is_ascii_digit).count();
+    let allowed = phone
+        .chars()
+        .all(|c| c.is_ascii_digit() || matches!(c, ' ' | '+' | '-' | '(' | ')'));
+
+    if !allowed || !(6..=15).contains(&digits) || phone.len() > 32 {
+        return Err(AppError::ValidationError(i18n::t_args(
+            "error.user.invalid_phone",
+            &[("phone", phone)],
+        )));
+    }
+    Ok(phone.to_string())
+}
+
+pub fn normalize_display_name(name: &str) -> Result<String, AppError> {
+    let name = name.trim();
+    if name.is_empty() || name.chars().count() > MAX_DISPLAY_NAME_LENGTH {
+        return Err(AppError::ValidationError(i18n::t_args(
+            "error.user.display_name_length",
+            &[("max", &MAX_DISPLAY_NAME_LENGTH.to_string())],
+        )));
+    }
+    Ok(name.to_string())
+}
@@ -0,0 +1,104 @@
+use axum::{
+    http::{HeaderMap, HeaderValue, StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+
+use crate::models::error::AppError;
+use crate::models::response::ApiResponse;
+
+// ETag ของ resource สร้างจาก version (เพิ่มทุกครั้งที่แก้ไข)
+pub fn etag_for(version: i32) -> HeaderValue {
+    HeaderValue::from_str(&format!("\"{}\"", version)).expect("ETag is always valid ASCII")
+}
+
+// แกะ version ออกจาก ETag รองรับทั้งแบบ "3" และ W/"3"
+fn parse_etag(tag: &str) -> Option<i32> {
+    let tag = tag.trim();
+    let tag = tag.strip_prefix("W/").unwrap_or(tag);
+    tag.strip_prefix('"')?.strip_suffix('"')?.parse().ok()
+}
+
+// If-Match: None = ไม่ได้ส่งมา หรือส่ง "*" (ยอมรับทุก version)
+// Some = version ที่ยอมรับ ตรงตัวใดตัวหนึ่งก็ผ่าน (RFC 9110)
+// If-Match ต้องเทียบแบบ strong จึงตัด W/ ออก ถ้าเหลือว่างก็ไม่มีทาง match (ตอบ 412)
+pub fn if_match_versions(headers: &HeaderMap) -> Result<Option<Vec<i32>>, AppError> {
+    let Some(value) = headers.get(header::IF_MATCH) else {
+        return Ok(None);
+    };
+
+    let invalid = || AppError::PreconditionFailed("error.request.invalid_if_match".into());
+    let value = value.to_str().map_err(|_| invalid())?;
+
+    if value.trim() == "*" {
+        return Ok(None);
+    }
+
+    let mut versions = Vec::new();
+    for tag in value.split(',') {
+        let version = parse_etag(tag).ok_or_else(invalid)?;
+        if !tag.trim().starts_with("W/") {
+            versions.push(version);
+        }
+    }
+    Ok(Some(versions))
+}
+
+// If-None-Match: true = client มี version นี้อยู่แล้ว (ตอบ 304 ได้)
+pub fn if_none_match_hits(headers: &HeaderMap, version: i32) -> bool {
+    headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .map(|value| {
+            value.trim() == "*" || value.split(',').any(|tag| parse_etag(tag) == Some(version))
+        })
+        .unwrap_or(false)
+}
+
+// ตอบกลับพร้อม ETag ของ version ล่าสุด (ใช้หลัง PATCH/PUT)
+pub fn with_etag<T: Serialize>(version: i32, response: ApiResponse<T>) -> Response {
+    ([(header::ETAG, etag_for(version))], response).into_response()
+}
+
+// สำหรับ GET: ถ้า client มี version นี้อยู่แล้วตอบ 304 โดยไม่ต้องส่ง body
+pub fn conditional_get<T: Serialize>(
+    headers: &HeaderMap,
+    version: i32,
+    response: ApiResponse<T>,
+) -> Response {
+    if if_none_match_hits(headers, version) {
+        return (StatusCode::NOT_MODIFIED, [(header::ETAG, etag_for(version))]).into_response();
+    }
+    with_etag(version, response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn if_match(value: &str) -> Result<Option<Vec<i32>>, AppError> {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::IF_MATCH, HeaderValue::from_str(value).unwrap());
+        if_match_versions(&headers)
+    }
+
+    #[test]
+    fn any_listed_strong_tag_is_accepted() {
+        assert_eq!(if_match("\"3\"").unwrap(), Some(vec![3]));
+        assert_eq!(if_match("\"1\", \"2\"").unwrap(), Some(vec![1, 2]));
+        assert_eq!(if_match("*").unwrap(), None);
+        assert_eq!(if_match_versions(&HeaderMap::new()).unwrap(), None);
+    }
+
+    #[test]
+    fn weak_tags_never_match() {
+        assert_eq!(if_match("W/\"3\"").unwrap(), Some(vec![]));
+        assert_eq!(if_match("W/\"2\", \"3\"").unwrap(), Some(vec![3]));
+    }
+
+    #[test]
+    fn malformed_tag_is_rejected() {
+        assert!(if_match("3").is_err());
+        assert!(if_match("\"3\", nope").is_err());
+    }
+}
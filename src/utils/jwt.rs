@@ -1,9 +1,10 @@
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, Utc};
 use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
-use std::env;
 
+use crate::config::settings::JwtSettings;
+use crate::constants::ROLE_ADMIN;
 use crate::models::error::AppError;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -11,35 +12,162 @@ pub struct Claims {
     pub sub: String, // Subject (User ID)
     pub iat: usize,  // Issued At
     pub exp: usize,  // Expiration
+    #[serde(default)]
+    pub role: String, // สิทธิ์ผู้ใช้ตอนออก Token (Token เก่าที่ไม่มี field นี้ถือว่าไม่มีสิทธิ์พิเศษ)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>, // ภาษาที่ผู้ใช้เลือกไว้ตอน login (เปลี่ยนแล้วมีผลเมื่อ login ใหม่)
 }
 
 // Function สร้าง Token
-pub fn encode_jwt(user_id: Uuid) -> Result<String, jsonwebtoken::errors::Error> {
-    let secret = env::var("JWT_SECRET").expect("JWT_SECRET must be set");
+pub fn encode_jwt(
+    user_id: Uuid,
+    role: &str,
+    locale: Option<&str>,
+    settings: &JwtSettings,
+) -> Result<String, jsonwebtoken::errors::Error> {
     let now = Utc::now();
-    let expire = now + Duration::hours(24);
+    let expire = now + Duration::hours(settings.expiration_hours);
 
     let claims = Claims {
         sub: user_id.to_string(),
         iat: now.timestamp() as usize,
         exp: expire.timestamp() as usize,
+        role: role.to_string(),
+        locale: locale.map(str::to_string),
     };
 
     encode(
         &Header::default(),
         &claims,
-        &EncodingKey::from_secret(secret.as_bytes()),
+        &EncodingKey::from_secret(settings.secret.expose().as_bytes()),
     )
 }
 
+// Token ยืนยันอีเมล: ใช้ key แยกจาก Access Token (กันเอา token ยืนยันอีเมลไปใช้แทน login)
+// และผูกกับอีเมล ถ้า user เปลี่ยนอีเมลไปแล้ว token เก่าจะใช้ไม่ได้
+const EMAIL_VERIFICATION_PURPOSE: &str = "email_verification";
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EmailVerificationClaims {
+    pub sub: String,
+    pub email: String,
+    pub purpose: String,
+    pub iat: usize,
+    pub exp: usize,
+}
+
+fn email_verification_secret(settings: &JwtSettings) -> Vec<u8> {
+    format!("{}:{}", settings.secret.expose(), EMAIL_VERIFICATION_PURPOSE).into_bytes()
+}
+
+pub fn encode_email_verification(
+    user_id: Uuid,
+    email: &str,
+    ttl_hours: i64,
+    settings: &JwtSettings,
+) -> Result<String, jsonwebtoken::errors::Error> {
+    let now = Utc::now();
+    let claims = EmailVerificationClaims {
+        sub: user_id.to_string(),
+        email: email.to_string(),
+        purpose: EMAIL_VERIFICATION_PURPOSE.to_string(),
+        iat: now.timestamp() as usize,
+        exp: (now + Duration::hours(ttl_hours)).timestamp() as usize,
+    };
+
+    encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(&email_verification_secret(settings)),
+    )
+}
+
+pub fn decode_email_verification(
+    token: &str,
+    settings: &JwtSettings,
+) -> Result<EmailVerificationClaims, AppError> {
+    let invalid = || AppError::ValidationError("error.user.invalid_verification_token".into());
+
+    let claims = decode::<EmailVerificationClaims>(
+        token,
+        &DecodingKey::from_secret(&email_verification_secret(settings)),
+        &Validation::default(),
+    )
+    .map_err(|_| invalid())?
+    .claims;
+
+    if claims.purpose != EMAIL_VERIFICATION_PURPOSE {
+        return Err(invalid());
+    }
+    Ok(claims)
+}
+
+// Token ในลิงก์กู้ตะกร้า: ระบุการแจ้งเตือน (rid) เพื่อนับการเปิดลิงก์ ใช้ key แยกเช่นเดียวกัน
+const CART_RECOVERY_PURPOSE: &str = "cart_recovery";
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CartRecoveryClaims {
+    pub sub: String,
+    pub rid: String, // cart_recoveries.id
+    pub purpose: String,
+    pub iat: usize,
+    pub exp: usize,
+}
+
+fn cart_recovery_secret(settings: &JwtSettings) -> Vec<u8> {
+    format!("{}:{}", settings.secret.expose(), CART_RECOVERY_PURPOSE).into_bytes()
+}
+
+pub fn encode_cart_recovery(
+    user_id: Uuid,
+    recovery_id: Uuid,
+    expires_at: DateTime<Utc>,
+    settings: &JwtSettings,
+) -> Result<String, jsonwebtoken::errors::Error> {
+    let claims = CartRecoveryClaims {
+        sub: user_id.to_string(),
+        rid: recovery_id.to_string(),
+        purpose: CART_RECOVERY_PURPOSE.to_string(),
+        iat: Utc::now().timestamp() as usize,
+        exp: expires_at.timestamp() as usize,
+    };
+
+    encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(&cart_recovery_secret(settings)),
+    )
+}
+
+pub fn decode_cart_recovery(
+    token: &str,
+    settings: &JwtSettings,
+) -> Result<CartRecoveryClaims, AppError> {
+    let invalid = || AppError::ValidationError("error.cart.invalid_recovery_token".into());
+
+    let claims = decode::<CartRecoveryClaims>(
+        token,
+        &DecodingKey::from_secret(&cart_recovery_secret(settings)),
+        &Validation::default(),
+    )
+    .map_err(|_| invalid())?
+    .claims;
+
+    if claims.purpose != CART_RECOVERY_PURPOSE {
+        return Err(invalid());
+    }
+    Ok(claims)
+}
+
 // Function แกะ Token
-pub fn decode_jwt(token: &str) -> Result<Claims, jsonwebtoken::errors::Error> {
-    let secret = env::var("JWT_SECRET").expect("JWT_SECRET must be set");
-    
+pub fn decode_jwt(
+    token: &str,
+    settings: &JwtSettings,
+) -> Result<Claims, jsonwebtoken::errors::Error> {
     // Validation default คือเช็ค exp ให้อัตโนมัติ
     let token_data = decode::<Claims>(
         token,
-        &DecodingKey::from_secret(secret.as_bytes()),
+        &DecodingKey::from_secret(settings.secret.expose().as_bytes()),
         &Validation::default(),
     )?;
 
@@ -50,6 +178,10 @@ pub fn decode_jwt(token: &str) -> Result<Claims, jsonwebtoken::errors::Error> {
 impl Claims {
     pub fn get_user_id(&self) -> Result<Uuid, AppError> {
         Uuid::parse_str(&self.sub)
-            .map_err(|_| AppError::AuthError("Invalid User ID format in token".into()))
+            .map_err(|_| AppError::AuthError("error.auth.invalid_token_subject".into()))
+    }
+
+    pub fn is_admin(&self) -> bool {
+        self.role == ROLE_ADMIN
     }
 }
\ No newline at end of file
@@ -1 +1,8 @@
-pub mod jwt;
\ No newline at end of file
+pub mod address;
+pub mod contact;
+pub mod etag;
+pub mod jwt;
+pub mod money;
+pub mod password_policy;
+pub mod product_transfer;
+pub mod tax;
@@ -0,0 +1,183 @@
+use crate::i18n::{self, Locale};
+use crate::models::error::AppError;
+use rust_decimal::{Decimal, RoundingStrategy};
+
+// จำนวนหลักทศนิยมตาม ISO 4217 (ส่วนใหญ่ 2 หลัก)
+pub fn minor_units(currency: &str) -> u32 {
+    match currency {
+        "JPY" | "KRW" | "VND" | "CLP" | "ISK" | "UGX" => 0,
+        "BHD" | "KWD" | "OMR" | "JOD" | "TND" => 3,
+        _ => 2,
+    }
+}
+
+// ปัดแบบการค้า (.5 ปัดขึ้น) ตามจำนวนหลักของสกุลเงิน
+pub fn round(amount: Decimal, currency: &str) -> Decimal {
+    amount.round_dp_with_strategy(minor_units(currency), RoundingStrategy::MidpointAwayFromZero)
+}
+
+fn symbol(currency: &str) -> Option<&'static str> {
+    match currency {
+        "THB" => Some("฿"),
+        "USD" => Some("$"),
+        "EUR" => Some("€"),
+        "GBP" => Some("£"),
+        "JPY" => Some("¥"),
+        _ => None,
+    }
+}
+
+// 1234567.5 -> "1,234,567.50"
+fn group_thousands(amount: Decimal, currency: &str) -> String {
+    let rounded = round(amount, currency);
+    let text = rounded.abs().to_string();
+    let (integer, fraction) = match text.split_once('.') {
+        Some((integer, fraction)) => (integer.to_string(), fraction.to_string()),
+        None => (text, String::new()),
+    };
+    let digits = minor_units(currency) as usize;
+
+    let mut grouped = String::new();
+    for (i, c) in integer.chars().enumerate() {
+        if i > 0 && (integer.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(c);
+    }
+    if digits > 0 {
+        grouped.push('.');
+        grouped.push_str(&format!("{:0<width$}", fraction, width = digits));
+    }
+    // เช็คหลังปัด: -0.001 ปัดแล้วเป็น 0 ต้องไม่แสดงเป็น "-0.00"
+    if rounded.is_sign_negative() && !rounded.is_zero() {
+        grouped.insert(0, '-');
+    }
+    grouped
+}
+
+// ข้อความราคาสำหรับแสดงผลตามภาษาของ request
+// th: "1,250.00 บาท" / "$12.50"   en: "฿1,250.00" / "$12.50"   ไม่รู้จักสัญลักษณ์: "12.50 CHF"
+pub fn format(amount: Decimal, currency: &str) -> String {
+    let number = group_thousands(amount, currency);
+    match (i18n::current(), currency, symbol(currency)) {
+        (Locale::Th, "THB", _) => format!("{} บาท", number),
+        (_, _, Some(symbol)) => format!("{}{}", symbol, number),
+        (_, _, None) => format!("{} {}", number, currency),
+    }
+}
+
+fn out_of_range() -> AppError {
+    AppError::ValidationError("error.currency.amount_out_of_range".into())
+}
+
+// แปลงราคาจากสกุลเงินหลักของร้านเป็นสกุลที่ client ขอ
+#[derive(Debug, Clone)]
+pub struct CurrencyConverter {
+    pub currency: String,
+    pub rate: Decimal, // 1 หน่วยของสกุลหลัก = rate หน่วยของ currency
+}
+
+impl CurrencyConverter {
+    pub fn base(currency: &str) -> Self {
+        Self {
+            currency: currency.to_string(),
+            rate: Decimal::ONE,
+        }
+    }
+
+    // อัตราแลกเปลี่ยนสูงมากคูณแล้วอาจเกินช่วงของ Decimal ตอบ 400 แทน panic
+    pub fn convert(&self, amount: Decimal) -> Result<Decimal, AppError> {
+        amount
+            .checked_mul(self.rate)
+            .map(|converted| round(converted, &self.currency))
+            .ok_or_else(out_of_range)
+    }
+
+    // ราคาที่ client ส่งมา (เช่น min_price ตอนค้นหา) กลับเป็นสกุลหลัก ไม่ปัดเพื่อไม่ให้ขอบเขตเพี้ยน
+    // ค่าที่ client ส่งมาใหญ่แค่ไหนก็ได้ หารด้วย rate ที่น้อยมากแล้วล้นได้
+    pub fn to_base(&self, amount: Decimal) -> Result<Decimal, AppError> {
+        amount
+            .checked_div(self.rate)
+            .map(|base| base.normalize())
+            .ok_or_else(out_of_range)
+    }
+
+    pub fn format(&self, amount: Decimal) -> String {
+        format(amount, &self.currency)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(value: &str) -> Decimal {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn minor_units_follow_iso_4217() {
+        assert_eq!(minor_units("THB"), 2);
+        assert_eq!(minor_units("USD"), 2);
+        assert_eq!(minor_units("JPY"), 0);
+        assert_eq!(minor_units("KWD"), 3);
+        assert_eq!(minor_units("XYZ"), 2);
+    }
+
+    #[test]
+    fn round_uses_midpoint_away_from_zero() {
+        assert_eq!(round(d("1.005"), "USD"), d("1.01"));
+        assert_eq!(round(d("-1.005"), "USD"), d("-1.01"));
+        assert_eq!(round(d("1.004"), "USD"), d("1.00"));
+        assert_eq!(round(d("2.5"), "JPY"), d("3"));
+        assert_eq!(round(d("-2.5"), "JPY"), d("-3"));
+        assert_eq!(round(d("1.2345"), "KWD"), d("1.235"));
+    }
+
+    #[test]
+    fn group_thousands_pads_fraction_and_keeps_sign() {
+        assert_eq!(group_thousands(d("1234567.5"), "THB"), "1,234,567.50");
+        assert_eq!(group_thousands(d("999"), "USD"), "999.00");
+        assert_eq!(group_thousands(d("0"), "USD"), "0.00");
+        assert_eq!(group_thousands(d("-1234.5"), "USD"), "-1,234.50");
+        assert_eq!(group_thousands(d("-0.001"), "USD"), "0.00");
+        assert_eq!(group_thousands(d("1234567.6"), "JPY"), "1,234,568");
+        assert_eq!(group_thousands(d("-1000"), "JPY"), "-1,000");
+        assert_eq!(group_thousands(d("1234.5"), "KWD"), "1,234.500");
+        assert_eq!(group_thousands(d("0.0004"), "KWD"), "0.000");
+    }
+
+    #[test]
+    fn to_base_reverses_convert_without_rounding() {
+        let converter = CurrencyConverter {
+            currency: "USD".into(),
+            rate: d("0.028"),
+        };
+
+        assert_eq!(converter.convert(d("1000")).unwrap(), d("28.00"));
+        assert_eq!(converter.to_base(d("28")).unwrap(), d("1000"));
+        assert_eq!(converter.to_base(d("1")).unwrap(), d("1") / d("0.028"));
+        assert_eq!(CurrencyConverter::base("THB").to_base(d("12.50")).unwrap(), d("12.5"));
+    }
+
+    #[test]
+    fn overflow_is_a_validation_error() {
+        let tiny_rate = CurrencyConverter {
+            currency: "USD".into(),
+            rate: d("0.0000000001"),
+        };
+        assert!(matches!(
+            tiny_rate.to_base(Decimal::MAX),
+            Err(AppError::ValidationError(_))
+        ));
+
+        let huge_rate = CurrencyConverter {
+            currency: "USD".into(),
+            rate: d("10000000000"),
+        };
+        assert!(matches!(
+            huge_rate.convert(Decimal::MAX),
+            Err(AppError::ValidationError(_))
+        ));
+    }
+}
@@ -0,0 +1,80 @@
+use crate::config::settings::PasswordSettings;
+use crate::i18n;
+use crate::models::error::AppError;
+use std::collections::HashSet;
+use std::sync::OnceLock;
+
+// รายการรหัสผ่านยอดฮิต (1 บรรทัดต่อ 1 รหัส, เทียบแบบไม่สนตัวพิมพ์)
+const COMMON_PASSWORDS: &str = include_str!("common_passwords.txt");
+
+fn common_passwords() -> &'static HashSet<&'static str> {
+    static LIST: OnceLock<HashSet<&'static str>> = OnceLock::new();
+    LIST.get_or_init(|| {
+        COMMON_PASSWORDS
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .collect()
+    })
+}
+
+#[derive(Clone)]
+pub struct PasswordPolicy {
+    min_length: usize,
+    min_character_classes: usize,
+}
+
+impl PasswordPolicy {
+    pub fn new(settings: &PasswordSettings) -> Self {
+        Self {
+            min_length: settings.min_length,
+            min_character_classes: settings.min_character_classes,
+        }
+    }
+
+    // รวมทุกข้อที่ไม่ผ่านไว้ในข้อความเดียว
+    pub fn validate(&self, username: &str, password: &str) -> Result<(), AppError> {
+        let mut problems = Vec::new();
+
+        if password.chars().count() < self.min_length {
+            problems.push(i18n::t_args(
+                "error.password.too_short",
+                &[("min", &self.min_length.to_string())],
+            ));
+        }
+
+        let classes = [
+            password.chars().any(|c| c.is_lowercase()),
+            password.chars().any(|c| c.is_uppercase()),
+            password.chars().any(|c| c.is_ascii_digit()),
+            password.chars().any(|c| !c.is_alphanumeric()),
+        ]
+        .iter()
+        .filter(|present| **present)
+        .count();
+        if classes < self.min_character_classes {
+            problems.push(i18n::t_args(
+                "error.password.character_classes",
+                &[("min", &self.min_character_classes.to_string())],
+            ));
+        }
+
+        let lowered = password.to_lowercase();
+        if common_passwords().contains(lowered.as_str()) {
+            problems.push(i18n::t("error.password.too_common"));
+        }
+        let username = username.trim().to_lowercase();
+        if !username.is_empty() && lowered.contains(&username) {
+            problems.push(i18n::t("error.password.contains_username"));
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(AppError::ValidationError(i18n::t_args(
+                "error.password.invalid",
+                &[("problems", &problems.join(", "))],
+            )))
+        }
+    }
+}
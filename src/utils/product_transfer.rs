@@ -0,0 +1,235 @@
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::constants::DEFAULT_LOW_STOCK_THRESHOLD;
+use crate::i18n;
+use crate::models::dto::{ProductImportRecord, ProductTransferRow, TransferFormat};
+use crate::models::entity::CategoryEntity;
+use crate::models::error::AppError;
+
+// ลำดับคอลัมน์ CSV ต้องตรงกับ field ของ ProductTransferRow
+const CSV_HEADERS: [&str; 10] = [
+    "sku",
+    "external_id",
+    "category_id",
+    "category_name",
+    "name",
+    "description",
+    "price",
+    "stock",
+    "is_active",
+    "low_stock_threshold",
+];
+
+// ราคาสูงสุดที่ DECIMAL(10, 2) เก็บได้ (99,999,999.99)
+const MAX_PRICE: Decimal = Decimal::from_parts(1_410_065_407, 2, 0, false, 2);
+
+impl TransferFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            TransferFormat::Csv => "text/csv; charset=utf-8",
+            TransferFormat::Ndjson => "application/x-ndjson",
+        }
+    }
+
+    pub fn file_extension(&self) -> &'static str {
+        match self {
+            TransferFormat::Csv => "csv",
+            TransferFormat::Ndjson => "ndjson",
+        }
+    }
+
+    // เดารูปแบบจาก Content-Type (ใช้เมื่อไม่ได้ส่ง ?format= มา)
+    pub fn from_content_type(content_type: &str) -> Option<Self> {
+        let content_type = content_type.to_ascii_lowercase();
+        if content_type.contains("csv") {
+            Some(TransferFormat::Csv)
+        } else if content_type.contains("ndjson") || content_type.contains("jsonlines") {
+            Some(TransferFormat::Ndjson)
+        } else {
+            None
+        }
+    }
+}
+
+// แปลง body เป็นรายการแถว (เลขแถว, ผลการ parse) โดยไม่หยุดที่แถวเสีย
+pub fn parse_rows(
+    format: TransferFormat,
+    body: &[u8],
+) -> Vec<(usize, Result<ProductTransferRow, String>)> {
+    match format {
+        TransferFormat::Csv => {
+            let mut reader = csv::ReaderBuilder::new()
+                .trim(csv::Trim::All)
+                .from_reader(body);
+
+            reader
+                .deserialize::<ProductTransferRow>()
+                .enumerate()
+                .map(|(i, result)| (i + 1, result.map_err(|e| e.to_string())))
+                .collect()
+        }
+        TransferFormat::Ndjson => String::from_utf8_lossy(body)
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| (i + 1, serde_json::from_str(line).map_err(|e| e.to_string())))
+            .collect(),
+    }
+}
+
+// Lookup หมวดหมู่ได้ทั้งจาก id และชื่อ (ชื่อไม่สนตัวพิมพ์เล็กใหญ่)
+pub struct CategoryLookup {
+    by_id: HashMap<Uuid, String>,
+    by_name: HashMap<String, Uuid>,
+}
+
+impl CategoryLookup {
+    pub fn new(categories: Vec<CategoryEntity>) -> Self {
+        let mut by_id = HashMap::new();
+        let mut by_name = HashMap::new();
+        for category in categories {
+            by_name.insert(category.name.to_lowercase(), category.id);
+            by_id.insert(category.id, category.name);
+        }
+        Self { by_id, by_name }
+    }
+
+    fn resolve(&self, id: Option<Uuid>, name: Option<&str>) -> Result<Uuid, String> {
+        match (id, name.filter(|n| !n.trim().is_empty())) {
+            (Some(id), name) => {
+                let found = self
+                    .by_id
+                    .get(&id)
+                    .ok_or_else(|| {
+                        i18n::t_args("error.import.category_id_not_found", &[("id", &id.to_string())])
+                    })?;
+                match name {
+                    Some(name) if found.to_lowercase() != name.trim().to_lowercase() => {
+                        Err(i18n::t_args(
+                            "error.import.category_mismatch",
+                            &[("id", &id.to_string()), ("name", name)],
+                        ))
+                    }
+                    _ => Ok(id),
+                }
+            }
+            (None, Some(name)) => self
+                .by_name
+                .get(&name.trim().to_lowercase())
+                .copied()
+                .ok_or_else(|| i18n::t_args("error.import.category_not_found", &[("name", name)])),
+            (None, None) => Err(i18n::t("error.import.category_required")),
+        }
+    }
+}
+
+// Validate แถวเดียว คืน error ทั้งหมดของแถวนั้นพร้อมกัน
+pub fn validate_row(
+    row: ProductTransferRow,
+    categories: &CategoryLookup,
+) -> Result<ProductImportRecord, Vec<String>> {
+    let mut errors = Vec::new();
+
+    let sku = non_empty(row.sku);
+    let external_id = non_empty(row.external_id);
+    if sku.is_none() && external_id.is_none() {
+        errors.push(i18n::t("error.import.key_required"));
+    }
+
+    let name = row.name.trim().to_string();
+    if name.is_empty() {
+        errors.push(i18n::t("error.import.name_required"));
+    }
+
+    if row.price.is_sign_negative() {
+        errors.push(i18n::t("error.import.price_negative"));
+    } else if row.price.round_dp(2) != row.price {
+        errors.push(i18n::t("error.import.price_scale"));
+    } else if row.price > MAX_PRICE {
+        errors.push(i18n::t_args(
+            "error.import.price_too_large",
+            &[("max", &MAX_PRICE.to_string())],
+        ));
+    }
+
+    if row.stock < 0 {
+        errors.push(i18n::t("error.import.stock_negative"));
+    }
+
+    let low_stock_threshold = row
+        .low_stock_threshold
+        .unwrap_or(DEFAULT_LOW_STOCK_THRESHOLD);
+    if low_stock_threshold < 0 {
+        errors.push(i18n::t("error.import.low_stock_threshold_negative"));
+    }
+
+    let category_id = match categories.resolve(row.category_id, row.category_name.as_deref()) {
+        Ok(id) => Some(id),
+        Err(e) => {
+            errors.push(e);
+            None
+        }
+    };
+
+    match category_id {
+        Some(category_id) if errors.is_empty() => Ok(ProductImportRecord {
+            sku,
+            external_id,
+            category_id,
+            name,
+            description: non_empty(row.description),
+            price: row.price,
+            stock: row.stock,
+            is_active: row.is_active.unwrap_or(true),
+            low_stock_threshold,
+        }),
+        _ => Err(errors),
+    }
+}
+
+// เขียนแถวเป็น bytes ตามรูปแบบ (CSV ใส่ header เฉพาะ chunk แรก)
+pub fn write_rows(
+    format: TransferFormat,
+    rows: &[ProductTransferRow],
+    include_header: bool,
+) -> Result<Vec<u8>, AppError> {
+    match format {
+        TransferFormat::Csv => {
+            let mut writer = csv::WriterBuilder::new()
+                .has_headers(false)
+                .from_writer(Vec::new());
+
+            if include_header {
+                writer
+                    .write_record(CSV_HEADERS)
+                    .map_err(|e| AppError::InternalServerError(e.to_string()))?;
+            }
+            for row in rows {
+                writer
+                    .serialize(row)
+                    .map_err(|e| AppError::InternalServerError(e.to_string()))?;
+            }
+
+            writer
+                .into_inner()
+                .map_err(|e| AppError::InternalServerError(e.to_string()))
+        }
+        TransferFormat::Ndjson => {
+            let mut buf = Vec::new();
+            for row in rows {
+                serde_json::to_writer(&mut buf, row)
+                    .map_err(|e| AppError::InternalServerError(e.to_string()))?;
+                buf.push(b'\n');
+            }
+            Ok(buf)
+        }
+    }
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
@@ -0,0 +1,273 @@
+use crate::utils::money;
+use rust_decimal::Decimal;
+
+// อัตราภาษีของ tax class หนึ่ง (region None = ใช้ทั้งประเทศ)
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaxRate {
+    pub tax_class: String,
+    pub region: Option<String>,
+    pub name: String,
+    pub rate: Decimal,   // 0.07 = 7%
+    pub inclusive: bool, // true = ราคาสินค้ารวมภาษีแล้ว (เช่น VAT ไทย)
+}
+
+// ภาษีของสินค้า 1 บรรทัด (ราคาต่อชิ้น x จำนวน)
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineTax {
+    pub name: Option<String>, // None = ไม่มีภาษี
+    pub rate: Decimal,
+    pub inclusive: bool,
+    pub net: Decimal,   // ยอดก่อนภาษี
+    pub tax: Decimal,
+    pub gross: Decimal, // ยอดที่ลูกค้าจ่ายจริงของบรรทัดนี้
+}
+
+impl LineTax {
+    // ปัดภาษีทีละบรรทัดตามหลักของสกุลเงิน ยอดรวมจึงเท่ากับผลบวกของทุกบรรทัดเสมอ
+    // inclusive: ถอดภาษีออกจากราคา, exclusive: บวกภาษีเพิ่มจากราคา
+    pub fn new(
+        amount: Decimal,
+        rate: Decimal,
+        inclusive: bool,
+        name: Option<String>,
+        currency: &str,
+    ) -> Self {
+        let amount = money::round(amount, currency);
+        let (net, tax, gross) = if inclusive {
+            let tax = money::round(amount * rate / (Decimal::ONE + rate), currency);
+            (amount - tax, tax, amount)
+        } else {
+            let tax = money::round(amount * rate, currency);
+            (amount, tax, amount + tax)
+        };
+
+        Self {
+            name,
+            rate,
+            inclusive,
+            net,
+            tax,
+            gross,
+        }
+    }
+}
+
+// ยอดภาษีแยกตามชื่อ/อัตรา สำหรับแสดงสรุปท้ายตะกร้า
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaxBreakdown {
+    pub name: String,
+    pub rate: Decimal,
+    pub inclusive: bool,
+    pub taxable_amount: Decimal,
+    pub tax: Decimal,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TaxSummary {
+    pub net_total: Decimal,
+    pub tax_total: Decimal,
+    pub gross_total: Decimal,
+    pub breakdown: Vec<TaxBreakdown>,
+}
+
+impl TaxSummary {
+    pub fn from_lines<'a>(lines: impl IntoIterator<Item = &'a LineTax>) -> Self {
+        let mut summary = Self::default();
+        for line in lines {
+            summary.net_total += line.net;
+            summary.tax_total += line.tax;
+            summary.gross_total += line.gross;
+
+            let Some(name) = &line.name else {
+                continue;
+            };
+            match summary.breakdown.iter_mut().find(|entry| {
+                entry.name == *name && entry.rate == line.rate && entry.inclusive == line.inclusive
+            }) {
+                Some(entry) => {
+                    entry.taxable_amount += line.net;
+                    entry.tax += line.tax;
+                }
+                None => summary.breakdown.push(TaxBreakdown {
+                    name: name.clone(),
+                    rate: line.rate,
+                    inclusive: line.inclusive,
+                    taxable_amount: line.net,
+                    tax: line.tax,
+                }),
+            }
+        }
+        summary
+    }
+}
+
+// คำนวณภาษีของตะกร้า/Order ตามอัตราของที่อยู่จัดส่งหนึ่งที่ (ไม่แตะ DB)
+#[derive(Debug, Clone)]
+pub struct TaxCalculator {
+    currency: String,
+    rates: Vec<TaxRate>,
+}
+
+impl TaxCalculator {
+    // rates = อัตราทั้งหมดของประเทศนั้น (ทั้งแบบทั้งประเทศและของ region ที่ตรงกัน)
+    pub fn new(currency: &str, rates: Vec<TaxRate>) -> Self {
+        Self {
+            currency: currency.to_string(),
+            rates,
+        }
+    }
+
+    // อัตราของ region ชนะอัตราของทั้งประเทศ
+    pub fn rate_for(&self, tax_class: &str) -> Option<&TaxRate> {
+        self.rates
+            .iter()
+            .filter(|rate| rate.tax_class == tax_class)
+            .max_by_key(|rate| rate.region.is_some())
+    }
+
+    pub fn line(&self, tax_class: &str, unit_price: Decimal, quantity: i32) -> LineTax {
+        let amount = unit_price * Decimal::from(quantity);
+        match self.rate_for(tax_class) {
+            Some(rate) => LineTax::new(
+                amount,
+                rate.rate,
+                rate.inclusive,
+                Some(rate.name.clone()),
+                &self.currency,
+            ),
+            None => LineTax::new(amount, Decimal::ZERO, false, None, &self.currency),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(value: &str) -> Decimal {
+        value.parse().unwrap()
+    }
+
+    fn vat_th() -> TaxRate {
+        TaxRate {
+            tax_class: "standard".into(),
+            region: None,
+            name: "VAT 7%".into(),
+            rate: d("0.07"),
+            inclusive: true,
+        }
+    }
+
+    fn sales_tax(region: Option<&str>, rate: Decimal) -> TaxRate {
+        TaxRate {
+            tax_class: "standard".into(),
+            region: region.map(str::to_string),
+            name: "Sales tax".into(),
+            rate,
+            inclusive: false,
+        }
+    }
+
+    #[test]
+    fn inclusive_vat_is_extracted_from_price() {
+        let calculator = TaxCalculator::new("THB", vec![vat_th()]);
+
+        let line = calculator.line("standard", d("107"), 1);
+        assert_eq!((line.net, line.tax, line.gross), (d("100.00"), d("7.00"), d("107")));
+
+        // 100 * 7 / 107 = 6.5420... -> 6.54
+        let line = calculator.line("standard", d("100"), 1);
+        assert_eq!((line.net, line.tax, line.gross), (d("93.46"), d("6.54"), d("100")));
+    }
+
+    #[test]
+    fn inclusive_tax_never_changes_what_customer_pays() {
+        let calculator = TaxCalculator::new("THB", vec![vat_th()]);
+
+        for cents in [1, 7, 14, 15, 99, 1999, 123457] {
+            let price = Decimal::new(cents, 2);
+            let line = calculator.line("standard", price, 3);
+            assert_eq!(line.gross, price * d("3"));
+            assert_eq!(line.net + line.tax, line.gross);
+        }
+    }
+
+    #[test]
+    fn exclusive_midpoint_rounds_away_from_zero() {
+        let calculator = TaxCalculator::new("USD", vec![sales_tax(None, d("0.1"))]);
+
+        // 0.05 * 10% = 0.005 -> 0.01
+        let line = calculator.line("standard", d("0.05"), 1);
+        assert_eq!((line.tax, line.gross), (d("0.01"), d("0.06")));
+
+        // 0.04 * 10% = 0.004 -> 0.00
+        let line = calculator.line("standard", d("0.04"), 1);
+        assert_eq!((line.tax, line.gross), (d("0.00"), d("0.04")));
+    }
+
+    #[test]
+    fn tax_is_rounded_per_line_not_per_unit() {
+        let calculator = TaxCalculator::new("USD", vec![sales_tax(None, d("0.0825"))]);
+
+        // ต่อชิ้น 0.0825 -> 0.08 (x3 = 0.24) แต่ทั้งบรรทัด 3.00 * 8.25% = 0.2475 -> 0.25
+        let line = calculator.line("standard", d("1.00"), 3);
+        assert_eq!(line.tax, d("0.25"));
+    }
+
+    #[test]
+    fn summary_is_sum_of_rounded_lines() {
+        let calculator = TaxCalculator::new("THB", vec![vat_th()]);
+        let lines = [
+            calculator.line("standard", d("0.50"), 1),
+            calculator.line("standard", d("0.50"), 1),
+            calculator.line("standard", d("0.50"), 1),
+        ];
+
+        // แต่ละบรรทัด 0.50 * 7 / 107 = 0.0327 -> 0.03 (ไม่ใช่ 1.50 * 7 / 107 = 0.098 -> 0.10)
+        let summary = TaxSummary::from_lines(&lines);
+        assert_eq!(summary.tax_total, d("0.09"));
+        assert_eq!(summary.gross_total, d("1.50"));
+        assert_eq!(summary.net_total + summary.tax_total, summary.gross_total);
+        assert_eq!(summary.breakdown.len(), 1);
+        assert_eq!(summary.breakdown[0].tax, d("0.09"));
+        assert_eq!(summary.breakdown[0].taxable_amount, d("1.41"));
+    }
+
+    #[test]
+    fn zero_decimal_currency_rounds_to_whole_units() {
+        let rate = TaxRate {
+            tax_class: "standard".into(),
+            region: None,
+            name: "Consumption tax".into(),
+            rate: d("0.1"),
+            inclusive: false,
+        };
+        let calculator = TaxCalculator::new("JPY", vec![rate]);
+
+        // 105 * 10% = 10.5 -> 11
+        let line = calculator.line("standard", d("105"), 1);
+        assert_eq!((line.tax, line.gross), (d("11"), d("116")));
+    }
+
+    #[test]
+    fn region_rate_overrides_country_rate() {
+        let calculator = TaxCalculator::new(
+            "USD",
+            vec![sales_tax(None, d("0.05")), sales_tax(Some("CA"), d("0.0725"))],
+        );
+        assert_eq!(calculator.rate_for("standard").unwrap().rate, d("0.0725"));
+
+        let calculator = TaxCalculator::new("USD", vec![sales_tax(None, d("0.05"))]);
+        assert_eq!(calculator.rate_for("standard").unwrap().rate, d("0.05"));
+    }
+
+    #[test]
+    fn unknown_class_is_untaxed() {
+        let calculator = TaxCalculator::new("THB", vec![vat_th()]);
+
+        let line = calculator.line("zero", d("19.99"), 2);
+        assert_eq!(line.name, None);
+        assert_eq!((line.net, line.tax, line.gross), (d("39.98"), d("0"), d("39.98")));
+        assert!(TaxSummary::from_lines(&[line]).breakdown.is_empty());
+    }
+}